@@ -369,13 +369,13 @@ pub static FUNC_RENAME: Lazy<HashMap<&str, &str>> = Lazy::new(|| {
 		"cv_dnn_TextRecognitionModel_TextRecognitionModel_const_stringR_const_stringR" => "from_file",
 		"cv_dnn_blobFromImage_const__InputArrayR_const__OutputArrayR_double_const_SizeR_const_ScalarR_bool_bool_int" => "+_to",
 		"cv_dnn_blobFromImages_const__InputArrayR_const__OutputArrayR_double_Size_const_ScalarR_bool_bool_int" => "+_to",
-		"cv_dnn_readNetFromCaffe_const_charX_size_t_const_charX_size_t" => "+_str",
+		"cv_dnn_readNetFromCaffe_const_charX_size_t_const_charX_size_t" => "+_slice",
 		"cv_dnn_readNetFromCaffe_const_vector_unsigned_char_R_const_vector_unsigned_char_R" => "+_buffer",
-		"cv_dnn_readNetFromDarknet_const_charX_size_t_const_charX_size_t" => "+_str",
+		"cv_dnn_readNetFromDarknet_const_charX_size_t_const_charX_size_t" => "+_slice",
 		"cv_dnn_readNetFromDarknet_const_vector_unsigned_char_R_const_vector_unsigned_char_R" => "+_buffer",
-		"cv_dnn_readNetFromONNX_const_charX_size_t" => "+_str",
+		"cv_dnn_readNetFromONNX_const_charX_size_t" => "+_slice",
 		"cv_dnn_readNetFromONNX_const_vector_unsigned_char_R" => "+_buffer",
-		"cv_dnn_readNetFromTensorflow_const_charX_size_t_const_charX_size_t" => "+_str",
+		"cv_dnn_readNetFromTensorflow_const_charX_size_t_const_charX_size_t" => "+_slice",
 		"cv_dnn_readNetFromTensorflow_const_vector_unsigned_char_R_const_vector_unsigned_char_R" => "+_buffer",
 
 		"cv_dnn_DictValue_DictValue_const_StringR" => "-", // effectively duplicate of cv_dnn_DictValue_DictValue_const_charX
@@ -1058,6 +1058,42 @@ pub static ARGUMENT_OVERRIDE: Lazy<HashMap<FuncId, HashMap<&str, ArgOverride>>>
 		FuncId::new("cv::getOptimalNewCameraMatrix", ["cameraMatrix", "distCoeffs", "imageSize", "alpha", "newImgSize", "validPixROI", "centerPrincipalPoint"]) => hashmap! {
 			"validPixROI" => ArgOverride::Nullable,
 		},
+		// all `const char* buffer, size_t len` and `const uchar* buffer, size_t len` pairs in OpenCV are the in-memory model
+		// buffers of dnn, they are listed explicitly because the generic `char*` is a C string
+		FuncId::new("cv::dnn::Net::readFromModelOptimizer", ["bufferModelConfigPtr", "bufferModelConfigSize", "bufferWeightsPtr", "bufferWeightsSize"]) => hashmap! {
+			"bufferModelConfigPtr" => ArgOverride::Slice,
+			"bufferModelConfigSize" => ArgOverride::LenForSlice("buffer_model_config_ptr", 1),
+			"bufferWeightsPtr" => ArgOverride::Slice,
+			"bufferWeightsSize" => ArgOverride::LenForSlice("buffer_weights_ptr", 1),
+		},
+		FuncId::new("cv::dnn::readNetFromCaffe", ["bufferProto", "lenProto", "bufferModel", "lenModel"]) => hashmap! {
+			"bufferProto" => ArgOverride::Slice,
+			"lenProto" => ArgOverride::LenForSlice("buffer_proto", 1),
+			"bufferModel" => ArgOverride::Slice,
+			"lenModel" => ArgOverride::LenForSlice("buffer_model", 1),
+		},
+		FuncId::new("cv::dnn::readNetFromDarknet", ["bufferCfg", "lenCfg", "bufferModel", "lenModel"]) => hashmap! {
+			"bufferCfg" => ArgOverride::Slice,
+			"lenCfg" => ArgOverride::LenForSlice("buffer_cfg", 1),
+			"bufferModel" => ArgOverride::Slice,
+			"lenModel" => ArgOverride::LenForSlice("buffer_model", 1),
+		},
+		FuncId::new("cv::dnn::readNetFromModelOptimizer", ["bufferModelConfigPtr", "bufferModelConfigSize", "bufferWeightsPtr", "bufferWeightsSize"]) => hashmap! {
+			"bufferModelConfigPtr" => ArgOverride::Slice,
+			"bufferModelConfigSize" => ArgOverride::LenForSlice("buffer_model_config_ptr", 1),
+			"bufferWeightsPtr" => ArgOverride::Slice,
+			"bufferWeightsSize" => ArgOverride::LenForSlice("buffer_weights_ptr", 1),
+		},
+		FuncId::new("cv::dnn::readNetFromONNX", ["buffer", "sizeBuffer"]) => hashmap! {
+			"buffer" => ArgOverride::Slice,
+			"sizeBuffer" => ArgOverride::LenForSlice("buffer", 1),
+		},
+		FuncId::new("cv::dnn::readNetFromTensorflow", ["bufferModel", "lenModel", "bufferConfig", "lenConfig"]) => hashmap! {
+			"bufferModel" => ArgOverride::Slice,
+			"lenModel" => ArgOverride::LenForSlice("buffer_model", 1),
+			"bufferConfig" => ArgOverride::Slice,
+			"lenConfig" => ArgOverride::LenForSlice("buffer_config", 1),
+		},
 		FuncId::new("cv::VideoWriter::fourcc", ["c1", "c2", "c3", "c4"]) => hashmap! {
 			"c1" => ArgOverride::Char8AsChar,
 			"c2" => ArgOverride::Char8AsChar,
@@ -132,6 +132,10 @@ impl<'tu, 'ge> TypeRef<'tu, 'ge> {
 		match self.type_ref.get_kind() {
 			TypeKind::Void => Kind::Primitive("()", "void"),
 			TypeKind::Bool => Kind::Primitive("bool", "bool"),
+			// binary data passed as a slice of bytes, e.g. `const char* buffer, size_t len`
+			TypeKind::CharS if matches!(self.type_hint, TypeRefTypeHint::ArgOverride(ArgOverride::Slice)) => {
+				Kind::Primitive("u8", "char")
+			}
 			TypeKind::CharS => Kind::Primitive("i8", "char"),
 			TypeKind::CharU => Kind::Primitive("u8", "char"),
 			TypeKind::SChar => Kind::Primitive("i8", "signed char"),
@@ -473,7 +477,7 @@ impl<'tu, 'ge> TypeRef<'tu, 'ge> {
 					}
 				}
 			}
-			Kind::Array(inner, ..) => {
+			Kind::Array(inner, ..) if !matches!(self.type_hint, TypeRefTypeHint::ArgOverride(ArgOverride::Slice)) => {
 				let inner_cpp_full = inner.cpp_full();
 				if inner_cpp_full == "char" || inner_cpp_full == "const char" {
 					return Some(Dir::In(StrType::CharPtr));
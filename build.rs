@@ -35,6 +35,17 @@ static OPENCV_BRANCH_34: Lazy<VersionReq> =
 	Lazy::new(|| VersionReq::parse("~3.4").expect("Can't parse OpenCV 3.4 version requirement"));
 static OPENCV_BRANCH_4: Lazy<VersionReq> =
 	Lazy::new(|| VersionReq::parse("~4").expect("Can't parse OpenCV 4 version requirement"));
+/// cfgs for the APIs introduced in the minor releases, each one is emitted when OpenCV is at least of the specified version
+static OPENCV_MIN_VERSIONS: Lazy<Vec<(&str, VersionReq)>> = Lazy::new(|| {
+	[
+		("ocvrs_opencv_4_5_2", ">=4.5.2"),
+		("ocvrs_opencv_4_5_5", ">=4.5.5"),
+		("ocvrs_opencv_4_7_0", ">=4.7.0"),
+	]
+	.iter()
+	.map(|&(cfg, req)| (cfg, VersionReq::parse(req).expect("Can't parse OpenCV version requirement")))
+	.collect()
+});
 
 static ENV_VARS: [&str; 15] = [
 	"OPENCV_PACKAGE_NAME",
@@ -293,10 +304,28 @@ fn build_wrapper(opencv: &Library) {
 	cc.compile("ocvrs");
 }
 
+/// Version of OpenCV that the pregenerated bindings in `src/opencv/hub` were generated for
+fn hub_opencv_version() -> Result<Version> {
+	let core = BufReader::new(File::open(SRC_DIR.join("opencv/hub/core.rs"))?);
+	for line in core.lines() {
+		let line = line?;
+		if let Some(version) = line.strip_prefix("pub const CV_VERSION: &str = \"") {
+			return Ok(Version::parse(version.trim_end_matches("\";"))?);
+		}
+	}
+	Err("Can't find CV_VERSION in the pregenerated bindings".into())
+}
+
 fn main() -> Result<()> {
 	if cfg!(feature = "docs-only") {
 		// fake setup for docs.rs
 		println!(r#"cargo:rustc-cfg=ocvrs_opencv_branch_4"#);
+		let hub_version = hub_opencv_version()?;
+		for (cfg, req) in OPENCV_MIN_VERSIONS.iter() {
+			if req.matches(&hub_version) {
+				println!("cargo:rustc-cfg={}", cfg);
+			}
+		}
 		for entry in SRC_DIR.join("opencv/hub").read_dir().expect("Can't read hub dir") {
 			let entry = entry.expect("Can't read directory entry");
 			let path = entry.path();
@@ -348,6 +377,11 @@ fn main() -> Result<()> {
 			opencv.version
 		);
 	}
+	for (cfg, req) in OPENCV_MIN_VERSIONS.iter() {
+		if req.matches(&opencv.version) {
+			println!("cargo:rustc-cfg={}", cfg);
+		}
+	}
 	let opencv_header_dir = opencv
 		.include_paths
 		.iter()
@@ -13,12 +13,9 @@ fn main() -> Result<()> {
 	if !opened {
 		panic!("Unable to open default camera!");
 	}
-	loop {
-		let mut frame = Mat::default();
-		cam.read(&mut frame)?;
-		if frame.size()?.width > 0 {
-			highgui::imshow(window, &mut frame)?;
-		}
+	for frame in cam.frames() {
+		let mut frame = frame?;
+		highgui::imshow(window, &mut frame.mat)?;
 		let key = highgui::wait_key(10)?;
 		if key > 0 && key != 255 {
 			break;
@@ -13,14 +13,12 @@ fn main() -> Result<()> {
 	if !opened {
 		panic!("Unable to open default camera!");
 	}
-	loop {
-		let mut frame = Mat::default();
-		cam.read(&mut frame)?;
-		if frame.size()?.width > 0 {
-			let mut gray = Mat::default();
-			imgproc::cvt_color(&frame, &mut gray, imgproc::COLOR_BGR2GRAY, 0)?;
-			highgui::imshow(window, &gray)?;
-		}
+	let mut frame = Mat::default();
+	let mut gray = Mat::default();
+	let mut frames = cam.frames_into(&mut frame);
+	while let Some(frame) = frames.next_frame() {
+		imgproc::cvt_color(frame?.mat, &mut gray, imgproc::COLOR_BGR2GRAY, 0)?;
+		highgui::imshow(window, &gray)?;
 		if highgui::wait_key(10)? > 0 {
 			break;
 		}
@@ -1,9 +1,23 @@
-use opencv::{highgui, imgcodecs, Result};
+use std::time::Duration;
+
+use opencv::{
+	highgui::{Window, WindowEvent, WindowFlags},
+	imgcodecs, Result,
+};
 
 fn main() -> Result<()> {
 	let image = imgcodecs::imread("lena.jpg", 0)?;
-	highgui::named_window("hello opencv!", 0)?;
-	highgui::imshow("hello opencv!", &image)?;
-	highgui::wait_key(10000)?;
+	let window = Window::new("hello opencv!", WindowFlags::WINDOW_NORMAL)?;
+	window.show(&image)?;
+	for _ in 0..100 {
+		window.wait(Duration::from_millis(100))?;
+		if window
+			.events()
+			.try_iter()
+			.any(|event| matches!(event, WindowEvent::Key(_) | WindowEvent::Closed))
+		{
+			break;
+		}
+	}
 	Ok(())
 }
@@ -0,0 +1,73 @@
+use std::{ffi::c_void, mem::ManuallyDrop};
+
+use crate::{
+	calib3d::LMSolver_Callback,
+	core::{Ptr, _InputArray, _OutputArray},
+	sys,
+	traits::Boxed,
+	Result,
+};
+
+/// Rust implementation of the `LMSolver::Callback` that computes the error and the Jacobian for the Levenberg-Marquardt solver
+///
+/// Use [Ptr::from_rust](crate::core::Ptr::<dyn LMSolver_Callback>::from_rust) to pass it to `LMSolver::create()`.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/d1/d37/classcv_1_1LMSolver_1_1Callback.html)
+#[allow(non_camel_case_types)]
+pub trait LMSolver_CallbackImpl: Send + Sync {
+	/// Computes the error and the Jacobian for the specified vector of parameters
+	///
+	/// ## Parameters
+	/// * param: the current vector of parameters
+	/// * err: output vector of errors: err_i = actual_f_i - ideal_f_i
+	/// * j: output Jacobian: J_ij = d(err_i)/d(param_j), can be empty when it's not needed (check with `j.needed()`)
+	///
+	/// Return `Ok(false)` when the computation fails to stop the solver.
+	fn compute(&self, param: &_InputArray, err: &mut _OutputArray, j: &mut _OutputArray) -> Result<bool>;
+}
+
+#[repr(C)]
+struct Vtable {
+	drop: unsafe extern "C" fn(*mut c_void),
+	compute: unsafe extern "C" fn(*const c_void, *const c_void, *const c_void, *const c_void, *mut sys::Result<bool>),
+}
+
+static VTABLE: Vtable = Vtable {
+	drop: sys::drop_instance::<dyn LMSolver_CallbackImpl>,
+	compute: vtable_compute,
+};
+
+unsafe extern "C" fn vtable_compute(
+	instance: *const c_void,
+	param: *const c_void,
+	err: *const c_void,
+	j: *const c_void,
+	ocvrs_return: *mut sys::Result<bool>,
+) {
+	let instance = sys::instance_ref::<dyn LMSolver_CallbackImpl>(instance);
+	// arrays are owned by the C++ caller
+	let param = ManuallyDrop::new(_InputArray::from_raw(param as *mut c_void));
+	let mut err = ManuallyDrop::new(_OutputArray::from_raw(err as *mut c_void));
+	let mut j = ManuallyDrop::new(_OutputArray::from_raw(j as *mut c_void));
+	ocvrs_return.write(sys::Result::from_callback(|| instance.compute(&param, &mut err, &mut j)));
+}
+
+impl Ptr<dyn LMSolver_Callback> {
+	/// Wraps the Rust implementation of the callback into the C++ `LMSolver::Callback` object
+	pub fn from_rust(callback: impl LMSolver_CallbackImpl + 'static) -> Result<Self> {
+		extern "C" {
+			fn cv_manual_PtrOfLMSolver_Callback_from_rust(
+				instance: *mut c_void,
+				vtable: *const Vtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		let callback: Box<dyn LMSolver_CallbackImpl> = Box::new(callback);
+		unsafe {
+			sys::into_extern(callback, |instance, ocvrs_return| {
+				cv_manual_PtrOfLMSolver_Callback_from_rust(instance, &VTABLE, ocvrs_return)
+			})
+		}
+		.map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
@@ -1,10 +1,14 @@
 pub use affine3::*;
+#[cfg(feature = "async")]
+pub use blocking::*;
 pub use data_type::*;
 pub use gpumat::*;
 pub use input_output_array::*;
 pub use mat::*;
 pub use mat_ops::*;
 pub use matx::*;
+pub use min_problem_solver::*;
+pub use parallel::*;
 pub use point::*;
 pub use point3::*;
 pub use ptr::*;
@@ -17,12 +21,18 @@ pub use vector::*;
 pub use CV_MAKETYPE as CV_MAKE_TYPE;
 
 mod affine3;
+#[cfg(feature = "async")]
+mod blocking;
+pub(crate) mod checksum;
 mod data_type;
 mod gpumat;
 mod input_output_array;
 mod mat;
 mod mat_ops;
 mod matx;
+mod min_problem_solver;
+pub mod npy;
+mod parallel;
 mod point;
 mod point3;
 pub(crate) mod ptr;
@@ -0,0 +1,233 @@
+use std::{
+	fmt,
+	future::Future,
+	pin::Pin,
+	sync::{Arc, Mutex, MutexGuard, PoisonError},
+	task::{Context, Poll, Waker},
+	thread,
+	time::Duration,
+};
+
+use crate::{
+	core::{self, AsyncArray, Mat},
+	prelude::*,
+	Error, Result,
+};
+
+struct TaskState<T> {
+	result: Option<Result<T>>,
+	waker: Option<Waker>,
+	cancelled: bool,
+}
+
+struct Task<T> {
+	state: Mutex<TaskState<T>>,
+}
+
+impl<T> Task<T> {
+	fn new() -> Arc<Self> {
+		Arc::new(Self {
+			state: Mutex::new(TaskState {
+				result: None,
+				waker: None,
+				cancelled: false,
+			}),
+		})
+	}
+
+	fn lock(&self) -> MutexGuard<'_, TaskState<T>> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	fn is_cancelled(&self) -> bool {
+		self.lock().cancelled
+	}
+
+	fn complete(&self, result: Result<T>) {
+		let mut state = self.lock();
+		if !state.cancelled {
+			state.result = Some(result);
+			if let Some(waker) = state.waker.take() {
+				waker.wake();
+			}
+		}
+	}
+
+	/// Returns the result if it's available, otherwise registers the waker
+	fn poll_result(&self, cx: &Context) -> Poll<Result<T>> {
+		let mut state = self.lock();
+		match state.result.take() {
+			Some(res) => Poll::Ready(res),
+			None => {
+				state.waker = Some(cx.waker().clone());
+				Poll::Pending
+			}
+		}
+	}
+
+	fn cancel(&self) {
+		let mut state = self.lock();
+		state.cancelled = true;
+		state.result = None;
+		state.waker = None;
+	}
+}
+
+fn spawn_thread(name: &str, f: impl FnOnce() + Send + 'static) -> Result<()> {
+	thread::Builder::new()
+		.name(name.to_string())
+		.spawn(f)
+		.map(|_| ())
+		.map_err(|e| Error::new(core::StsError, format!("Can't spawn {} thread: {}", name, e)))
+}
+
+/// Future that runs a blocking OpenCV call on a separate thread, see [spawn_blocking]
+#[must_use = "futures do nothing unless polled"]
+pub struct Blocking<T> {
+	f: Option<Box<dyn FnOnce() -> Result<T> + Send>>,
+	task: Arc<Task<T>>,
+}
+
+impl<T: Send + 'static> Future for Blocking<T> {
+	type Output = Result<T>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		if let Some(f) = self.f.take() {
+			let task = Arc::clone(&self.task);
+			let res = spawn_thread("opencv-blocking", move || {
+				if !task.is_cancelled() {
+					task.complete(f());
+				}
+			});
+			if let Err(e) = res {
+				return Poll::Ready(Err(e));
+			}
+		}
+		self.task.poll_result(cx)
+	}
+}
+
+impl<T> Drop for Blocking<T> {
+	fn drop(&mut self) {
+		self.task.cancel();
+	}
+}
+
+impl<T> fmt::Debug for Blocking<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Blocking").field("started", &self.f.is_none()).finish()
+	}
+}
+
+/// Runs the blocking closure on a separate thread and returns a future resolving to its result
+///
+/// The future is executor-agnostic: it doesn't need a runtime and can be awaited from any of them. Nothing is started until the
+/// future is polled for the first time. Dropping the future cancels the operation: if it hasn't started yet it never runs,
+/// otherwise its result is discarded (OpenCV calls can't be interrupted midway).
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{core, imgcodecs};
+///
+/// # async fn f() -> opencv::Result<()> {
+/// let img = core::spawn_blocking(|| imgcodecs::imread("image.png", imgcodecs::IMREAD_COLOR)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn spawn_blocking<T, F>(f: F) -> Blocking<T>
+where
+	T: Send + 'static,
+	F: FnOnce() -> Result<T> + Send + 'static,
+{
+	Blocking {
+		f: Some(Box::new(f)),
+		task: Task::new(),
+	}
+}
+
+/// Future that resolves to the result of an [AsyncArray], see [AsyncArray::wait_async]
+#[must_use = "futures do nothing unless polled"]
+pub struct AsyncArrayFuture {
+	array: AsyncArray,
+	task: Arc<Task<()>>,
+	waiting: bool,
+}
+
+impl AsyncArrayFuture {
+	/// Interval that the waiting thread uses to check whether the future was dropped
+	const POLL_INTERVAL: Duration = Duration::from_millis(50);
+}
+
+impl Future for AsyncArrayFuture {
+	type Output = Result<Mat>;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		loop {
+			if self.array.wait_for(0)? {
+				let mut out = Mat::default();
+				self.array.get(&mut out)?;
+				return Poll::Ready(Ok(out));
+			}
+			match self.task.poll_result(cx) {
+				Poll::Ready(Ok(())) => continue,
+				Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+				Poll::Pending => break,
+			}
+		}
+		if !self.waiting {
+			self.waiting = true;
+			// the asynchronous state is shared between the copies
+			let array = AsyncArray::copy(&self.array);
+			let task = Arc::clone(&self.task);
+			let timeout_ns = Self::POLL_INTERVAL.as_nanos() as i64;
+			spawn_thread("opencv-async-array", move || loop {
+				if task.is_cancelled() {
+					break;
+				}
+				match array.wait_for(timeout_ns) {
+					Ok(false) => continue,
+					res => {
+						task.complete(res.map(|_| ()));
+						break;
+					}
+				}
+			})?;
+		}
+		Poll::Pending
+	}
+}
+
+impl Drop for AsyncArrayFuture {
+	fn drop(&mut self) {
+		self.task.cancel();
+	}
+}
+
+impl fmt::Debug for AsyncArrayFuture {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("AsyncArrayFuture").field("waiting", &self.waiting).finish()
+	}
+}
+
+impl AsyncArray {
+	/// Converts the asynchronous result into a future, e.g. the one returned by `Net::forward_async()`
+	///
+	/// Waiting is done on a helper thread that is stopped when the future is dropped.
+	///
+	/// ## Example
+	/// ```no_run
+	/// use opencv::{dnn, prelude::*};
+	///
+	/// # async fn f(mut net: dnn::Net) -> opencv::Result<()> {
+	/// let out = net.forward_async("")?.wait_async().await?;
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn wait_async(self) -> AsyncArrayFuture {
+		AsyncArrayFuture {
+			array: self,
+			task: Task::new(),
+			waiting: false,
+		}
+	}
+}
@@ -0,0 +1,30 @@
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+	let mut out = [0; 256];
+	let mut i = 0;
+	while i < 256 {
+		let mut crc = i as u32;
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 1 == 1 {
+				(crc >> 1) ^ 0xEDB8_8320
+			} else {
+				crc >> 1
+			};
+			bit += 1;
+		}
+		out[i] = crc;
+		i += 1;
+	}
+	out
+}
+
+/// CRC-32 (ISO-HDLC) of the concatenated `parts`, as used by PNG and ZIP
+pub(crate) fn crc32(parts: &[&[u8]]) -> u32 {
+	let mut crc = !0u32;
+	for &byte in parts.iter().flat_map(|part| part.iter()) {
+		crc = CRC32_TABLE[((crc ^ u32::from(byte)) & 0xFF) as usize] ^ (crc >> 8);
+	}
+	!crc
+}
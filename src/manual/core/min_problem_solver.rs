@@ -0,0 +1,138 @@
+use std::{ffi::c_void, slice};
+
+use crate::{
+	core::{MinProblemSolver_Function, Ptr},
+	sys,
+	traits::Boxed,
+	types::Unit,
+	Result,
+};
+
+/// Rust implementation of the `MinProblemSolver::Function`, the function to be minimized by `DownhillSolver` and `ConjGradSolver`
+///
+/// Use [Ptr::from_rust](crate::core::Ptr::<dyn MinProblemSolver_Function>::from_rust) to pass it to the solver.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/d5/d46/classcv_1_1MinProblemSolver_1_1Function.html)
+#[allow(non_camel_case_types)]
+pub trait MinProblemSolver_FunctionImpl: Send + Sync {
+	/// Number of dimensions of the function argument
+	fn get_dims(&self) -> i32;
+
+	/// Step used for the numerical calculation of the gradient in the default `get_gradient()` implementation
+	fn get_gradient_eps(&self) -> f64 {
+		1e-3
+	}
+
+	/// Calculates the function value at point `x`, `x.len()` is always equal to `get_dims()`
+	fn calc(&self, x: &[f64]) -> Result<f64>;
+
+	/// Calculates the function gradient at point `x`, used by `ConjGradSolver`
+	///
+	/// The default implementation uses central differences like the C++ version does.
+	fn get_gradient(&mut self, x: &[f64], grad: &mut [f64]) -> Result<()> {
+		let eps = self.get_gradient_eps();
+		let mut x = x.to_vec();
+		for (i, grad) in grad.iter_mut().enumerate() {
+			let orig = x[i];
+			x[i] = orig + eps;
+			let f_plus = self.calc(&x)?;
+			x[i] = orig - eps;
+			let f_minus = self.calc(&x)?;
+			x[i] = orig;
+			*grad = (f_plus - f_minus) / (2. * eps);
+		}
+		Ok(())
+	}
+}
+
+#[repr(C)]
+struct Vtable {
+	drop: unsafe extern "C" fn(*mut c_void),
+	get_dims: unsafe extern "C" fn(*const c_void, *mut sys::Result<i32>),
+	get_gradient_eps: unsafe extern "C" fn(*const c_void, *mut sys::Result<f64>),
+	calc: unsafe extern "C" fn(*const c_void, *const f64, i32, *mut sys::Result<f64>),
+	get_gradient: unsafe extern "C" fn(*mut c_void, *const f64, *mut f64, i32, *mut sys::Result_void),
+}
+
+static VTABLE: Vtable = Vtable {
+	drop: sys::drop_instance::<dyn MinProblemSolver_FunctionImpl>,
+	get_dims: vtable_get_dims,
+	get_gradient_eps: vtable_get_gradient_eps,
+	calc: vtable_calc,
+	get_gradient: vtable_get_gradient,
+};
+
+unsafe extern "C" fn vtable_get_dims(instance: *const c_void, ocvrs_return: *mut sys::Result<i32>) {
+	let instance = sys::instance_ref::<dyn MinProblemSolver_FunctionImpl>(instance);
+	ocvrs_return.write(sys::Result::from_callback(|| Ok(instance.get_dims())));
+}
+
+unsafe extern "C" fn vtable_get_gradient_eps(instance: *const c_void, ocvrs_return: *mut sys::Result<f64>) {
+	let instance = sys::instance_ref::<dyn MinProblemSolver_FunctionImpl>(instance);
+	ocvrs_return.write(sys::Result::from_callback(|| Ok(instance.get_gradient_eps())));
+}
+
+unsafe extern "C" fn vtable_calc(instance: *const c_void, x: *const f64, dims: i32, ocvrs_return: *mut sys::Result<f64>) {
+	let instance = sys::instance_ref::<dyn MinProblemSolver_FunctionImpl>(instance);
+	let x = slice::from_raw_parts(x, dims as usize);
+	ocvrs_return.write(sys::Result::from_callback(|| instance.calc(x)));
+}
+
+unsafe extern "C" fn vtable_get_gradient(
+	instance: *mut c_void,
+	x: *const f64,
+	grad: *mut f64,
+	dims: i32,
+	ocvrs_return: *mut sys::Result_void,
+) {
+	let instance = sys::instance_mut::<dyn MinProblemSolver_FunctionImpl>(instance);
+	let x = slice::from_raw_parts(x, dims as usize);
+	let grad = slice::from_raw_parts_mut(grad, dims as usize);
+	ocvrs_return.write(sys::Result::<Unit, ()>::from_callback(|| instance.get_gradient(x, grad)));
+}
+
+impl Ptr<dyn MinProblemSolver_Function> {
+	/// Wraps the Rust implementation of the function into the C++ `MinProblemSolver::Function` object
+	///
+	/// ## Example
+	/// ```no_run
+	/// use opencv::{core::{self, MinProblemSolver_FunctionImpl}, prelude::*, Result};
+	///
+	/// struct Paraboloid;
+	///
+	/// impl MinProblemSolver_FunctionImpl for Paraboloid {
+	///     fn get_dims(&self) -> i32 {
+	///         2
+	///     }
+	///
+	///     fn calc(&self, x: &[f64]) -> Result<f64> {
+	///         Ok((x[0] - 1.).powi(2) + (x[1] + 2.).powi(2))
+	///     }
+	/// }
+	///
+	/// let mut solver = <dyn core::DownhillSolver>::create(
+	///     &core::Ptr::<dyn core::MinProblemSolver_Function>::from_rust(Paraboloid)?,
+	///     &Mat::from_slice(&[1., 1.])?,
+	///     core::TermCriteria::default()?,
+	/// )?;
+	/// let mut x = Mat::from_slice(&[0., 0.])?;
+	/// solver.minimize(&mut x)?;
+	/// # Ok::<_, opencv::Error>(())
+	/// ```
+	pub fn from_rust(function: impl MinProblemSolver_FunctionImpl + 'static) -> Result<Self> {
+		extern "C" {
+			fn cv_manual_PtrOfMinProblemSolver_Function_from_rust(
+				instance: *mut c_void,
+				vtable: *const Vtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		let function: Box<dyn MinProblemSolver_FunctionImpl> = Box::new(function);
+		unsafe {
+			sys::into_extern(function, |instance, ocvrs_return| {
+				cv_manual_PtrOfMinProblemSolver_Function_from_rust(instance, &VTABLE, ocvrs_return)
+			})
+		}
+		.map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
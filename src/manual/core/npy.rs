@@ -0,0 +1,682 @@
+//! Reading and writing of the NumPy `.npy` arrays and `.npz` archives
+//!
+//! Arrays are read into single-channel [Mat]s with the same shape as the NumPy array. Because [Mat] has at least 2 dimensions, a
+//! 0-d array becomes a 1x1 Mat and a 1-d array of length `n` becomes a 1xn Mat, like the one created by `Mat::from_slice()`.
+//! When writing, the channels of a multi-channel Mat are stored as the last axis of the array.
+//!
+//! Supported element types are `bool`, `uint8`, `int8`, `uint16`, `int16`, `int32`, `float16`, `float32` and `float64`, in both
+//! byte orders. Structured arrays, object arrays and the 64-bit integer types are not supported.
+//!
+//! ## Example
+//! ```no_run
+//! use opencv::core::{npy, Mat};
+//! use opencv::prelude::*;
+//!
+//! let mat = Mat::from_slice_2d(&[[1f32, 2., 3.], [4., 5., 6.]])?;
+//! npy::write("mat.npy", &mat)?;
+//! let read = npy::read("mat.npy")?;
+//! assert_eq!(mat.data_bytes()?, read.data_bytes()?);
+//! # Ok::<_, opencv::Error>(())
+//! ```
+
+use std::{borrow::Cow, convert::TryFrom, fs, path::Path};
+
+use miniz_oxide::{deflate::compress_to_vec, inflate::decompress_to_vec_with_limit};
+
+use crate::{
+	core::{self, Mat, Scalar, CV_MAKETYPE},
+	manual::core::checksum::crc32,
+	prelude::*,
+	Error, Result,
+};
+
+const MAGIC: &[u8] = b"\x93NUMPY";
+const HEADER_ALIGN: usize = 64;
+
+const ZIP_LOCAL_HEADER: u32 = 0x0403_4B50;
+const ZIP_CENTRAL_HEADER: u32 = 0x0201_4B50;
+const ZIP_END_OF_CENTRAL_DIR: u32 = 0x0605_4B50;
+const ZIP64_END_OF_CENTRAL_DIR: u32 = 0x0606_4B50;
+const ZIP64_END_OF_CENTRAL_DIR_LOCATOR: u32 = 0x0706_4B50;
+const ZIP64_EXTRA: u16 = 0x0001;
+const ZIP_METHOD_STORED: u16 = 0;
+const ZIP_METHOD_DEFLATE: u16 = 8;
+const ZIP_FLAG_ENCRYPTED: u16 = 0x0001;
+const ZIP_FLAG_UTF8: u16 = 0x0800;
+/// 1980-01-01 in the MS-DOS date format
+const ZIP_DATE: u16 = 0x0021;
+const ZIP_VERSION: u16 = 20;
+
+/// Memory layout of the array elements
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Order {
+	/// Row-major, the last axis changes fastest, the layout of [Mat]
+	C,
+	/// Column-major, the first axis changes fastest
+	Fortran,
+}
+
+impl Default for Order {
+	fn default() -> Self {
+		Self::C
+	}
+}
+
+/// Compression of the arrays in the `.npz` archive
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NpzCompression {
+	/// The arrays are stored as is, like `numpy.savez()`
+	Stored,
+	/// The arrays are compressed with deflate, like `numpy.savez_compressed()`
+	Deflate,
+}
+
+impl Default for NpzCompression {
+	fn default() -> Self {
+		Self::Stored
+	}
+}
+
+fn parse_error(msg: impl Into<String>) -> Error {
+	Error::new(core::StsParseError, msg.into())
+}
+
+/// Size of the single element of the `depth` in bytes
+fn depth_size(depth: i32) -> Result<usize> {
+	match depth {
+		core::CV_8U | core::CV_8S => Ok(1),
+		core::CV_16U | core::CV_16S => Ok(2),
+		#[cfg(not(ocvrs_opencv_branch_32))]
+		core::CV_16F => Ok(2),
+		core::CV_32S | core::CV_32F => Ok(4),
+		core::CV_64F => Ok(8),
+		_ => Err(Error::new(
+			core::StsUnsupportedFormat,
+			format!("Unsupported Mat depth: {}", depth),
+		)),
+	}
+}
+
+/// Returns the Mat depth of the NumPy type descriptor like `<f4` and whether the elements need byte swapping
+fn depth_from_descr(descr: &str) -> Result<(i32, bool)> {
+	let unsupported = || Error::new(core::StsUnsupportedFormat, format!("Unsupported NumPy type: {}", descr));
+	let (swap, kind) = match descr.as_bytes().first() {
+		Some(b'<') => (cfg!(target_endian = "big"), &descr[1..]),
+		Some(b'>') => (cfg!(target_endian = "little"), &descr[1..]),
+		Some(b'|') | Some(b'=') => (false, &descr[1..]),
+		_ => (false, descr),
+	};
+	let depth = match kind {
+		"b1" | "u1" => core::CV_8U,
+		"i1" => core::CV_8S,
+		"u2" => core::CV_16U,
+		"i2" => core::CV_16S,
+		#[cfg(not(ocvrs_opencv_branch_32))]
+		"f2" => core::CV_16F,
+		"i4" => core::CV_32S,
+		"f4" => core::CV_32F,
+		"f8" => core::CV_64F,
+		_ => return Err(unsupported()),
+	};
+	Ok((depth, swap))
+}
+
+/// Returns the NumPy type descriptor for the Mat depth in the native byte order
+fn descr_from_depth(depth: i32) -> Result<String> {
+	let kind = match depth {
+		core::CV_8U => "u1",
+		core::CV_8S => "i1",
+		core::CV_16U => "u2",
+		core::CV_16S => "i2",
+		#[cfg(not(ocvrs_opencv_branch_32))]
+		core::CV_16F => "f2",
+		core::CV_32S => "i4",
+		core::CV_32F => "f4",
+		core::CV_64F => "f8",
+		_ => {
+			return Err(Error::new(
+				core::StsUnsupportedFormat,
+				format!("Mat depth: {} can't be stored in .npy", depth),
+			))
+		}
+	};
+	let endian = if depth_size(depth)? == 1 {
+		'|'
+	} else if cfg!(target_endian = "little") {
+		'<'
+	} else {
+		'>'
+	};
+	Ok(format!("{}{}", endian, kind))
+}
+
+/// Parsed `.npy` header
+struct Header {
+	depth: i32,
+	swap: bool,
+	order: Order,
+	shape: Vec<usize>,
+}
+
+enum HeaderValue {
+	Str(String),
+	Bool(bool),
+	Shape(Vec<usize>),
+}
+
+/// Parser of the Python dict literal in the `.npy` header
+struct HeaderParser<'h> {
+	rest: &'h str,
+}
+
+impl<'h> HeaderParser<'h> {
+	fn eat(&mut self, c: char) -> bool {
+		self.rest = self.rest.trim_start();
+		if let Some(rest) = self.rest.strip_prefix(c) {
+			self.rest = rest;
+			true
+		} else {
+			false
+		}
+	}
+
+	fn expect(&mut self, c: char) -> Result<()> {
+		if self.eat(c) {
+			Ok(())
+		} else {
+			Err(parse_error(format!("Expected '{}' in .npy header at: {}", c, self.rest)))
+		}
+	}
+
+	fn string(&mut self) -> Result<String> {
+		self.rest = self.rest.trim_start();
+		let quote = self
+			.rest
+			.chars()
+			.next()
+			.filter(|&c| c == '\'' || c == '"')
+			.ok_or_else(|| parse_error(format!("Expected string in .npy header at: {}", self.rest)))?;
+		let end = self.rest[1..]
+			.find(quote)
+			.ok_or_else(|| parse_error("Unterminated string in .npy header"))?;
+		let out = self.rest[1..=end].to_string();
+		self.rest = &self.rest[end + 2..];
+		Ok(out)
+	}
+
+	fn shape(&mut self) -> Result<Vec<usize>> {
+		self.expect('(')?;
+		let mut out = vec![];
+		while !self.eat(')') {
+			self.rest = self.rest.trim_start();
+			let len = self.rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(self.rest.len());
+			let dim = self.rest[..len]
+				.parse()
+				.map_err(|_| parse_error(format!("Invalid dimension in .npy header at: {}", self.rest)))?;
+			out.push(dim);
+			self.rest = &self.rest[len..];
+			// Python 2 long suffix
+			self.eat('L');
+			if !self.eat(',') {
+				self.expect(')')?;
+				break;
+			}
+		}
+		Ok(out)
+	}
+
+	fn value(&mut self) -> Result<HeaderValue> {
+		self.rest = self.rest.trim_start();
+		let first = self.rest.chars().next();
+		match first {
+			Some('\'') | Some('"') => self.string().map(HeaderValue::Str),
+			Some('(') => self.shape().map(HeaderValue::Shape),
+			Some('[') => Err(Error::new(
+				core::StsUnsupportedFormat,
+				"Structured NumPy arrays are not supported",
+			)),
+			_ => {
+				for (literal, value) in [("True", true), ("False", false)] {
+					if let Some(rest) = self.rest.strip_prefix(literal) {
+						self.rest = rest;
+						return Ok(HeaderValue::Bool(value));
+					}
+				}
+				Err(parse_error(format!("Unexpected value in .npy header at: {}", self.rest)))
+			}
+		}
+	}
+
+	fn parse(mut self) -> Result<Header> {
+		let mut descr = None;
+		let mut order = None;
+		let mut shape = None;
+		self.expect('{')?;
+		while !self.eat('}') {
+			let key = self.string()?;
+			self.expect(':')?;
+			match (key.as_str(), self.value()?) {
+				("descr", HeaderValue::Str(val)) => descr = Some(val),
+				("fortran_order", HeaderValue::Bool(val)) => {
+					order = Some(if val {
+						Order::Fortran
+					} else {
+						Order::C
+					})
+				}
+				("shape", HeaderValue::Shape(val)) => shape = Some(val),
+				(key, _) => return Err(parse_error(format!("Unexpected key or value type in .npy header: {}", key))),
+			}
+			if !self.eat(',') {
+				self.expect('}')?;
+				break;
+			}
+		}
+		let (depth, swap) = depth_from_descr(&descr.ok_or_else(|| parse_error("Missing descr in .npy header"))?)?;
+		Ok(Header {
+			depth,
+			swap,
+			order: order.ok_or_else(|| parse_error("Missing fortran_order in .npy header"))?,
+			shape: shape.ok_or_else(|| parse_error("Missing shape in .npy header"))?,
+		})
+	}
+}
+
+/// Parses the `.npy` header, returns it together with the offset of the array data
+fn read_header(data: &[u8]) -> Result<(Header, usize)> {
+	if !data.starts_with(MAGIC) || data.len() < 10 {
+		return Err(parse_error("Not a .npy file"));
+	}
+	let (len, offset) = match data[6] {
+		1 => (usize::from(u16::from_le_bytes([data[8], data[9]])), 10),
+		2 | 3 => {
+			let len = data.get(8..12).ok_or_else(|| parse_error("Truncated .npy header"))?;
+			let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]);
+			(usize::try_from(len).unwrap_or(usize::MAX), 12)
+		}
+		major => {
+			return Err(Error::new(
+				core::StsUnsupportedFormat,
+				format!("Unsupported .npy version: {}.{}", major, data[7]),
+			))
+		}
+	};
+	let text = data
+		.get(offset..)
+		.and_then(|rest| rest.get(..len))
+		.ok_or_else(|| parse_error("Truncated .npy header"))?;
+	let text = std::str::from_utf8(text).map_err(|_| parse_error("Invalid .npy header encoding"))?;
+	Ok((HeaderParser { rest: text }.parse()?, offset + len))
+}
+
+fn write_header(out: &mut Vec<u8>, descr: &str, order: Order, shape: &[usize]) {
+	let shape = match shape {
+		[dim] => format!("({},)", dim),
+		shape => format!("({})", shape.iter().map(|dim| dim.to_string()).collect::<Vec<_>>().join(", ")),
+	};
+	let mut dict = format!(
+		"{{'descr': '{}', 'fortran_order': {}, 'shape': {}, }}",
+		descr,
+		if order == Order::Fortran {
+			"True"
+		} else {
+			"False"
+		},
+		shape
+	);
+	// magic, version and the header length, followed by the dict padded with spaces and terminated by a newline
+	let mut prefix_len = MAGIC.len() + 2 + 2;
+	let padded_len = |prefix_len: usize| {
+		let len = prefix_len + dict.len() + 1;
+		len + (HEADER_ALIGN - len % HEADER_ALIGN) % HEADER_ALIGN
+	};
+	let mut total = padded_len(prefix_len);
+	let version = if total - prefix_len > usize::from(u16::MAX) {
+		prefix_len += 2;
+		total = padded_len(prefix_len);
+		2
+	} else {
+		1
+	};
+	let header_len = total - prefix_len;
+	dict.push_str(&" ".repeat(header_len - dict.len() - 1));
+	dict.push('\n');
+	out.extend_from_slice(MAGIC);
+	out.extend_from_slice(&[version, 0]);
+	if version == 1 {
+		out.extend_from_slice(&(header_len as u16).to_le_bytes());
+	} else {
+		out.extend_from_slice(&(header_len as u32).to_le_bytes());
+	}
+	out.extend_from_slice(dict.as_bytes());
+}
+
+/// Reverses the order of the axes of the C-ordered array with the `shape`, i.e. converts the array between C and Fortran order
+fn reverse_axes(data: &[u8], shape: &[usize], elem: usize) -> Vec<u8> {
+	if shape.len() < 2 || data.is_empty() {
+		return data.to_vec();
+	}
+	let mut strides = vec![1; shape.len()];
+	for i in (0..shape.len() - 1).rev() {
+		strides[i] = strides[i + 1] * shape[i + 1];
+	}
+	// walk the destination in C order, axis `i` of the destination is axis `n - 1 - i` of the source
+	let dims = shape.iter().rev().copied().collect::<Vec<_>>();
+	strides.reverse();
+	let mut index = vec![0; dims.len()];
+	let mut offset = 0;
+	let mut out = Vec::with_capacity(data.len());
+	for _ in 0..data.len() / elem {
+		out.extend_from_slice(&data[offset * elem..(offset + 1) * elem]);
+		for axis in (0..dims.len()).rev() {
+			index[axis] += 1;
+			offset += strides[axis];
+			if index[axis] < dims[axis] {
+				break;
+			}
+			offset -= strides[axis] * dims[axis];
+			index[axis] = 0;
+		}
+	}
+	out
+}
+
+/// Reads the `.npy` array from memory
+pub fn read_bytes(data: &[u8]) -> Result<Mat> {
+	let (header, offset) = read_header(data)?;
+	let elem = depth_size(header.depth)?;
+	let count = header
+		.shape
+		.iter()
+		.try_fold(1usize, |acc, &dim| acc.checked_mul(dim))
+		.ok_or_else(|| Error::new(core::StsOutOfRange, "NumPy array is too large"))?;
+	let payload = count
+		.checked_mul(elem)
+		.and_then(|len| data.get(offset..)?.get(..len))
+		.ok_or_else(|| parse_error("Truncated .npy data"))?;
+	let mut payload = if header.order == Order::Fortran {
+		let reversed = header.shape.iter().rev().copied().collect::<Vec<_>>();
+		Cow::Owned(reverse_axes(payload, &reversed, elem))
+	} else {
+		Cow::Borrowed(payload)
+	};
+	if header.swap && elem > 1 {
+		payload.to_mut().chunks_exact_mut(elem).for_each(|chunk| chunk.reverse());
+	}
+	let dims = match header.shape.as_slice() {
+		[] => vec![1, 1],
+		&[len] => vec![1, len],
+		shape => shape.to_vec(),
+	};
+	let dims = dims
+		.into_iter()
+		.map(|dim| {
+			i32::try_from(dim).map_err(|_| Error::new(core::StsOutOfRange, format!("NumPy array dimension is too large: {}", dim)))
+		})
+		.collect::<Result<Vec<_>>>()?;
+	let mut out = Mat::new_nd_with_default(&dims, CV_MAKETYPE(header.depth, 1), Scalar::all(0.))?;
+	if count > 0 {
+		out.data_bytes_mut()?.copy_from_slice(&payload);
+	}
+	Ok(out)
+}
+
+/// Reads the `.npy` file
+pub fn read(path: impl AsRef<Path>) -> Result<Mat> {
+	read_bytes(&read_file(path.as_ref())?)
+}
+
+/// Serializes the `mat` into the `.npy` format with the elements in the specified `order`
+pub fn to_bytes(mat: &Mat, order: Order) -> Result<Vec<u8>> {
+	let descr = descr_from_depth(mat.depth())?;
+	let elem = depth_size(mat.depth())?;
+	let (shape, data) = if mat.empty() {
+		(vec![0], Cow::Borrowed(&[][..]))
+	} else {
+		let mut shape = mat.mat_size().iter().map(|&dim| dim as usize).collect::<Vec<_>>();
+		if mat.channels() > 1 {
+			shape.push(mat.channels() as usize);
+		}
+		let data = if mat.is_continuous() {
+			Cow::Borrowed(mat.data_bytes()?)
+		} else {
+			Cow::Owned(mat.try_clone()?.data_bytes()?.to_vec())
+		};
+		let data = if order == Order::Fortran {
+			Cow::Owned(reverse_axes(&data, &shape, elem))
+		} else {
+			data
+		};
+		(shape, data)
+	};
+	let mut out = Vec::with_capacity(HEADER_ALIGN + data.len());
+	write_header(&mut out, &descr, order, &shape);
+	out.extend_from_slice(&data);
+	Ok(out)
+}
+
+/// Writes the `mat` into the `.npy` file in C order
+pub fn write(path: impl AsRef<Path>, mat: &Mat) -> Result<()> {
+	write_with_order(path, mat, Order::C)
+}
+
+/// Writes the `mat` into the `.npy` file with the elements in the specified `order`
+pub fn write_with_order(path: impl AsRef<Path>, mat: &Mat, order: Order) -> Result<()> {
+	write_file(path.as_ref(), &to_bytes(mat, order)?)
+}
+
+/// `pos + len` for the offsets in the `.npz` archive, they come from the untrusted data and can overflow
+fn advance(pos: usize, len: usize) -> Result<usize> {
+	pos.checked_add(len).ok_or_else(|| parse_error("Truncated .npz archive"))
+}
+
+fn read_u16(data: &[u8], pos: usize) -> Result<u16> {
+	data
+		.get(pos..advance(pos, 2)?)
+		.map(|b| u16::from_le_bytes([b[0], b[1]]))
+		.ok_or_else(|| parse_error("Truncated .npz archive"))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Result<u32> {
+	data
+		.get(pos..advance(pos, 4)?)
+		.map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+		.ok_or_else(|| parse_error("Truncated .npz archive"))
+}
+
+fn read_u64(data: &[u8], pos: usize) -> Result<u64> {
+	Ok(u64::from(read_u32(data, pos)?) | u64::from(read_u32(data, advance(pos, 4)?)?) << 32)
+}
+
+fn to_usize(val: u64) -> Result<usize> {
+	usize::try_from(val).map_err(|_| Error::new(core::StsOutOfRange, "The .npz archive is too large"))
+}
+
+/// Returns the number of entries and the offset of the central directory, zip64 archives are supported
+fn read_end_of_central_dir(data: &[u8]) -> Result<(u64, u64)> {
+	// the record is 22 bytes followed by a comment of up to 65535 bytes
+	let last = data.len().checked_sub(22).ok_or_else(|| parse_error("Not a .npz archive"))?;
+	let first = last.saturating_sub(usize::from(u16::MAX));
+	let eocd = (first..=last)
+		.rev()
+		.find(|&pos| read_u32(data, pos).ok() == Some(ZIP_END_OF_CENTRAL_DIR))
+		.ok_or_else(|| parse_error("Not a .npz archive"))?;
+	let entries = read_u16(data, eocd + 10)?;
+	let offset = read_u32(data, eocd + 16)?;
+	if entries != u16::MAX && offset != u32::MAX {
+		return Ok((u64::from(entries), u64::from(offset)));
+	}
+	let locator = eocd
+		.checked_sub(20)
+		.filter(|&pos| read_u32(data, pos).ok() == Some(ZIP64_END_OF_CENTRAL_DIR_LOCATOR))
+		.ok_or_else(|| parse_error("Missing zip64 end of central directory locator"))?;
+	let eocd64 = to_usize(read_u64(data, locator + 8)?)?;
+	if read_u32(data, eocd64)? != ZIP64_END_OF_CENTRAL_DIR {
+		return Err(parse_error("Invalid zip64 end of central directory"));
+	}
+	Ok((read_u64(data, advance(eocd64, 32)?)?, read_u64(data, advance(eocd64, 48)?)?))
+}
+
+/// Reads the `.npz` archive from memory, returns the arrays in the archive order with their names without the `.npy` extension
+pub fn read_npz_bytes(data: &[u8]) -> Result<Vec<(String, Mat)>> {
+	let (entries, offset) = read_end_of_central_dir(data)?;
+	let mut pos = to_usize(offset)?;
+	let mut out = Vec::with_capacity(to_usize(entries)?.min(1024));
+	for _ in 0..entries {
+		if read_u32(data, pos)? != ZIP_CENTRAL_HEADER {
+			return Err(parse_error("Invalid .npz central directory"));
+		}
+		// the central header is 46 bytes, the check makes the fixed offsets below safe
+		let name_start = advance(pos, 46)?;
+		let flags = read_u16(data, pos + 8)?;
+		let method = read_u16(data, pos + 10)?;
+		let crc = read_u32(data, pos + 16)?;
+		let mut compressed_size = u64::from(read_u32(data, pos + 20)?);
+		let mut size = u64::from(read_u32(data, pos + 24)?);
+		let name_len = usize::from(read_u16(data, pos + 28)?);
+		let extra_len = usize::from(read_u16(data, pos + 30)?);
+		let comment_len = usize::from(read_u16(data, pos + 32)?);
+		let mut local_offset = u64::from(read_u32(data, pos + 42)?);
+		let name = data
+			.get(name_start..advance(name_start, name_len)?)
+			.ok_or_else(|| parse_error("Truncated .npz archive"))?;
+		let name = String::from_utf8_lossy(name);
+		// zip64 extended information, present only for the fields that don't fit into 32 bits
+		let mut extra_pos = name_start + name_len;
+		let extra_end = advance(extra_pos, extra_len)?;
+		while advance(extra_pos, 4)? <= extra_end {
+			let id = read_u16(data, extra_pos)?;
+			let len = usize::from(read_u16(data, extra_pos + 2)?);
+			if id == ZIP64_EXTRA {
+				let mut field = extra_pos + 4;
+				for val in [&mut size, &mut compressed_size, &mut local_offset] {
+					if *val == u64::from(u32::MAX) {
+						*val = read_u64(data, field)?;
+						field = advance(field, 8)?;
+					}
+				}
+			}
+			extra_pos = advance(extra_pos, 4 + len)?;
+		}
+		if flags & ZIP_FLAG_ENCRYPTED != 0 {
+			return Err(Error::new(
+				core::StsUnsupportedFormat,
+				format!("Encrypted .npz entry: {}", name),
+			));
+		}
+		let local = to_usize(local_offset)?;
+		if read_u32(data, local)? != ZIP_LOCAL_HEADER {
+			return Err(parse_error(format!("Invalid .npz local header for: {}", name)));
+		}
+		// the local header is 30 bytes, the check makes the fixed offsets below safe
+		let data_start = advance(local, 30)?;
+		let local_name_len = usize::from(read_u16(data, local + 26)?);
+		let local_extra_len = usize::from(read_u16(data, local + 28)?);
+		let data_start = advance(advance(data_start, local_name_len)?, local_extra_len)?;
+		let compressed = data
+			.get(data_start..)
+			.and_then(|rest| rest.get(..usize::try_from(compressed_size).ok()?))
+			.ok_or_else(|| parse_error(format!("Truncated .npz entry: {}", name)))?;
+		let size = to_usize(size)?;
+		let npy = match method {
+			ZIP_METHOD_STORED => Cow::Borrowed(compressed),
+			ZIP_METHOD_DEFLATE => Cow::Owned(
+				decompress_to_vec_with_limit(compressed, size)
+					.map_err(|_| parse_error(format!("Can't inflate .npz entry: {}", name)))?,
+			),
+			_ => {
+				return Err(Error::new(
+					core::StsUnsupportedFormat,
+					format!("Unsupported compression method: {} of .npz entry: {}", method, name),
+				))
+			}
+		};
+		if npy.len() != size || crc32(&[&npy]) != crc {
+			return Err(parse_error(format!("Corrupted .npz entry: {}", name)));
+		}
+		let name = name.strip_suffix(".npy").unwrap_or(&name).to_string();
+		let mat = read_bytes(&npy)?;
+		out.push((name, mat));
+		pos = advance(extra_end, comment_len)?;
+	}
+	Ok(out)
+}
+
+/// Reads the `.npz` archive, see [read_npz_bytes]
+pub fn read_npz(path: impl AsRef<Path>) -> Result<Vec<(String, Mat)>> {
+	read_npz_bytes(&read_file(path.as_ref())?)
+}
+
+/// Serializes the named arrays into the `.npz` archive, the arrays are stored in C order
+pub fn npz_to_bytes(arrays: &[(&str, &Mat)], compression: NpzCompression) -> Result<Vec<u8>> {
+	fn too_large() -> Error {
+		Error::new(core::StsOutOfRange, "The array is too large for .npz archive")
+	}
+
+	let mut out = vec![];
+	let mut central = vec![];
+	for &(name, mat) in arrays {
+		let npy = to_bytes(mat, Order::C)?;
+		let name = format!("{}.npy", name);
+		let (method, payload) = match compression {
+			NpzCompression::Stored => (ZIP_METHOD_STORED, Cow::Borrowed(npy.as_slice())),
+			NpzCompression::Deflate => (ZIP_METHOD_DEFLATE, Cow::Owned(compress_to_vec(&npy, 6))),
+		};
+		let offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+		let size = u32::try_from(npy.len()).map_err(|_| too_large())?;
+		let compressed_size = u32::try_from(payload.len()).map_err(|_| too_large())?;
+		let name_len = u16::try_from(name.len()).map_err(|_| too_large())?;
+		let crc = crc32(&[&npy]);
+		// fields shared by the local and the central header starting from the version needed to extract
+		let mut common = vec![];
+		for val in [ZIP_VERSION, ZIP_FLAG_UTF8, method, 0, ZIP_DATE] {
+			common.extend_from_slice(&val.to_le_bytes());
+		}
+		for val in [crc, compressed_size, size] {
+			common.extend_from_slice(&val.to_le_bytes());
+		}
+		common.extend_from_slice(&name_len.to_le_bytes());
+		// extra field length
+		common.extend_from_slice(&0u16.to_le_bytes());
+
+		out.extend_from_slice(&ZIP_LOCAL_HEADER.to_le_bytes());
+		out.extend_from_slice(&common);
+		out.extend_from_slice(name.as_bytes());
+		out.extend_from_slice(&payload);
+
+		central.extend_from_slice(&ZIP_CENTRAL_HEADER.to_le_bytes());
+		central.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+		central.extend_from_slice(&common);
+		// comment length, disk number, internal and external attributes
+		central.extend_from_slice(&[0; 10]);
+		central.extend_from_slice(&offset.to_le_bytes());
+		central.extend_from_slice(name.as_bytes());
+	}
+	let entries = u16::try_from(arrays.len()).map_err(|_| Error::new(core::StsOutOfRange, "Too many arrays for .npz archive"))?;
+	let central_offset = u32::try_from(out.len()).map_err(|_| too_large())?;
+	let central_size = u32::try_from(central.len()).map_err(|_| too_large())?;
+	out.extend_from_slice(&central);
+	out.extend_from_slice(&ZIP_END_OF_CENTRAL_DIR.to_le_bytes());
+	// disk numbers
+	out.extend_from_slice(&[0; 4]);
+	out.extend_from_slice(&entries.to_le_bytes());
+	out.extend_from_slice(&entries.to_le_bytes());
+	out.extend_from_slice(&central_size.to_le_bytes());
+	out.extend_from_slice(&central_offset.to_le_bytes());
+	// comment length
+	out.extend_from_slice(&0u16.to_le_bytes());
+	Ok(out)
+}
+
+/// Writes the named arrays into the `.npz` archive, the names are stored with the `.npy` extension like `numpy.savez()` does
+pub fn write_npz(path: impl AsRef<Path>, arrays: &[(&str, &Mat)], compression: NpzCompression) -> Result<()> {
+	write_file(path.as_ref(), &npz_to_bytes(arrays, compression)?)
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>> {
+	fs::read(path).map_err(|e| Error::new(core::StsError, format!("Can't read: {}: {}", path.display(), e)))
+}
+
+fn write_file(path: &Path, data: &[u8]) -> Result<()> {
+	fs::write(path, data).map_err(|e| Error::new(core::StsError, format!("Can't write: {}: {}", path.display(), e)))
+}
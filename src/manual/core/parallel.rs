@@ -0,0 +1,85 @@
+use std::{
+	any::Any,
+	ffi::c_void,
+	ops::Range,
+	panic::{self, AssertUnwindSafe},
+	sync::Mutex,
+};
+
+use crate::{sys, Result};
+
+struct ParallelLoopBodyState<'f, F> {
+	body: &'f F,
+	panic: Mutex<Option<Box<dyn Any + Send>>>,
+}
+
+unsafe extern "C" fn parallel_loop_body_trampoline<F: Fn(Range<i32>) + Sync>(userdata: *const c_void, start: i32, end: i32) {
+	let state = &*(userdata as *const ParallelLoopBodyState<F>);
+	// unwinding into the OpenCV thread pool is UB, so store the first panic and resume it after the loop is done
+	if let Err(e) = panic::catch_unwind(AssertUnwindSafe(|| (state.body)(start..end))) {
+		if let Ok(mut panic) = state.panic.lock() {
+			panic.get_or_insert(e);
+		}
+	}
+}
+
+/// Runs `body` in parallel over the `range` using the OpenCV thread pool
+///
+/// This is the Rust counterpart of `parallel_for_` that accepts a closure instead of a `ParallelLoopBody` implementation. The
+/// closure receives subranges of `range` and can be called from multiple threads simultaneously. The number of threads is
+/// controlled by `set_num_threads()` like for the rest of OpenCV functions. If `body` panics the panic is propagated to the
+/// caller after all the running stripes are finished.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/db/de0/group__core__utils.html#ga7bed2d8ebeca5e0f6f8bc8ab21fd2c0d)
+///
+/// ## Parameters
+/// * range: range of indices to process
+/// * nstripes: number of parts to split the range into, pass `-1.` to let OpenCV decide
+/// * body: closure that processes the passed subrange
+///
+/// ## Example
+/// ```no_run
+/// use std::sync::atomic::{AtomicI64, Ordering};
+///
+/// let sum = AtomicI64::new(0);
+/// opencv::core::parallel_for(0..1000, -1., |r| {
+///     sum.fetch_add(r.map(i64::from).sum(), Ordering::Relaxed);
+/// })?;
+/// assert_eq!(499500, sum.into_inner());
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub fn parallel_for<F>(range: Range<i32>, nstripes: f64, body: F) -> Result<()>
+where
+	F: Fn(Range<i32>) + Sync,
+{
+	extern "C" {
+		fn cv_manual_parallel_for(
+			start: i32,
+			end: i32,
+			body: unsafe extern "C" fn(*const c_void, i32, i32),
+			userdata: *const c_void,
+			nstripes: f64,
+			ocvrs_return: *mut sys::Result_void,
+		);
+	}
+	let state = ParallelLoopBodyState {
+		body: &body,
+		panic: Mutex::new(None),
+	};
+	return_send!(via ocvrs_return);
+	unsafe {
+		cv_manual_parallel_for(
+			range.start,
+			range.end,
+			parallel_loop_body_trampoline::<F>,
+			&state as *const ParallelLoopBodyState<F> as *const c_void,
+			nstripes,
+			ocvrs_return.as_mut_ptr(),
+		)
+	};
+	return_receive!(unsafe ocvrs_return => ret);
+	if let Some(e) = state.panic.into_inner().unwrap_or_else(|e| e.into_inner()) {
+		panic::resume_unwind(e);
+	}
+	ret.into_result()
+}
@@ -1,11 +1,34 @@
 use std::{ffi::c_void, fmt};
 
+pub use graph::*;
+#[cfg(not(ocvrs_opencv_branch_32))]
+pub use model::*;
+pub use pool::*;
+#[cfg(ocvrs_has_module_imgproc)]
+pub use preprocess::*;
+pub use read::*;
+pub use rust_layer::*;
+pub use tensor::*;
+
 use crate::{
 	dnn::{DictValue, LayerParams},
 	prelude::*,
 	sys, Result,
 };
 
+mod graph;
+#[cfg(not(ocvrs_opencv_branch_32))]
+mod model;
+pub mod postprocess;
+mod pool;
+#[cfg(ocvrs_has_module_imgproc)]
+mod preprocess;
+mod read;
+mod rust_layer;
+mod tensor;
+#[cfg(feature = "dnn-testing")]
+pub mod testing;
+
 impl fmt::Debug for DictValue {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
 		let mut d = f.debug_struct("DictValue");
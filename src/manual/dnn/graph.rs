@@ -0,0 +1,392 @@
+use std::{
+	collections::HashMap,
+	fmt::{self, Write},
+	time::Duration,
+};
+
+use crate::{
+	core::{self, Vector},
+	dnn::{LayerParams, MatShape, Net},
+	prelude::*,
+	Result,
+};
+
+/// Layer of a [NetGraph]
+pub struct NetNode {
+	/// Layer id, 0 is the network input layer
+	pub id: i32,
+	pub name: String,
+	pub typ: String,
+	/// Name, type and learned blobs of the layer
+	///
+	/// The scalar parameters that were used to create the layer are not kept by OpenCV so the dictionary part is empty.
+	pub params: LayerParams,
+	/// Shapes of the layer inputs, empty if the graph was built without the network input shapes
+	pub input_shapes: Vec<Vec<i32>>,
+	/// Shapes of the layer outputs, empty if the graph was built without the network input shapes
+	pub output_shapes: Vec<Vec<i32>>,
+	/// Estimated floating point operations of the layer
+	pub flops: i64,
+	/// Memory in bytes taken by the layer weights
+	pub weights_memory: usize,
+	/// Memory in bytes taken by the layer output blobs
+	pub blobs_memory: usize,
+}
+
+impl NetNode {
+	/// Returns the shapes of the learned blobs of the layer
+	pub fn blob_shapes(&self) -> Vec<Vec<i32>> {
+		self.params.blobs().iter().map(|blob| blob.mat_size().to_vec()).collect()
+	}
+}
+
+impl fmt::Debug for NetNode {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("NetNode")
+			.field("id", &self.id)
+			.field("name", &self.name)
+			.field("typ", &self.typ)
+			.field("blob_shapes", &self.blob_shapes())
+			.field("input_shapes", &self.input_shapes)
+			.field("output_shapes", &self.output_shapes)
+			.field("flops", &self.flops)
+			.field("weights_memory", &self.weights_memory)
+			.field("blobs_memory", &self.blobs_memory)
+			.finish()
+	}
+}
+
+/// Connection between the output of the layer `from` and the input of the layer `to`, both are layer ids
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NetEdge {
+	pub from: i32,
+	pub to: i32,
+}
+
+/// Snapshot of the structure of a [Net] returned by [Net::graph]
+#[derive(Debug)]
+pub struct NetGraph {
+	/// Layers sorted by id
+	pub nodes: Vec<NetNode>,
+	pub edges: Vec<NetEdge>,
+	/// Estimated floating point operations of the whole network
+	pub flops: i64,
+	/// Memory in bytes taken by all weights of the network
+	pub weights_memory: usize,
+	/// Memory in bytes taken by all intermediate blobs of the network
+	pub blobs_memory: usize,
+}
+
+impl NetGraph {
+	/// Returns the node with the specified layer id
+	pub fn node(&self, id: i32) -> Option<&NetNode> {
+		self.nodes.iter().find(|node| node.id == id)
+	}
+
+	/// Returns the node with the specified layer name
+	pub fn node_by_name(&self, name: &str) -> Option<&NetNode> {
+		self.nodes.iter().find(|node| node.name == name)
+	}
+
+	/// Returns the ids of the layers which outputs are connected to the inputs of the layer `id`
+	pub fn inputs_of(&self, id: i32) -> impl Iterator<Item = i32> + '_ {
+		self.edges.iter().filter(move |e| e.to == id).map(|e| e.from)
+	}
+
+	/// Returns the ids of the layers which inputs are connected to the outputs of the layer `id`
+	pub fn outputs_of(&self, id: i32) -> impl Iterator<Item = i32> + '_ {
+		self.edges.iter().filter(move |e| e.from == id).map(|e| e.to)
+	}
+
+	/// Exports the graph in the Graphviz DOT format, e.g. for rendering with `dot -Tsvg`
+	pub fn to_dot(&self) -> String {
+		let mut out = String::from("digraph net {\n\tnode [shape=record];\n");
+		for node in &self.nodes {
+			let mut label = format!("{}|{}", escape_dot(&node.name), escape_dot(&node.typ));
+			for shape in &node.output_shapes {
+				write!(label, "|{}", format_shape(shape)).expect("Writing to String can't fail");
+			}
+			writeln!(out, "\tl{} [label=\"{{{}}}\"];", node.id, label).expect("Writing to String can't fail");
+		}
+		for edge in &self.edges {
+			writeln!(out, "\tl{} -> l{};", edge.from, edge.to).expect("Writing to String can't fail");
+		}
+		out.push_str("}\n");
+		out
+	}
+
+	/// Exports the graph as a JSON object with the `nodes`, `edges`, `flops`, `weights_memory` and `blobs_memory` keys
+	pub fn to_json(&self) -> String {
+		let mut out = String::from("{\"nodes\":[");
+		for (i, node) in self.nodes.iter().enumerate() {
+			if i > 0 {
+				out.push(',');
+			}
+			write!(
+				out,
+				"{{\"id\":{},\"name\":{},\"type\":{},\"blobs\":{},\"inputs\":{},\"outputs\":{},\"flops\":{},\"weights_memory\":{},\"blobs_memory\":{}}}",
+				node.id,
+				json_string(&node.name),
+				json_string(&node.typ),
+				json_shapes(&node.blob_shapes()),
+				json_shapes(&node.input_shapes),
+				json_shapes(&node.output_shapes),
+				node.flops,
+				node.weights_memory,
+				node.blobs_memory,
+			)
+			.expect("Writing to String can't fail");
+		}
+		out.push_str("],\"edges\":[");
+		for (i, edge) in self.edges.iter().enumerate() {
+			if i > 0 {
+				out.push(',');
+			}
+			write!(out, "[{},{}]", edge.from, edge.to).expect("Writing to String can't fail");
+		}
+		write!(
+			out,
+			"],\"flops\":{},\"weights_memory\":{},\"blobs_memory\":{}}}",
+			self.flops, self.weights_memory, self.blobs_memory
+		)
+		.expect("Writing to String can't fail");
+		out
+	}
+}
+
+fn format_shape(shape: &[i32]) -> String {
+	shape.iter().map(|d| d.to_string()).collect::<Vec<_>>().join("x")
+}
+
+fn escape_dot(s: &str) -> String {
+	let mut out = String::with_capacity(s.len());
+	for c in s.chars() {
+		if matches!(c, '"' | '\\' | '{' | '}' | '|' | '<' | '>') {
+			out.push('\\');
+		}
+		out.push(c);
+	}
+	out
+}
+
+fn json_string(s: &str) -> String {
+	let mut out = String::with_capacity(s.len() + 2);
+	out.push('"');
+	for c in s.chars() {
+		match c {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			c if c.is_control() => write!(out, "\\u{:04x}", c as u32).expect("Writing to String can't fail"),
+			c => out.push(c),
+		}
+	}
+	out.push('"');
+	out
+}
+
+fn json_shapes(shapes: &[Vec<i32>]) -> String {
+	let shapes = shapes
+		.iter()
+		.map(|shape| format!("[{}]", shape.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(",")))
+		.collect::<Vec<_>>();
+	format!("[{}]", shapes.join(","))
+}
+
+/// Execution time of a single layer, part of [PerfProfile]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerTiming {
+	pub id: i32,
+	pub name: String,
+	pub typ: String,
+	/// Zero for the layers that were fused with the others
+	pub time: Duration,
+}
+
+/// Timings of the last inference returned by [Net::perf_profile]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerfProfile {
+	/// Overall inference time
+	pub total: Duration,
+	/// Timings of all layers except the input one, sorted by id
+	pub layers: Vec<LayerTiming>,
+}
+
+impl fmt::Display for PerfProfile {
+	/// Writes a table of the layers sorted by the time they took, the slowest first
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut layers = self.layers.iter().collect::<Vec<_>>();
+		layers.sort_by(|a, b| b.time.cmp(&a.time).then(a.id.cmp(&b.id)));
+		let name_width = layers.iter().map(|l| l.name.len()).max().unwrap_or(0).max(4);
+		let type_width = layers.iter().map(|l| l.typ.len()).max().unwrap_or(0).max(4);
+		let total = self.total.as_secs_f64();
+		writeln!(
+			f,
+			"{:<name_width$}  {:<type_width$}  {:>10}  {:>6}",
+			"name",
+			"type",
+			"time, ms",
+			"%",
+			name_width = name_width,
+			type_width = type_width,
+		)?;
+		for layer in layers {
+			let time = layer.time.as_secs_f64();
+			let percent = if total > 0. {
+				time / total * 100.
+			} else {
+				0.
+			};
+			writeln!(
+				f,
+				"{:<name_width$}  {:<type_width$}  {:>10.3}  {:>6.2}",
+				layer.name,
+				layer.typ,
+				time * 1000.,
+				percent,
+				name_width = name_width,
+				type_width = type_width,
+			)?;
+		}
+		write!(f, "total: {:.3} ms", total * 1000.)
+	}
+}
+
+fn to_mat_shapes(shapes: &[&[i32]]) -> Vector<MatShape> {
+	shapes.iter().map(|shape| MatShape::from_slice(shape)).collect()
+}
+
+fn ticks_to_duration(ticks: f64, frequency: f64) -> Duration {
+	Duration::from_secs_f64((ticks / frequency).max(0.))
+}
+
+impl Net {
+	/// Collects the layers of the network together with their connections
+	///
+	/// `input_shapes` are the shapes of the network inputs, they are needed to calculate the shapes of the layer blobs and the
+	/// FLOPs and memory estimates. Pass an empty slice to skip those, the corresponding fields are then left empty or zero.
+	///
+	/// ## Example
+	/// ```no_run
+	/// use opencv::dnn;
+	///
+	/// let net = dnn::read_net_from_onnx("model.onnx")?;
+	/// let graph = net.graph(&[&[1, 3, 224, 224]])?;
+	/// std::fs::write("model.dot", graph.to_dot()).unwrap();
+	/// # Ok::<_, opencv::Error>(())
+	/// ```
+	pub fn graph(&self, input_shapes: &[&[i32]]) -> Result<NetGraph> {
+		let mut nodes = vec![];
+		let mut name_to_id = HashMap::new();
+		// the input layer has id 0 and it's not listed in the layer names
+		let mut ids = vec![0];
+		for name in self.get_layer_names()? {
+			ids.push(self.get_layer_id(&name)?);
+		}
+		for &id in &ids {
+			let layer = self.get_layer(id)?;
+			let mut params = LayerParams::default()?;
+			params.set_name(&layer.name());
+			params.set_type(&layer.typ());
+			params.set_blobs(layer.blobs());
+			name_to_id.insert(layer.name(), id);
+			nodes.push(NetNode {
+				id,
+				name: layer.name(),
+				typ: layer.typ(),
+				params,
+				input_shapes: vec![],
+				output_shapes: vec![],
+				flops: 0,
+				weights_memory: 0,
+				blobs_memory: 0,
+			});
+		}
+		nodes.sort_by_key(|node| node.id);
+
+		let mut edges = vec![];
+		for node in nodes.iter().filter(|node| node.id != 0) {
+			for input in self.get_layer_inputs(node.id)? {
+				if let Some(&from) = name_to_id.get(&input.name()) {
+					edges.push(NetEdge { from, to: node.id });
+				}
+			}
+		}
+
+		let mut graph = NetGraph {
+			nodes,
+			edges,
+			flops: 0,
+			weights_memory: 0,
+			blobs_memory: 0,
+		};
+		if input_shapes.is_empty() {
+			return Ok(graph);
+		}
+
+		let net_input_shapes = to_mat_shapes(input_shapes);
+		let mut layer_ids = Vector::new();
+		let mut in_shapes = Vector::new();
+		let mut out_shapes = Vector::new();
+		self.get_layers_shapes(&net_input_shapes, &mut layer_ids, &mut in_shapes, &mut out_shapes)?;
+		let by_id = graph
+			.nodes
+			.iter()
+			.enumerate()
+			.map(|(i, node)| (node.id, i))
+			.collect::<HashMap<_, _>>();
+		for ((id, ins), outs) in layer_ids.iter().zip(in_shapes).zip(out_shapes) {
+			if let Some(&i) = by_id.get(&id) {
+				let node = &mut graph.nodes[i];
+				node.input_shapes = ins.iter().map(|shape| shape.to_vec()).collect();
+				node.output_shapes = outs.iter().map(|shape| shape.to_vec()).collect();
+			}
+		}
+
+		let mut weights = Vector::new();
+		let mut blobs = Vector::new();
+		self.get_memory_consumption_for_layers(&net_input_shapes, &mut layer_ids, &mut weights, &mut blobs)?;
+		for ((id, weights), blobs) in layer_ids.iter().zip(weights).zip(blobs) {
+			if let Some(&i) = by_id.get(&id) {
+				graph.nodes[i].weights_memory = weights;
+				graph.nodes[i].blobs_memory = blobs;
+			}
+		}
+		for node in graph.nodes.iter_mut().filter(|node| node.id != 0) {
+			node.flops = self.get_flops_2(node.id, &net_input_shapes)?;
+		}
+
+		graph.flops = self.get_flops(&net_input_shapes)?;
+		self.get_memory_consumption(&net_input_shapes, &mut graph.weights_memory, &mut graph.blobs_memory)?;
+		Ok(graph)
+	}
+
+	/// Returns the per-layer timings of the last [forward](crate::dnn::NetTrait::forward) call
+	///
+	/// Supported by `DNN_BACKEND_OPENCV` on `DNN_TARGET_CPU` only. Use `{}` formatting of the result for a text report.
+	pub fn perf_profile(&mut self) -> Result<PerfProfile> {
+		let mut timings = Vector::<f64>::new();
+		let total = self.get_perf_profile(&mut timings)?;
+		let frequency = core::get_tick_frequency()?;
+		let names = self.get_layer_names()?;
+		let mut layers = Vec::with_capacity(timings.len());
+		// timings start with the layer following the input one, in the same order as the layer names
+		for (name, ticks) in names.iter().zip(timings) {
+			let id = self.get_layer_id(&name)?;
+			let typ = self.get_layer(id)?.typ();
+			layers.push(LayerTiming {
+				id,
+				name,
+				typ,
+				time: ticks_to_duration(ticks, frequency),
+			});
+		}
+		layers.sort_by_key(|layer| layer.id);
+		Ok(PerfProfile {
+			total: ticks_to_duration(total as f64, frequency),
+			layers,
+		})
+	}
+}
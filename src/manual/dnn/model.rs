@@ -0,0 +1,246 @@
+use std::{convert::TryFrom, fs, path::Path};
+
+#[cfg(ocvrs_opencv_branch_4)]
+use crate::{
+	core::Point,
+	dnn::{TextDetectionModelTraitConst, TextRecognitionModelTraitConst},
+};
+use crate::{
+	core::{self, Mat, Point2f, Rect, Rect2f, ToInputArray, Vector},
+	dnn::{postprocess::Detection, ClassificationModelTrait, DetectionModelTrait, KeypointsModelTrait, SegmentationModelTrait},
+	Error, Result,
+};
+
+/// Class names indexed by the class id, usually loaded from a text file with one name per line like `coco.names`
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Labels {
+	names: Vec<String>,
+}
+
+impl Labels {
+	pub fn new(names: Vec<String>) -> Self {
+		Self { names }
+	}
+
+	/// Parses the labels from the text with one name per line, the trailing whitespace of every line and the trailing empty lines
+	/// are ignored
+	pub fn parse(text: &str) -> Self {
+		let mut names = text.lines().map(|line| line.trim_end().to_string()).collect::<Vec<_>>();
+		while let Some(true) = names.last().map(String::is_empty) {
+			names.pop();
+		}
+		Self { names }
+	}
+
+	/// Reads the labels from the file with one name per line, see [Labels::parse]
+	pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+		let path = path.as_ref();
+		fs::read_to_string(path)
+			.map(|text| Self::parse(&text))
+			.map_err(|e| Error::new(core::StsError, format!("Can't read labels from: {}: {}", path.display(), e)))
+	}
+
+	/// Returns the name of the class or `None` if the id is out of bounds
+	pub fn name(&self, class_id: i32) -> Option<&str> {
+		usize::try_from(class_id)
+			.ok()
+			.and_then(|i| self.names.get(i))
+			.map(|s| s.as_str())
+	}
+
+	/// Returns the id of the class with the specified name
+	pub fn id(&self, name: &str) -> Option<i32> {
+		self.names.iter().position(|n| n == name).map(|i| i as i32)
+	}
+
+	pub fn len(&self) -> usize {
+		self.names.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.names.is_empty()
+	}
+
+	pub fn names(&self) -> &[String] {
+		&self.names
+	}
+}
+
+impl Detection {
+	/// Returns the class name of the detection
+	pub fn label<'l>(&self, labels: &'l Labels) -> Option<&'l str> {
+		labels.name(self.class_id)
+	}
+}
+
+/// Top class returned by [ClassificationModelTraitManual::classification]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Classification {
+	pub class_id: i32,
+	pub confidence: f32,
+}
+
+impl Classification {
+	/// Returns the class name of the classification
+	pub fn label<'l>(&self, labels: &'l Labels) -> Option<&'l str> {
+		labels.name(self.class_id)
+	}
+}
+
+/// Keypoint found by [KeypointsModelTraitManual::keypoints]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Keypoint {
+	/// Index of the keypoint in the model output, e.g. the body part for the pose estimation models
+	pub id: i32,
+	pub point: Point2f,
+}
+
+impl Keypoint {
+	/// Returns the name of the keypoint
+	pub fn label<'l>(&self, labels: &'l Labels) -> Option<&'l str> {
+		labels.name(self.id)
+	}
+}
+
+/// Text area found by [TextDetectionModelTraitConstManual::text_regions]
+#[cfg(ocvrs_opencv_branch_4)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextRegion {
+	/// Corners of the area in the order: bottom-left, top-left, top-right, bottom-right
+	pub quad: [Point; 4],
+	/// Confidence of the detection, 1 for the models that don't provide it
+	pub confidence: f32,
+}
+
+#[cfg(ocvrs_opencv_branch_4)]
+impl TextRegion {
+	/// Returns the axis-aligned bounding box of the quad
+	pub fn bounding_rect(&self) -> Rect {
+		let min_x = self.quad.iter().map(|pt| pt.x).min().unwrap_or(0);
+		let min_y = self.quad.iter().map(|pt| pt.y).min().unwrap_or(0);
+		let max_x = self.quad.iter().map(|pt| pt.x).max().unwrap_or(0);
+		let max_y = self.quad.iter().map(|pt| pt.y).max().unwrap_or(0);
+		Rect::new(min_x, min_y, max_x - min_x, max_y - min_y)
+	}
+}
+
+pub trait DetectionModelTraitManual: DetectionModelTrait {
+	/// Same as `detect()`, but returns the results as a list of [Detection]s
+	///
+	/// `landmarks` of the detections are always empty.
+	fn detections(&mut self, frame: &dyn ToInputArray, conf_threshold: f32, nms_threshold: f32) -> Result<Vec<Detection>> {
+		let mut class_ids = Vector::<i32>::new();
+		let mut confidences = Vector::<f32>::new();
+		let mut boxes = Vector::<Rect>::new();
+		self.detect(
+			frame,
+			&mut class_ids,
+			&mut confidences,
+			&mut boxes,
+			conf_threshold,
+			nms_threshold,
+		)?;
+		Ok(class_ids
+			.iter()
+			.zip(confidences)
+			.zip(boxes)
+			.map(|((class_id, confidence), rect)| Detection {
+				class_id,
+				confidence,
+				rect: Rect2f::new(rect.x as f32, rect.y as f32, rect.width as f32, rect.height as f32),
+				landmarks: vec![],
+			})
+			.collect())
+	}
+}
+
+impl<T: DetectionModelTrait + ?Sized> DetectionModelTraitManual for T {}
+
+pub trait ClassificationModelTraitManual: ClassificationModelTrait {
+	/// Same as `classify()`, but returns the result as a [Classification]
+	fn classification(&mut self, frame: &dyn ToInputArray) -> Result<Classification> {
+		let mut out = Classification {
+			class_id: -1,
+			confidence: 0.,
+		};
+		self.classify(frame, &mut out.class_id, &mut out.confidence)?;
+		Ok(out)
+	}
+}
+
+impl<T: ClassificationModelTrait + ?Sized> ClassificationModelTraitManual for T {}
+
+pub trait SegmentationModelTraitManual: SegmentationModelTrait {
+	/// Same as `segment()`, but returns the mask of the class ids
+	fn segmentation(&mut self, frame: &dyn ToInputArray) -> Result<Mat> {
+		let mut mask = Mat::default();
+		self.segment(frame, &mut mask)?;
+		Ok(mask)
+	}
+}
+
+impl<T: SegmentationModelTrait + ?Sized> SegmentationModelTraitManual for T {}
+
+pub trait KeypointsModelTraitManual: KeypointsModelTrait {
+	/// Same as `estimate()`, but returns only the found keypoints as a list of [Keypoint]s
+	///
+	/// The keypoints with the confidence not exceeding `thresh` are skipped, the ids of the remaining ones are kept.
+	fn keypoints(&mut self, frame: &dyn ToInputArray, thresh: f32) -> Result<Vec<Keypoint>> {
+		// OpenCV reports the keypoints that are not found as (-1, -1)
+		let missing = Point2f::new(-1., -1.);
+		Ok(self
+			.estimate(frame, thresh)?
+			.iter()
+			.enumerate()
+			.filter(|&(_, point)| point != missing)
+			.map(|(id, point)| Keypoint { id: id as i32, point })
+			.collect())
+	}
+}
+
+impl<T: KeypointsModelTrait + ?Sized> KeypointsModelTraitManual for T {}
+
+#[cfg(ocvrs_opencv_branch_4)]
+pub trait TextDetectionModelTraitConstManual: TextDetectionModelTraitConst {
+	/// Same as `detect_with_confidences()`, but returns the results as a list of [TextRegion]s
+	fn text_regions(&self, frame: &dyn ToInputArray) -> Result<Vec<TextRegion>> {
+		let mut detections = Vector::<Vector<Point>>::new();
+		let mut confidences = Vector::<f32>::new();
+		self.detect_with_confidences(frame, &mut detections, &mut confidences)?;
+		detections
+			.iter()
+			.enumerate()
+			.map(|(i, quad)| {
+				if quad.len() != 4 {
+					return Err(Error::new(
+						core::StsUnmatchedSizes,
+						format!("Text region must have 4 corners, but it has: {}", quad.len()),
+					));
+				}
+				Ok(TextRegion {
+					quad: [quad.get(0)?, quad.get(1)?, quad.get(2)?, quad.get(3)?],
+					confidence: confidences.get(i).unwrap_or(1.),
+				})
+			})
+			.collect()
+	}
+}
+
+#[cfg(ocvrs_opencv_branch_4)]
+impl<T: TextDetectionModelTraitConst + ?Sized> TextDetectionModelTraitConstManual for T {}
+
+#[cfg(ocvrs_opencv_branch_4)]
+pub trait TextRecognitionModelTraitConstManual: TextRecognitionModelTraitConst {
+	/// Recognizes the text in the bounding boxes of the `regions`, e.g. returned by [TextDetectionModelTraitConstManual::text_regions]
+	///
+	/// The regions are cropped without rotation, so the text is expected to be roughly horizontal.
+	fn recognize_regions(&self, frame: &dyn ToInputArray, regions: &[TextRegion]) -> Result<Vec<String>> {
+		let rects = regions.iter().map(TextRegion::bounding_rect).collect::<Vector<Rect>>();
+		let mut results = Vector::<String>::new();
+		self.recognize_1(frame, &rects, &mut results)?;
+		Ok(results.to_vec())
+	}
+}
+
+#[cfg(ocvrs_opencv_branch_4)]
+impl<T: TextRecognitionModelTraitConst + ?Sized> TextRecognitionModelTraitConstManual for T {}
@@ -0,0 +1,451 @@
+use std::{
+	collections::VecDeque,
+	fmt, mem,
+	ops::{Deref, DerefMut},
+	sync::{mpsc, Arc, Condvar, Mutex, MutexGuard, PoisonError},
+	thread::{self, JoinHandle},
+	time::{Duration, Instant},
+};
+
+use crate::{
+	core::{self, Mat, Scalar, Vector},
+	dnn::{self, Net, Preprocessor, Transform},
+	prelude::*,
+	Error, Result,
+};
+
+struct PoolShared {
+	nets: Mutex<Vec<Net>>,
+	size: usize,
+	net_returned: Condvar,
+}
+
+impl PoolShared {
+	fn lock(&self) -> MutexGuard<'_, Vec<Net>> {
+		// the list of nets is kept consistent even if a thread panics while holding the lock
+		self.nets.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	fn get(&self, timeout: Option<Duration>) -> Option<NetGuard<'_>> {
+		// a timeout too large to be represented as an Instant means waiting indefinitely
+		let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+		let mut nets = self.lock();
+		loop {
+			if let Some(net) = nets.pop() {
+				return Some(NetGuard {
+					pool: self,
+					net: Some(net),
+				});
+			}
+			nets = match deadline {
+				Some(deadline) => {
+					let now = Instant::now();
+					if now >= deadline {
+						return None;
+					}
+					self
+						.net_returned
+						.wait_timeout(nets, deadline - now)
+						.unwrap_or_else(PoisonError::into_inner)
+						.0
+				}
+				None => self.net_returned.wait(nets).unwrap_or_else(PoisonError::into_inner),
+			};
+		}
+	}
+}
+
+/// Fixed set of independent [Net] instances of the same model for running inference from multiple threads
+///
+/// A single `Net` can't run `forward()` concurrently and cloning it only creates another reference to the same instance, so the pool
+/// creates every net separately, e.g. by parsing the same in-memory model `size` times. Nets are borrowed with [NetPool::get] and
+/// are returned back when the [NetGuard] is dropped. Cloning the pool is cheap and produces another handle to the same nets.
+///
+/// ## Example
+/// ```no_run
+/// use std::thread;
+/// use opencv::{dnn::{self, NetPool}, prelude::*};
+///
+/// let model = std::fs::read("model.onnx").unwrap();
+/// let pool = NetPool::from_onnx_buffer(&model, 4, dnn::DNN_BACKEND_OPENCV, dnn::DNN_TARGET_CPU)?;
+/// let threads = (0..8)
+///     .map(|_| {
+///         let pool = pool.clone();
+///         thread::spawn(move || {
+///             let mut net = pool.get();
+///             net.forward_single("")
+///         })
+///     })
+///     .collect::<Vec<_>>();
+/// for thread in threads {
+///     let output = thread.join().unwrap()?;
+/// }
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[derive(Clone)]
+pub struct NetPool {
+	shared: Arc<PoolShared>,
+}
+
+impl NetPool {
+	/// Creates a pool of `size` nets each produced by a `create` call
+	pub fn new(size: usize, mut create: impl FnMut() -> Result<Net>) -> Result<Self> {
+		if size == 0 {
+			return Err(Error::new(core::StsBadArg, "Pool size must be at least 1"));
+		}
+		let nets = (0..size).map(|_| create()).collect::<Result<Vec<_>>>()?;
+		Ok(Self {
+			shared: Arc::new(PoolShared {
+				nets: Mutex::new(nets),
+				size,
+				net_returned: Condvar::new(),
+			}),
+		})
+	}
+
+	/// Creates a pool of `size` nets from the in-memory model of the specified framework, see `dnn::read_net_1()`
+	///
+	/// The model data is copied only once, the nets are configured to use the specified `backend` and `target`.
+	pub fn from_buffer(framework: &str, model: &[u8], config: &[u8], size: usize, backend: i32, target: i32) -> Result<Self> {
+		let model = Vector::<u8>::from_slice(model);
+		let config = Vector::<u8>::from_slice(config);
+		Self::new(size, || {
+			let mut net = dnn::read_net_1(framework, &model, &config)?;
+			net.set_preferable_backend(backend)?;
+			net.set_preferable_target(target)?;
+			Ok(net)
+		})
+	}
+
+	/// Same as [NetPool::from_buffer] for an ONNX model
+	pub fn from_onnx_buffer(model: &[u8], size: usize, backend: i32, target: i32) -> Result<Self> {
+		Self::from_buffer("onnx", model, &[], size, backend, target)
+	}
+
+	/// Total number of nets in the pool
+	pub fn size(&self) -> usize {
+		self.shared.size
+	}
+
+	/// Number of nets that are currently not borrowed
+	pub fn available(&self) -> usize {
+		self.shared.lock().len()
+	}
+
+	/// Borrows a net from the pool waiting until one is available
+	pub fn get(&self) -> NetGuard<'_> {
+		self.shared.get(None).expect("Waiting without timeout always returns a net")
+	}
+
+	/// Borrows a net from the pool if one is available right away
+	pub fn try_get(&self) -> Option<NetGuard<'_>> {
+		self.shared.get(Some(Duration::ZERO))
+	}
+
+	/// Borrows a net from the pool waiting for at most `timeout` for one to become available
+	pub fn get_timeout(&self, timeout: Duration) -> Option<NetGuard<'_>> {
+		self.shared.get(Some(timeout))
+	}
+
+	/// Starts a background batching of single-image requests using the nets of this pool, see [BatchedNetPool]
+	pub fn batched(&self, config: BatchConfig) -> Result<BatchedNetPool> {
+		BatchedNetPool::new(Arc::clone(&self.shared), config)
+	}
+}
+
+impl fmt::Debug for NetPool {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("NetPool")
+			.field("size", &self.size())
+			.field("available", &self.available())
+			.finish()
+	}
+}
+
+/// [Net] borrowed from a [NetPool], it's returned to the pool on drop
+pub struct NetGuard<'p> {
+	pool: &'p PoolShared,
+	net: Option<Net>,
+}
+
+impl Deref for NetGuard<'_> {
+	type Target = Net;
+
+	fn deref(&self) -> &Net {
+		self.net.as_ref().expect("Net is only taken on drop")
+	}
+}
+
+impl DerefMut for NetGuard<'_> {
+	fn deref_mut(&mut self) -> &mut Net {
+		self.net.as_mut().expect("Net is only taken on drop")
+	}
+}
+
+impl Drop for NetGuard<'_> {
+	fn drop(&mut self) {
+		if let Some(net) = self.net.take() {
+			self.pool.lock().push(net);
+			self.pool.net_returned.notify_one();
+		}
+	}
+}
+
+impl fmt::Debug for NetGuard<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("NetGuard").finish()
+	}
+}
+
+/// Preprocessing and batching parameters of [BatchedNetPool]
+///
+/// The images of a batch are converted to the input blob with [Preprocessor::process_batch].
+#[derive(Clone, Debug)]
+pub struct BatchConfig {
+	preprocessor: Preprocessor,
+	max_batch: usize,
+	max_latency: Duration,
+	outputs: Vec<String>,
+}
+
+impl BatchConfig {
+	/// Creates the config producing the network input with the `preprocessor`
+	///
+	/// Defaults are: batches of up to 8 images waiting for at most 5 ms, all unconnected outputs of the net.
+	pub fn new(preprocessor: Preprocessor) -> Self {
+		Self {
+			preprocessor,
+			max_batch: 8,
+			max_latency: Duration::from_millis(5),
+			outputs: vec![],
+		}
+	}
+
+	/// Maximum number of images in a single forward pass
+	pub fn max_batch(mut self, max_batch: usize) -> Self {
+		self.max_batch = max_batch;
+		self
+	}
+
+	/// Maximum time the first request of a batch waits for the other requests to join it
+	pub fn max_latency(mut self, max_latency: Duration) -> Self {
+		self.max_latency = max_latency;
+		self
+	}
+
+	/// Names of the output layers to return, empty for all unconnected outputs
+	pub fn outputs(mut self, outputs: &[&str]) -> Self {
+		self.outputs = outputs.iter().map(|s| s.to_string()).collect();
+		self
+	}
+}
+
+struct Request {
+	image: Mat,
+	arrived: Instant,
+	result: mpsc::Sender<Result<(Vec<Mat>, Transform)>>,
+}
+
+struct BatchState {
+	queue: VecDeque<Request>,
+	stop: bool,
+}
+
+struct BatchShared {
+	state: Mutex<BatchState>,
+	request_added: Condvar,
+	config: BatchConfig,
+}
+
+impl BatchShared {
+	fn lock(&self) -> MutexGuard<'_, BatchState> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Waits until the batch is full or the oldest request reaches its deadline, returns `None` when the worker must stop
+	fn next_batch(&self) -> Option<Vec<Request>> {
+		let mut state = self.lock();
+		loop {
+			if let Some(first) = state.queue.front() {
+				// a latency too large to be represented as an Instant means waiting for a full batch
+				let deadline = first.arrived.checked_add(self.config.max_latency);
+				let now = Instant::now();
+				if state.stop || state.queue.len() >= self.config.max_batch || deadline.map_or(false, |deadline| now >= deadline) {
+					let len = state.queue.len().min(self.config.max_batch);
+					let batch = state.queue.drain(..len).collect();
+					if !state.queue.is_empty() {
+						// let another worker pick up the rest
+						self.request_added.notify_one();
+					}
+					return Some(batch);
+				}
+				state = match deadline {
+					Some(deadline) => {
+						self
+							.request_added
+							.wait_timeout(state, deadline - now)
+							.unwrap_or_else(PoisonError::into_inner)
+							.0
+					}
+					None => self.request_added.wait(state).unwrap_or_else(PoisonError::into_inner),
+				};
+			} else if state.stop {
+				return None;
+			} else {
+				state = self.request_added.wait(state).unwrap_or_else(PoisonError::into_inner);
+			}
+		}
+	}
+}
+
+/// Runs the single-image requests in batches on the nets of a [NetPool]
+///
+/// Requests submitted with [BatchedNetPool::infer] are queued and processed by one background worker per net. A worker starts the
+/// forward pass once `max_batch` requests have been collected or the oldest one has waited for `max_latency`, whichever comes first.
+/// The network must accept a variable batch size. Dropping the `BatchedNetPool` processes the already queued requests and stops
+/// the workers.
+///
+/// ## Example
+/// ```no_run
+/// use std::time::Duration;
+/// use opencv::{core::Size, dnn::{self, postprocess::ResizeMode, BatchConfig, NetPool, Preprocessor}, imgcodecs};
+///
+/// let model = std::fs::read("model.onnx").unwrap();
+/// let pool = NetPool::from_onnx_buffer(&model, 2, dnn::DNN_BACKEND_OPENCV, dnn::DNN_TARGET_CPU)?;
+/// let preprocessor = Preprocessor::new(Size::new(640, 640))
+///     .resize_mode(ResizeMode::Letterbox)
+///     .swap_rb(true)
+///     .scale(1. / 255.);
+/// let batched = pool.batched(BatchConfig::new(preprocessor).max_latency(Duration::from_millis(10)))?;
+/// let image = imgcodecs::imread("image.jpg", imgcodecs::IMREAD_COLOR)?;
+/// let (outputs, transform) = batched.infer(&image)?;
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub struct BatchedNetPool {
+	shared: Arc<BatchShared>,
+	workers: Vec<JoinHandle<()>>,
+}
+
+impl BatchedNetPool {
+	fn new(pool: Arc<PoolShared>, config: BatchConfig) -> Result<Self> {
+		if config.max_batch == 0 {
+			return Err(Error::new(core::StsBadArg, "Maximum batch size must be at least 1"));
+		}
+		let shared = Arc::new(BatchShared {
+			state: Mutex::new(BatchState {
+				queue: VecDeque::new(),
+				stop: false,
+			}),
+			request_added: Condvar::new(),
+			config,
+		});
+		let mut out = Self { shared, workers: vec![] };
+		for i in 0..pool.size {
+			let shared = Arc::clone(&out.shared);
+			let pool = Arc::clone(&pool);
+			let worker = thread::Builder::new()
+				.name(format!("opencv-net-pool-{}", i))
+				.spawn(move || {
+					while let Some(mut batch) = shared.next_batch() {
+						let images = batch.iter_mut().map(|req| mem::take(&mut req.image)).collect::<Vec<_>>();
+						let mut res = {
+							let mut net = pool.get(None).expect("Waiting without timeout always returns a net");
+							run_batch(&mut net, &shared.config, &images)
+						};
+						for req in batch.into_iter().rev() {
+							let out = match &mut res {
+								Ok(outputs) => Ok(outputs.pop().expect("Output is produced for every request")),
+								Err(e) => Err(Error::new(e.code, e.message.clone())),
+							};
+							// the requester may have gone away, nothing to do about it
+							let _ = req.result.send(out);
+						}
+					}
+				})
+				.map_err(|e| Error::new(core::StsError, format!("Can't spawn net pool worker thread: {}", e)))?;
+			// already spawned workers are stopped by the Drop of `out` on error
+			out.workers.push(worker);
+		}
+		Ok(out)
+	}
+
+	/// Runs the inference on a single image waiting for the batch it's part of to finish
+	///
+	/// Returns the outputs in the order of [BatchConfig::outputs] or of the unconnected output layers, every output has the batch
+	/// dimension of 1, together with the [Transform] that maps the coordinates of the outputs back to the `image`.
+	pub fn infer(&self, image: &Mat) -> Result<(Vec<Mat>, Transform)> {
+		let (tx, rx) = mpsc::channel();
+		{
+			let mut state = self.shared.lock();
+			state.queue.push_back(Request {
+				image: image.try_clone()?,
+				arrived: Instant::now(),
+				result: tx,
+			});
+			self.shared.request_added.notify_one();
+		}
+		rx.recv()
+			.map_err(|_| Error::new(core::StsError, "Net pool worker has stopped unexpectedly"))?
+	}
+
+	/// Number of requests waiting for a batch to start
+	pub fn pending(&self) -> usize {
+		self.shared.lock().queue.len()
+	}
+}
+
+impl Drop for BatchedNetPool {
+	fn drop(&mut self) {
+		self.shared.lock().stop = true;
+		self.shared.request_added.notify_all();
+		for worker in self.workers.drain(..) {
+			// worker thread doesn't panic unless OpenCV does, nothing to do about it here
+			let _ = worker.join();
+		}
+	}
+}
+
+impl fmt::Debug for BatchedNetPool {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("BatchedNetPool")
+			.field("config", &self.shared.config)
+			.field("workers", &self.workers.len())
+			.field("pending", &self.pending())
+			.finish()
+	}
+}
+
+/// Runs a single forward pass for all `images` and splits the outputs per image
+fn run_batch(net: &mut Net, config: &BatchConfig, images: &[Mat]) -> Result<Vec<(Vec<Mat>, Transform)>> {
+	let (blob, transforms) = config.preprocessor.process_batch(&images.iter().collect::<Vec<_>>())?;
+	net.set_input(&blob, "", 1., Scalar::default())?;
+	let names = if config.outputs.is_empty() {
+		net.get_unconnected_out_layers_names()?
+	} else {
+		config.outputs.iter().map(|s| s.as_str()).collect()
+	};
+	let mut outputs = Vector::<Mat>::new();
+	net.forward(&mut outputs, &names)?;
+
+	let batch = images.len();
+	let mut out = transforms
+		.into_iter()
+		.map(|transform| (Vec::with_capacity(outputs.len()), transform))
+		.collect::<Vec<_>>();
+	for output in outputs {
+		let shape = output.mat_size();
+		if shape.first().map(|&n| n as usize) != Some(batch) {
+			return Err(Error::new(
+				core::StsUnmatchedSizes,
+				format!("Output shape: {:?} doesn't have the batch dimension of: {}", &*shape, batch),
+			));
+		}
+		let mut item_shape = shape.to_vec();
+		item_shape[0] = 1;
+		let rows = output.reshape(1, batch as i32)?;
+		for (i, (item, _)) in out.iter_mut().enumerate() {
+			item.push(rows.row(i as i32)?.try_clone()?.reshape_nd(1, &item_shape)?);
+		}
+	}
+	Ok(out)
+}
@@ -0,0 +1,682 @@
+//! Decoding of the outputs of the common detection networks
+//!
+//! The decoders take the output [Tensor]s of `Net::forward()` and return the [Detection]s in the pixel coordinates of the network
+//! input. The usual pipeline is to decode the outputs, filter them with [nms_by_class] and map them back to the source image with
+//! [Detection::to_source].
+//!
+//! ## Example
+//! ```no_run
+//! use opencv::{core::Size, dnn::{postprocess::{self, BlobMapping, ResizeMode, YoloLayout}, Tensor}, prelude::*};
+//!
+//! # fn detect(mut net: opencv::dnn::Net, image: opencv::core::Mat) -> opencv::Result<()> {
+//! let input_size = Size::new(640, 640);
+//! let mapping = BlobMapping::new(image.size()?, input_size, ResizeMode::Letterbox);
+//! let out = Tensor::<f32>::from_mat(net.forward_single("")?)?;
+//! let detections = postprocess::decode_yolo(&out, &YoloLayout::AnchorFree, input_size, 0.25)?;
+//! for detection in postprocess::nms_by_class(&detections, 0.25, 0.45, 0)? {
+//!     let detection = detection.to_source(&mapping);
+//!     println!("class {} ({:.2}) at {:?}", detection.class_id, detection.confidence, detection.rect);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(not(ocvrs_opencv_branch_32))]
+use std::collections::BTreeMap;
+
+#[cfg(not(ocvrs_opencv_branch_32))]
+use crate::core::{Rect2d, RotatedRect, Vector};
+#[cfg(ocvrs_opencv_branch_4)]
+use crate::{core::Rect, dnn::SoftNMSMethod};
+use crate::{
+	core::{self, Point2f, Rect2f, Size},
+	dnn::Tensor,
+	Error, Result,
+};
+
+/// Detected object
+#[derive(Clone, Debug, PartialEq)]
+pub struct Detection {
+	pub class_id: i32,
+	pub confidence: f32,
+	/// Bounding box in pixels
+	pub rect: Rect2f,
+	/// Landmarks like the eye and mouth corners for the face detectors, empty for the other networks
+	pub landmarks: Vec<Point2f>,
+}
+
+impl Detection {
+	/// Maps the detection from the network input coordinates to the source image, the bounding box is clipped to the image
+	pub fn to_source(&self, mapping: &BlobMapping) -> Self {
+		Self {
+			class_id: self.class_id,
+			confidence: self.confidence,
+			rect: mapping.to_source_rect(self.rect),
+			landmarks: self.landmarks.iter().map(|&pt| mapping.to_source_point(pt)).collect(),
+		}
+	}
+}
+
+/// The way the source image was resized to the size of the network input
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResizeMode {
+	/// Resize ignoring the aspect ratio, `blob_from_image()` with `crop = false`
+	Stretch,
+	/// Resize preserving the aspect ratio so that the image covers the input and crop the center, `blob_from_image()` with
+	/// `crop = true`
+	Crop,
+	/// Resize preserving the aspect ratio so that the image fits the input and pad it equally on both sides, the usual YOLO
+	/// preprocessing
+	Letterbox,
+	/// Like [ResizeMode::Letterbox], but the image is placed at the top left corner and padded on the right and bottom
+	LetterboxTopLeft,
+}
+
+impl Default for ResizeMode {
+	fn default() -> Self {
+		Self::Stretch
+	}
+}
+
+/// Mapping between the pixel coordinates of the source image and the network input
+///
+/// The point in the network input is `source * scale + offset` for each axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BlobMapping {
+	source_size: Size,
+	scale: (f32, f32),
+	offset: (f32, f32),
+}
+
+impl BlobMapping {
+	/// Creates the mapping for the source image of `source_size` resized to the network input of `blob_size` using `mode`
+	pub fn new(source_size: Size, blob_size: Size, mode: ResizeMode) -> Self {
+		let (sw, sh) = (source_size.width as f32, source_size.height as f32);
+		let (bw, bh) = (blob_size.width as f32, blob_size.height as f32);
+		let (scale_x, scale_y) = (bw / sw, bh / sh);
+		let (scale, offset) = match mode {
+			ResizeMode::Stretch => ((scale_x, scale_y), (0., 0.)),
+			ResizeMode::Crop | ResizeMode::Letterbox => {
+				let scale = if mode == ResizeMode::Crop {
+					scale_x.max(scale_y)
+				} else {
+					scale_x.min(scale_y)
+				};
+				// the offset is negative for the cropped part and positive for the padding
+				((scale, scale), ((bw - sw * scale) / 2., (bh - sh * scale) / 2.))
+			}
+			ResizeMode::LetterboxTopLeft => {
+				let scale = scale_x.min(scale_y);
+				((scale, scale), (0., 0.))
+			}
+		};
+		Self {
+			source_size,
+			scale,
+			offset,
+		}
+	}
+
+	/// Creates the mapping from the scale and the offset that were applied to the source image on each axis
+	pub fn from_scale_offset(source_size: Size, scale: (f32, f32), offset: (f32, f32)) -> Self {
+		Self {
+			source_size,
+			scale,
+			offset,
+		}
+	}
+
+	#[inline]
+	pub fn source_size(&self) -> Size {
+		self.source_size
+	}
+
+	/// Maps the point from the source image to the network input
+	#[inline]
+	pub fn to_blob_point(&self, pt: Point2f) -> Point2f {
+		Point2f::new(pt.x * self.scale.0 + self.offset.0, pt.y * self.scale.1 + self.offset.1)
+	}
+
+	/// Maps the point from the network input to the source image
+	#[inline]
+	pub fn to_source_point(&self, pt: Point2f) -> Point2f {
+		Point2f::new((pt.x - self.offset.0) / self.scale.0, (pt.y - self.offset.1) / self.scale.1)
+	}
+
+	/// Maps the rectangle from the network input to the source image clipping it to the image bounds
+	pub fn to_source_rect(&self, rect: Rect2f) -> Rect2f {
+		let (w, h) = (self.source_size.width as f32, self.source_size.height as f32);
+		let tl = self.to_source_point(rect.tl());
+		let br = self.to_source_point(rect.br());
+		let (x1, y1) = (tl.x.max(0.).min(w), tl.y.max(0.).min(h));
+		let (x2, y2) = (br.x.max(0.).min(w), br.y.max(0.).min(h));
+		Rect2f::new(x1, y1, x2 - x1, y2 - y1)
+	}
+}
+
+fn unexpected_shape(what: &str, shape: &[usize]) -> Error {
+	Error::new(
+		core::StsUnmatchedSizes,
+		format!("Unexpected shape of the {}: {:?}", what, shape),
+	)
+}
+
+/// Splits the data into the rows of `row_len` elements, the last dimension of the tensor must be equal to `row_len`
+fn rows<'t>(tensor: &'t Tensor<f32>, row_len: usize, what: &str) -> Result<impl Iterator<Item = &'t [f32]>> {
+	if tensor.shape().last() == Some(&row_len) {
+		Ok(tensor.as_slice().chunks(row_len))
+	} else {
+		Err(unexpected_shape(what, tensor.shape()))
+	}
+}
+
+/// Index and value of the maximum score
+fn max_score(scores: &[f32]) -> (usize, f32) {
+	scores.iter().copied().enumerate().fold((0, f32::NEG_INFINITY), |best, cur| {
+		if cur.1 > best.1 {
+			cur
+		} else {
+			best
+		}
+	})
+}
+
+#[inline]
+fn sigmoid(x: f32) -> f32 {
+	1. / (1. + (-x).exp())
+}
+
+fn center_rect(cx: f32, cy: f32, w: f32, h: f32) -> Rect2f {
+	Rect2f::new(cx - w / 2., cy - h / 2., w, h)
+}
+
+/// Decodes the output of the SSD `DetectionOutput` layer
+///
+/// The output has the shape `[1, 1, N, 7]` with the rows of `[image_id, class_id, confidence, left, top, right, bottom]`, the
+/// coordinates are normalized to `0..1`. Detections with the confidence below `conf_threshold` are skipped.
+pub fn decode_ssd(out: &Tensor<f32>, input_size: Size, conf_threshold: f32) -> Result<Vec<Detection>> {
+	let (w, h) = (input_size.width as f32, input_size.height as f32);
+	Ok(rows(out, 7, "SSD output")?
+		.filter(|row| row[2] >= conf_threshold)
+		.map(|row| Detection {
+			class_id: row[1] as i32,
+			confidence: row[2],
+			rect: Rect2f::new(row[3] * w, row[4] * h, (row[5] - row[3]) * w, (row[6] - row[4]) * h),
+			landmarks: vec![],
+		})
+		.collect())
+}
+
+/// Output layout of a YOLO network
+///
+/// Anchors are specified in the network input pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub enum YoloLayout {
+	/// YOLOv3 and v4 Darknet models, output of the `Region` layer with the rows of `[cx, cy, w, h, objectness, class scores...]`
+	/// normalized to `0..1`, the class scores are already multiplied by the objectness
+	Region,
+	/// YOLOv5, v6 and v7 exported together with the detection head, `[1, N, 5 + classes]` with the rows of
+	/// `[cx, cy, w, h, objectness, class scores...]` in pixels
+	Rows,
+	/// YOLOv8 anchor-free head, `[1, 4 + classes, N]` with the columns of `[cx, cy, w, h, class scores...]` in pixels
+	AnchorFree,
+	/// Raw YOLOv3 and v4 grid head with logits, `[1, anchors * (5 + classes), H, W]`
+	///
+	/// The boxes are decoded as `(sigmoid(t) + cell) * stride` and `anchor * exp(t)`.
+	GridV3 { stride: f32, anchors: Vec<(f32, f32)> },
+	/// Raw YOLOv5 and v7 grid head with logits, `[1, anchors, H, W, 5 + classes]` or `[1, anchors * (5 + classes), H, W]`
+	///
+	/// The boxes are decoded as `(2 * sigmoid(t) - 0.5 + cell) * stride` and `anchor * (2 * sigmoid(t))^2`.
+	GridV5 { stride: f32, anchors: Vec<(f32, f32)> },
+}
+
+/// Decodes the output of a YOLO network, call it for each output of the multi-output networks
+///
+/// The confidence of the detection is the class score multiplied by the objectness (where the layout has it). Detections with
+/// the confidence below `conf_threshold` are skipped.
+pub fn decode_yolo(out: &Tensor<f32>, layout: &YoloLayout, input_size: Size, conf_threshold: f32) -> Result<Vec<Detection>> {
+	let shape = out.shape();
+	let mut res = vec![];
+	match layout {
+		YoloLayout::Region | YoloLayout::Rows => {
+			let row_len = match shape.last() {
+				Some(&len) if len > 5 => len,
+				_ => return Err(unexpected_shape("YOLO output", shape)),
+			};
+			let (scale_x, scale_y) = if *layout == YoloLayout::Region {
+				(input_size.width as f32, input_size.height as f32)
+			} else {
+				(1., 1.)
+			};
+			for row in rows(out, row_len, "YOLO output")? {
+				let objectness = row[4];
+				if objectness < conf_threshold {
+					continue;
+				}
+				let (class_id, score) = max_score(&row[5..]);
+				let confidence = if *layout == YoloLayout::Region {
+					score
+				} else {
+					score * objectness
+				};
+				if confidence >= conf_threshold {
+					res.push(Detection {
+						class_id: class_id as i32,
+						confidence,
+						rect: center_rect(row[0] * scale_x, row[1] * scale_y, row[2] * scale_x, row[3] * scale_y),
+						landmarks: vec![],
+					});
+				}
+			}
+		}
+		YoloLayout::AnchorFree => {
+			let (channels, count) = match *shape {
+				[1, channels, count] if channels > 4 => (channels, count),
+				_ => return Err(unexpected_shape("YOLO output", shape)),
+			};
+			let data = out.as_slice();
+			let at = |channel: usize, i: usize| data[channel * count + i];
+			let mut scores = vec![0.; channels - 4];
+			for i in 0..count {
+				for (class_id, score) in scores.iter_mut().enumerate() {
+					*score = at(class_id + 4, i);
+				}
+				let (class_id, confidence) = max_score(&scores);
+				if confidence >= conf_threshold {
+					res.push(Detection {
+						class_id: class_id as i32,
+						confidence,
+						rect: center_rect(at(0, i), at(1, i), at(2, i), at(3, i)),
+						landmarks: vec![],
+					});
+				}
+			}
+		}
+		YoloLayout::GridV3 { stride, anchors } | YoloLayout::GridV5 { stride, anchors } => {
+			let v5 = matches!(layout, YoloLayout::GridV5 { .. });
+			let anchor_count = anchors.len();
+			// (attributes, height, width, channels last)
+			let (attrs, grid_h, grid_w, channels_last) = match *shape {
+				[1, a, h, w, attrs] if a == anchor_count && attrs > 5 => (attrs, h, w, true),
+				[1, c, h, w] if anchor_count > 0 && c % anchor_count == 0 && c / anchor_count > 5 => (c / anchor_count, h, w, false),
+				_ => return Err(unexpected_shape("YOLO grid output", shape)),
+			};
+			let data = out.as_slice();
+			let at = |a: usize, attr: usize, y: usize, x: usize| {
+				if channels_last {
+					data[((a * grid_h + y) * grid_w + x) * attrs + attr]
+				} else {
+					data[((a * attrs + attr) * grid_h + y) * grid_w + x]
+				}
+			};
+			let mut scores = vec![0.; attrs - 5];
+			for (a, &(anchor_w, anchor_h)) in anchors.iter().enumerate() {
+				for y in 0..grid_h {
+					for x in 0..grid_w {
+						let objectness = sigmoid(at(a, 4, y, x));
+						if objectness < conf_threshold {
+							continue;
+						}
+						for (class_id, score) in scores.iter_mut().enumerate() {
+							*score = at(a, class_id + 5, y, x);
+						}
+						let (class_id, logit) = max_score(&scores);
+						let confidence = sigmoid(logit) * objectness;
+						if confidence < conf_threshold {
+							continue;
+						}
+						let (tx, ty, tw, th) = (at(a, 0, y, x), at(a, 1, y, x), at(a, 2, y, x), at(a, 3, y, x));
+						let rect = if v5 {
+							center_rect(
+								(2. * sigmoid(tx) - 0.5 + x as f32) * stride,
+								(2. * sigmoid(ty) - 0.5 + y as f32) * stride,
+								anchor_w * (2. * sigmoid(tw)).powi(2),
+								anchor_h * (2. * sigmoid(th)).powi(2),
+							)
+						} else {
+							center_rect(
+								(sigmoid(tx) + x as f32) * stride,
+								(sigmoid(ty) + y as f32) * stride,
+								anchor_w * tw.exp(),
+								anchor_h * th.exp(),
+							)
+						};
+						res.push(Detection {
+							class_id: class_id as i32,
+							confidence,
+							rect,
+							landmarks: vec![],
+						});
+					}
+				}
+			}
+		}
+	}
+	Ok(res)
+}
+
+/// Prior boxes of a RetinaFace network
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetinaFaceConfig {
+	/// Sizes of the prior boxes in pixels for each feature map
+	pub min_sizes: Vec<Vec<f32>>,
+	/// Strides of the feature maps
+	pub steps: Vec<f32>,
+	/// Scaling of the box center and size regression
+	pub variance: [f32; 2],
+}
+
+impl Default for RetinaFaceConfig {
+	/// Configuration of the original RetinaFace models
+	fn default() -> Self {
+		Self {
+			min_sizes: vec![vec![16., 32.], vec![64., 128.], vec![256., 512.]],
+			steps: vec![8., 16., 32.],
+			variance: [0.1, 0.2],
+		}
+	}
+}
+
+impl RetinaFaceConfig {
+	/// Prior boxes as `(cx, cy, w, h)` normalized to `0..1`
+	fn priors(&self, input_size: Size) -> Vec<(f32, f32, f32, f32)> {
+		let (w, h) = (input_size.width as f32, input_size.height as f32);
+		let mut out = vec![];
+		for (&step, min_sizes) in self.steps.iter().zip(&self.min_sizes) {
+			let (map_w, map_h) = ((w / step).ceil() as usize, (h / step).ceil() as usize);
+			for y in 0..map_h {
+				for x in 0..map_w {
+					for &min_size in min_sizes {
+						out.push((
+							(x as f32 + 0.5) * step / w,
+							(y as f32 + 0.5) * step / h,
+							min_size / w,
+							min_size / h,
+						));
+					}
+				}
+			}
+		}
+		out
+	}
+}
+
+/// Decodes the outputs of a RetinaFace network
+///
+/// The outputs are the box regression `loc` of shape `[1, N, 4]`, the class scores `conf` of shape `[1, N, 2]` (background and
+/// face) and optionally the 5 facial landmarks of shape `[1, N, 10]`, where `N` is the number of prior boxes produced by `config`
+/// for the `input_size`. Detections with the face score below `conf_threshold` are skipped.
+pub fn decode_retinaface(
+	loc: &Tensor<f32>,
+	conf: &Tensor<f32>,
+	landmarks: Option<&Tensor<f32>>,
+	input_size: Size,
+	config: &RetinaFaceConfig,
+	conf_threshold: f32,
+) -> Result<Vec<Detection>> {
+	let priors = config.priors(input_size);
+	let count = priors.len();
+	let check = |tensor: &Tensor<f32>, row_len: usize, what: &str| {
+		if tensor.len() == count * row_len && tensor.shape().last() == Some(&row_len) {
+			Ok(())
+		} else {
+			Err(unexpected_shape(what, tensor.shape()))
+		}
+	};
+	check(loc, 4, "RetinaFace box regression")?;
+	check(conf, 2, "RetinaFace scores")?;
+	if let Some(landmarks) = landmarks {
+		check(landmarks, 10, "RetinaFace landmarks")?;
+	}
+	let (w, h) = (input_size.width as f32, input_size.height as f32);
+	let [center_var, size_var] = config.variance;
+	let (loc, conf) = (loc.as_slice(), conf.as_slice());
+	let mut res = vec![];
+	for (i, &(pcx, pcy, pw, ph)) in priors.iter().enumerate() {
+		let confidence = conf[i * 2 + 1];
+		if confidence < conf_threshold {
+			continue;
+		}
+		let d = &loc[i * 4..i * 4 + 4];
+		let cx = pcx + d[0] * center_var * pw;
+		let cy = pcy + d[1] * center_var * ph;
+		let bw = pw * (d[2] * size_var).exp();
+		let bh = ph * (d[3] * size_var).exp();
+		let landmarks = landmarks.map_or_else(Vec::new, |landmarks| {
+			landmarks.as_slice()[i * 10..i * 10 + 10]
+				.chunks(2)
+				.map(|l| Point2f::new((pcx + l[0] * center_var * pw) * w, (pcy + l[1] * center_var * ph) * h))
+				.collect()
+		});
+		res.push(Detection {
+			class_id: 0,
+			confidence,
+			rect: center_rect(cx * w, cy * h, bw * w, bh * h),
+			landmarks,
+		});
+	}
+	Ok(res)
+}
+
+/// Outputs of a single stride of a SCRFD network
+#[derive(Clone, Copy, Debug)]
+pub struct ScrfdLevel<'t> {
+	pub stride: usize,
+	/// Face scores `[N, 1]`
+	pub scores: &'t Tensor<f32>,
+	/// Distances from the anchor center to the box sides `[N, 4]` in the units of stride
+	pub boxes: &'t Tensor<f32>,
+	/// Offsets of the 5 facial landmarks from the anchor center `[N, 10]` in the units of stride
+	pub landmarks: Option<&'t Tensor<f32>>,
+}
+
+/// Decodes the outputs of a SCRFD network
+///
+/// `N` for each level is the number of the feature map cells multiplied by `anchors_per_cell` (usually 2). Detections with the
+/// score below `conf_threshold` are skipped.
+pub fn decode_scrfd(
+	levels: &[ScrfdLevel],
+	input_size: Size,
+	anchors_per_cell: usize,
+	conf_threshold: f32,
+) -> Result<Vec<Detection>> {
+	let mut res = vec![];
+	for level in levels {
+		let stride = level.stride;
+		if stride == 0 {
+			return Err(Error::new(core::StsBadArg, "SCRFD stride must not be zero"));
+		}
+		let (map_w, map_h) = (input_size.width as usize / stride, input_size.height as usize / stride);
+		let count = map_w * map_h * anchors_per_cell;
+		let check = |tensor: &Tensor<f32>, row_len: usize, what: &str| {
+			if tensor.len() == count * row_len && tensor.shape().last() == Some(&row_len) {
+				Ok(())
+			} else {
+				Err(unexpected_shape(what, tensor.shape()))
+			}
+		};
+		check(level.scores, 1, "SCRFD scores")?;
+		check(level.boxes, 4, "SCRFD boxes")?;
+		if let Some(landmarks) = level.landmarks {
+			check(landmarks, 10, "SCRFD landmarks")?;
+		}
+		let stride = stride as f32;
+		let (scores, boxes) = (level.scores.as_slice(), level.boxes.as_slice());
+		for (i, &confidence) in scores.iter().enumerate() {
+			if confidence < conf_threshold {
+				continue;
+			}
+			let cell = i / anchors_per_cell;
+			let (cx, cy) = ((cell % map_w) as f32 * stride, (cell / map_w) as f32 * stride);
+			let d = &boxes[i * 4..i * 4 + 4];
+			let landmarks = level.landmarks.map_or_else(Vec::new, |landmarks| {
+				landmarks.as_slice()[i * 10..i * 10 + 10]
+					.chunks(2)
+					.map(|l| Point2f::new(cx + l[0] * stride, cy + l[1] * stride))
+					.collect()
+			});
+			res.push(Detection {
+				class_id: 0,
+				confidence,
+				rect: Rect2f::new(
+					cx - d[0] * stride,
+					cy - d[1] * stride,
+					(d[0] + d[2]) * stride,
+					(d[1] + d[3]) * stride,
+				),
+				landmarks,
+			});
+		}
+	}
+	Ok(res)
+}
+
+/// Runs `nms` separately for each class, returns the kept indices sorted by the descending score
+///
+/// `nms` receives the indices of the items of a single class and returns the indices of the kept ones relative to its argument.
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn by_class(
+	class_ids: impl Iterator<Item = i32>,
+	scores: &[f32],
+	mut nms: impl FnMut(&[usize]) -> Result<Vec<usize>>,
+) -> Result<Vec<usize>> {
+	let mut classes = BTreeMap::<i32, Vec<usize>>::new();
+	for (i, class_id) in class_ids.enumerate() {
+		classes.entry(class_id).or_default().push(i);
+	}
+	let mut out = vec![];
+	for items in classes.values() {
+		out.extend(nms(items)?.into_iter().map(|i| items[i]));
+	}
+	out.sort_by(|&a, &b| scores[b].partial_cmp(&scores[a]).unwrap_or(std::cmp::Ordering::Equal));
+	Ok(out)
+}
+
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn kept_indices(indices: &Vector<i32>) -> Vec<usize> {
+	indices.iter().map(|i| i as usize).collect()
+}
+
+/// Non-maximum suppression applied separately to each class using `nms_boxes()`
+///
+/// Returns the kept detections sorted by the descending confidence, at most `top_k` of them if it's not zero.
+#[cfg(not(ocvrs_opencv_branch_32))]
+pub fn nms_by_class(detections: &[Detection], score_threshold: f32, nms_threshold: f32, top_k: usize) -> Result<Vec<Detection>> {
+	let scores = detections.iter().map(|d| d.confidence).collect::<Vec<_>>();
+	let mut kept = by_class(detections.iter().map(|d| d.class_id), &scores, |items| {
+		let boxes = items
+			.iter()
+			.map(|&i| {
+				let r = detections[i].rect;
+				Rect2d::new(f64::from(r.x), f64::from(r.y), f64::from(r.width), f64::from(r.height))
+			})
+			.collect::<Vector<Rect2d>>();
+		let scores = items.iter().map(|&i| scores[i]).collect::<Vector<f32>>();
+		let mut indices = Vector::new();
+		crate::dnn::nms_boxes_f64(&boxes, &scores, score_threshold, nms_threshold, &mut indices, 1., 0)?;
+		Ok(kept_indices(&indices))
+	})?;
+	if top_k > 0 {
+		kept.truncate(top_k);
+	}
+	Ok(kept.into_iter().map(|i| detections[i].clone()).collect())
+}
+
+/// Soft non-maximum suppression applied separately to each class using `soft_nms_boxes()`
+///
+/// The confidence of the kept detections is updated by the soft suppression, they are returned sorted by the descending updated
+/// confidence. The bounding boxes are rounded to the integer pixels for the suppression.
+#[cfg(ocvrs_opencv_branch_4)]
+pub fn soft_nms_by_class(
+	detections: &[Detection],
+	score_threshold: f32,
+	nms_threshold: f32,
+	sigma: f32,
+	method: SoftNMSMethod,
+) -> Result<Vec<Detection>> {
+	let mut updated = detections.iter().map(|d| d.confidence).collect::<Vec<_>>();
+	let scores = updated.clone();
+	by_class(detections.iter().map(|d| d.class_id), &scores, |items| {
+		let boxes = items
+			.iter()
+			.map(|&i| {
+				let r = detections[i].rect;
+				Rect::new(
+					r.x.round() as i32,
+					r.y.round() as i32,
+					r.width.round() as i32,
+					r.height.round() as i32,
+				)
+			})
+			.collect::<Vector<Rect>>();
+		let class_scores = items.iter().map(|&i| scores[i]).collect::<Vector<f32>>();
+		let mut updated_scores = Vector::new();
+		let mut indices = Vector::new();
+		crate::dnn::soft_nms_boxes(
+			&boxes,
+			&class_scores,
+			&mut updated_scores,
+			score_threshold,
+			nms_threshold,
+			&mut indices,
+			0,
+			sigma,
+			method,
+		)?;
+		let kept = kept_indices(&indices);
+		// updated scores correspond to the kept indices
+		for (&i, score) in kept.iter().zip(updated_scores) {
+			updated[items[i]] = score;
+		}
+		Ok(kept)
+	})
+	.map(|mut kept| {
+		kept.sort_by(|&a, &b| updated[b].partial_cmp(&updated[a]).unwrap_or(std::cmp::Ordering::Equal));
+		kept
+			.into_iter()
+			.map(|i| Detection {
+				confidence: updated[i],
+				..detections[i].clone()
+			})
+			.collect()
+	})
+}
+
+/// Non-maximum suppression of the rotated boxes applied separately to each class using `nms_boxes_rotated()`
+///
+/// Returns the indices of the kept boxes sorted by the descending score.
+#[cfg(not(ocvrs_opencv_branch_32))]
+pub fn nms_rotated_by_class(
+	boxes: &Vector<RotatedRect>,
+	scores: &[f32],
+	class_ids: &[i32],
+	score_threshold: f32,
+	nms_threshold: f32,
+) -> Result<Vec<usize>> {
+	if boxes.len() != scores.len() || boxes.len() != class_ids.len() {
+		return Err(Error::new(
+			core::StsUnmatchedSizes,
+			format!(
+				"Number of boxes: {}, scores: {} and class ids: {} must be equal",
+				boxes.len(),
+				scores.len(),
+				class_ids.len()
+			),
+		));
+	}
+	by_class(class_ids.iter().copied(), scores, |items| {
+		let class_boxes = items.iter().map(|&i| boxes.get(i)).collect::<Result<Vector<RotatedRect>>>()?;
+		let class_scores = items.iter().map(|&i| scores[i]).collect::<Vector<f32>>();
+		let mut indices = Vector::new();
+		crate::dnn::nms_boxes_rotated(
+			&class_boxes,
+			&class_scores,
+			score_threshold,
+			nms_threshold,
+			&mut indices,
+			1.,
+			0,
+		)?;
+		Ok(kept_indices(&indices))
+	})
+}
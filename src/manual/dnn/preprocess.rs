@@ -0,0 +1,397 @@
+use crate::{
+	core::{self, Mat, Point, Point2f, Rect, Rect2f, Scalar, Size, Vector},
+	dnn::{
+		self,
+		postprocess::{BlobMapping, ResizeMode},
+	},
+	imgproc,
+	prelude::*,
+	Error, Result,
+};
+
+/// Memory layout of the blob produced by [Preprocessor]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlobLayout {
+	/// `[N, C, H, W]`, the layout of `blob_from_image()`
+	Nchw,
+	/// `[N, H, W, C]`, used by the TensorFlow and TFLite models
+	Nhwc,
+}
+
+impl Default for BlobLayout {
+	fn default() -> Self {
+		Self::Nchw
+	}
+}
+
+/// Element type of the blob produced by [Preprocessor]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BlobDepth {
+	F32,
+	/// Half-precision float, requires OpenCV 4
+	F16,
+	/// Values are rounded and saturated
+	U8,
+}
+
+impl Default for BlobDepth {
+	fn default() -> Self {
+		Self::F32
+	}
+}
+
+/// Coordinates that can be mapped by a [Transform]
+pub trait TransformCoords: Sized {
+	fn to_source(self, mapping: &BlobMapping) -> Self;
+	fn to_blob(self, mapping: &BlobMapping) -> Self;
+}
+
+impl TransformCoords for Point2f {
+	#[inline]
+	fn to_source(self, mapping: &BlobMapping) -> Self {
+		mapping.to_source_point(self)
+	}
+
+	#[inline]
+	fn to_blob(self, mapping: &BlobMapping) -> Self {
+		mapping.to_blob_point(self)
+	}
+}
+
+impl TransformCoords for Point {
+	fn to_source(self, mapping: &BlobMapping) -> Self {
+		let pt = mapping.to_source_point(Point2f::new(self.x as f32, self.y as f32));
+		Point::new(pt.x.round() as i32, pt.y.round() as i32)
+	}
+
+	fn to_blob(self, mapping: &BlobMapping) -> Self {
+		let pt = mapping.to_blob_point(Point2f::new(self.x as f32, self.y as f32));
+		Point::new(pt.x.round() as i32, pt.y.round() as i32)
+	}
+}
+
+/// The rectangles mapped to the source image are clipped to its bounds
+impl TransformCoords for Rect2f {
+	#[inline]
+	fn to_source(self, mapping: &BlobMapping) -> Self {
+		mapping.to_source_rect(self)
+	}
+
+	fn to_blob(self, mapping: &BlobMapping) -> Self {
+		Rect2f::from_points(mapping.to_blob_point(self.tl()), mapping.to_blob_point(self.br()))
+	}
+}
+
+fn round_rect(rect: Rect2f) -> Rect {
+	let (x, y) = (rect.x.round() as i32, rect.y.round() as i32);
+	Rect::new(
+		x,
+		y,
+		(rect.x + rect.width).round() as i32 - x,
+		(rect.y + rect.height).round() as i32 - y,
+	)
+}
+
+/// The rectangles mapped to the source image are clipped to its bounds
+impl TransformCoords for Rect {
+	fn to_source(self, mapping: &BlobMapping) -> Self {
+		let rect = Rect2f::new(self.x as f32, self.y as f32, self.width as f32, self.height as f32);
+		round_rect(rect.to_source(mapping))
+	}
+
+	fn to_blob(self, mapping: &BlobMapping) -> Self {
+		let rect = Rect2f::new(self.x as f32, self.y as f32, self.width as f32, self.height as f32);
+		round_rect(rect.to_blob(mapping))
+	}
+}
+
+#[cfg(ocvrs_opencv_branch_4)]
+fn to_f16(blob: &Mat) -> Result<Mat> {
+	let mut out = Mat::default();
+	blob.convert_to(&mut out, core::CV_16F, 1., 0.)?;
+	Ok(out)
+}
+
+#[cfg(not(ocvrs_opencv_branch_4))]
+fn to_f16(_blob: &Mat) -> Result<Mat> {
+	Err(Error::new(core::StsNotImplemented, "Half-precision blobs require OpenCV 4"))
+}
+
+/// Geometric transformation applied to an image by [Preprocessor]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Transform {
+	mapping: BlobMapping,
+}
+
+impl Transform {
+	/// Mapping usable with [Detection::to_source](crate::dnn::postprocess::Detection::to_source)
+	#[inline]
+	pub fn mapping(&self) -> &BlobMapping {
+		&self.mapping
+	}
+
+	#[inline]
+	pub fn source_size(&self) -> Size {
+		self.mapping.source_size()
+	}
+
+	/// Maps the point or the rectangle from the blob to the source image
+	#[inline]
+	pub fn to_source<T: TransformCoords>(&self, coords: T) -> T {
+		coords.to_source(&self.mapping)
+	}
+
+	/// Maps the point or the rectangle from the source image to the blob
+	#[inline]
+	pub fn to_blob<T: TransformCoords>(&self, coords: T) -> T {
+		coords.to_blob(&self.mapping)
+	}
+}
+
+/// Builder of the network input blobs, a more flexible replacement for `blob_from_image()` and `blob_from_images()`
+///
+/// Each image is resized to the target size using the [ResizeMode], its channels are optionally swapped from BGR to RGB and the
+/// values are normalized as `(pixel * scale - mean) / std` for each channel. The `mean` and `std` are specified in the channel
+/// order of the blob, i.e. after swapping.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{core::{Scalar, Size}, dnn::{postprocess::ResizeMode, Preprocessor}, imgcodecs};
+///
+/// // ImageNet normalization
+/// let preprocessor = Preprocessor::new(Size::new(224, 224))
+///     .resize_mode(ResizeMode::Crop)
+///     .swap_rb(true)
+///     .scale(1. / 255.)
+///     .mean(Scalar::new(0.485, 0.456, 0.406, 0.))
+///     .std(Scalar::new(0.229, 0.224, 0.225, 1.));
+/// let image = imgcodecs::imread("cat.jpg", imgcodecs::IMREAD_COLOR)?;
+/// let (blob, transform) = preprocessor.process(&image)?;
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preprocessor {
+	size: Size,
+	resize_mode: ResizeMode,
+	pad_color: Scalar,
+	interpolation: i32,
+	scale: f64,
+	mean: Scalar,
+	std: Scalar,
+	swap_rb: bool,
+	layout: BlobLayout,
+	depth: BlobDepth,
+}
+
+impl Preprocessor {
+	/// Creates the preprocessor producing the blobs with images of `size`, by default the image is stretched and its values are
+	/// copied unchanged into a `f32` NCHW blob
+	pub fn new(size: Size) -> Self {
+		Self {
+			size,
+			resize_mode: ResizeMode::Stretch,
+			pad_color: Scalar::all(0.),
+			interpolation: imgproc::INTER_LINEAR,
+			scale: 1.,
+			mean: Scalar::all(0.),
+			std: Scalar::all(1.),
+			swap_rb: false,
+			layout: BlobLayout::Nchw,
+			depth: BlobDepth::F32,
+		}
+	}
+
+	pub fn resize_mode(mut self, resize_mode: ResizeMode) -> Self {
+		self.resize_mode = resize_mode;
+		self
+	}
+
+	/// Color of the padding for [ResizeMode::Letterbox], in the channel order of the source image
+	pub fn pad_color(mut self, pad_color: Scalar) -> Self {
+		self.pad_color = pad_color;
+		self
+	}
+
+	/// Interpolation method of `resize()`, `INTER_LINEAR` by default
+	pub fn interpolation(mut self, interpolation: i32) -> Self {
+		self.interpolation = interpolation;
+		self
+	}
+
+	pub fn scale(mut self, scale: f64) -> Self {
+		self.scale = scale;
+		self
+	}
+
+	pub fn mean(mut self, mean: Scalar) -> Self {
+		self.mean = mean;
+		self
+	}
+
+	pub fn std(mut self, std: Scalar) -> Self {
+		self.std = std;
+		self
+	}
+
+	/// Convert BGR images to RGB
+	pub fn swap_rb(mut self, swap_rb: bool) -> Self {
+		self.swap_rb = swap_rb;
+		self
+	}
+
+	pub fn layout(mut self, layout: BlobLayout) -> Self {
+		self.layout = layout;
+		self
+	}
+
+	pub fn depth(mut self, depth: BlobDepth) -> Self {
+		self.depth = depth;
+		self
+	}
+
+	/// Creates the blob with a single image
+	pub fn process(&self, image: &Mat) -> Result<(Mat, Transform)> {
+		let (blob, mut transforms) = self.process_batch(&[image])?;
+		let transform = transforms
+			.pop()
+			.ok_or_else(|| Error::new(core::StsError, "Missing transform of the image"))?;
+		Ok((blob, transform))
+	}
+
+	/// Creates the blob with a batch of images, returns it together with the transform of each image
+	pub fn process_batch(&self, images: &[&Mat]) -> Result<(Mat, Vec<Transform>)> {
+		if images.is_empty() {
+			return Err(Error::new(core::StsBadArg, "Can't create a blob from an empty batch"));
+		}
+		if self.size.width <= 0 || self.size.height <= 0 {
+			return Err(Error::new(core::StsBadArg, format!("Invalid target size: {:?}", self.size)));
+		}
+		let mut prepared = Vector::<Mat>::with_capacity(images.len());
+		let mut transforms = Vec::with_capacity(images.len());
+		for image in images {
+			let (image, transform) = self.prepare(image)?;
+			prepared.push(image);
+			transforms.push(transform);
+		}
+		let channels = prepared.get(0)?.channels();
+		if let Some(mismatch) = prepared.iter().map(|image| image.channels()).find(|&ch| ch != channels) {
+			return Err(Error::new(
+				core::StsUnmatchedSizes,
+				format!(
+					"All images in the batch must have the same number of channels, got: {} and {}",
+					channels, mismatch
+				),
+			));
+		}
+		let blob = match self.layout {
+			BlobLayout::Nchw => dnn::blob_from_images(&prepared, 1., self.size, Scalar::all(0.), false, false, core::CV_32F)?,
+			BlobLayout::Nhwc => {
+				let mut blob = Mat::new_nd_with_default(
+					&[prepared.len() as i32, self.size.height, self.size.width, channels],
+					core::CV_32F,
+					Scalar::all(0.),
+				)?;
+				let data = blob.data_typed_mut::<f32>()?;
+				for (image, out) in prepared.iter().zip(data.chunks_mut(data.len() / images.len())) {
+					let image = image.reshape(1, 0)?;
+					out.copy_from_slice(image.data_typed::<f32>()?);
+				}
+				blob
+			}
+		};
+		let blob = match self.depth {
+			BlobDepth::F32 => blob,
+			BlobDepth::F16 => to_f16(&blob)?,
+			BlobDepth::U8 => {
+				let mut out = Mat::default();
+				blob.convert_to(&mut out, core::CV_8U, 1., 0.)?;
+				out
+			}
+		};
+		Ok((blob, transforms))
+	}
+
+	/// Resizes, converts and normalizes the image, the result is a continuous `f32` image of the target size
+	fn prepare(&self, image: &Mat) -> Result<(Mat, Transform)> {
+		let source_size = image.size()?;
+		if source_size.width <= 0 || source_size.height <= 0 {
+			return Err(Error::new(core::StsBadArg, "Can't create a blob from an empty image"));
+		}
+		let (w, h) = (source_size.width as f64, source_size.height as f64);
+		let (tw, th) = (self.size.width, self.size.height);
+		let mut resized = Mat::default();
+		let mapping = match self.resize_mode {
+			ResizeMode::Stretch => {
+				imgproc::resize(image, &mut resized, self.size, 0., 0., self.interpolation)?;
+				BlobMapping::from_scale_offset(source_size, (tw as f32 / w as f32, th as f32 / h as f32), (0., 0.))
+			}
+			ResizeMode::Crop => {
+				let scale = (f64::from(tw) / w).max(f64::from(th) / h);
+				let scaled = Size::new(((w * scale).round() as i32).max(tw), ((h * scale).round() as i32).max(th));
+				let mut full = Mat::default();
+				imgproc::resize(image, &mut full, scaled, 0., 0., self.interpolation)?;
+				let (left, top) = ((scaled.width - tw) / 2, (scaled.height - th) / 2);
+				Mat::roi(&full, Rect::new(left, top, tw, th))?.copy_to(&mut resized)?;
+				BlobMapping::from_scale_offset(
+					source_size,
+					(scaled.width as f32 / w as f32, scaled.height as f32 / h as f32),
+					(-left as f32, -top as f32),
+				)
+			}
+			ResizeMode::Letterbox | ResizeMode::LetterboxTopLeft => {
+				let scale = (f64::from(tw) / w).min(f64::from(th) / h);
+				let scaled = Size::new(
+					((w * scale).round() as i32).clamp(1, tw),
+					((h * scale).round() as i32).clamp(1, th),
+				);
+				let mut fitted = Mat::default();
+				imgproc::resize(image, &mut fitted, scaled, 0., 0., self.interpolation)?;
+				let (left, top) = if self.resize_mode == ResizeMode::Letterbox {
+					((tw - scaled.width) / 2, (th - scaled.height) / 2)
+				} else {
+					(0, 0)
+				};
+				core::copy_make_border(
+					&fitted,
+					&mut resized,
+					top,
+					th - scaled.height - top,
+					left,
+					tw - scaled.width - left,
+					core::BORDER_CONSTANT,
+					self.pad_color,
+				)?;
+				BlobMapping::from_scale_offset(
+					source_size,
+					(scaled.width as f32 / w as f32, scaled.height as f32 / h as f32),
+					(left as f32, top as f32),
+				)
+			}
+		};
+		let channels = resized.channels();
+		let swapped = if self.swap_rb && (channels == 3 || channels == 4) {
+			let code = if channels == 3 {
+				imgproc::COLOR_BGR2RGB
+			} else {
+				imgproc::COLOR_BGRA2RGBA
+			};
+			let mut out = Mat::default();
+			imgproc::cvt_color(&resized, &mut out, code, 0)?;
+			out
+		} else {
+			resized
+		};
+		let mut out = Mat::default();
+		swapped.convert_to(&mut out, core::CV_MAKETYPE(core::CV_32F, channels), self.scale, 0.)?;
+		let channels = channels as usize;
+		let (mean, std) = (self.mean, self.std);
+		// single channel view of the same data
+		let mut values = out.reshape(1, 0)?;
+		for pixel in values.data_typed_mut::<f32>()?.chunks_mut(channels) {
+			for (c, val) in pixel.iter_mut().enumerate().take(4) {
+				*val = ((f64::from(*val) - mean[c]) / std[c]) as f32;
+			}
+		}
+		Ok((out, Transform { mapping }))
+	}
+}
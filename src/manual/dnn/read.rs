@@ -0,0 +1,136 @@
+use std::io::Read;
+
+use crate::{
+	core,
+	dnn::{self, Net},
+	Error, Result,
+};
+
+/// Framework of a model for [Net::from_reader]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Framework {
+	/// Detect the framework from the leading bytes of the model, see [Framework::detect]
+	Auto,
+	Onnx,
+	TensorFlow,
+	/// Binary `.caffemodel`, the `.prototxt` must be passed separately to [Net::from_caffe_bytes]
+	Caffe,
+	/// Binary `.weights`, the `.cfg` must be passed separately to [Net::from_darknet_bytes]
+	Darknet,
+}
+
+impl Default for Framework {
+	fn default() -> Self {
+		Self::Auto
+	}
+}
+
+/// Reads a protobuf varint from the start of `data`, returns the value and the number of bytes it took
+fn read_varint(data: &[u8]) -> Option<(u64, usize)> {
+	let mut out = 0;
+	for (i, &b) in data.iter().enumerate().take(10) {
+		out |= u64::from(b & 0x7F) << (7 * i);
+		if b & 0x80 == 0 {
+			return Some((out, i + 1));
+		}
+	}
+	None
+}
+
+impl Framework {
+	/// Guesses the framework of the binary model from its first bytes, returns `None` if the format is not recognized
+	///
+	/// Recognized are ONNX models (start with the `ir_version` field), TensorFlow frozen graphs (start with a `NodeDef`), Caffe
+	/// models (start with the net name or with the layers) and Darknet weights (start with the zero major version). The detection
+	/// is heuristic, at least 16 bytes are needed for reliable results.
+	pub fn detect(data: &[u8]) -> Option<Self> {
+		match *data {
+			// ModelProto.ir_version, field 1, varint
+			[0x08, ..] => Some(Self::Onnx),
+			// NetParameter.layers (V1), field 2, or NetParameter.layer, field 100
+			[0x12, ..] | [0xA2, 0x06, ..] => Some(Self::Caffe),
+			// GraphDef.node or NetParameter.name, field 1, length-delimited
+			[0x0A, ref rest @ ..] => {
+				let (len, len_size) = read_varint(rest)?;
+				let payload = &rest[len_size..];
+				// NodeDef.name, field 1, length-delimited
+				if payload.first() == Some(&0x0A) {
+					Some(Self::TensorFlow)
+				} else if len > 0 && payload.iter().take(len as usize).all(|c| c.is_ascii_graphic() || *c == b' ') {
+					Some(Self::Caffe)
+				} else {
+					None
+				}
+			}
+			// int32 major = 0, minor, revision of the weights file header
+			[0, 0, 0, 0, minor, 0, 0, 0, ..] if minor <= 2 => Some(Self::Darknet),
+			_ => None,
+		}
+	}
+}
+
+impl Net {
+	/// Reads an ONNX model from memory
+	#[cfg(not(ocvrs_opencv_branch_32))]
+	pub fn from_onnx_bytes(model: &[u8]) -> Result<Self> {
+		dnn::read_net_from_onnx_slice(model)
+	}
+
+	/// Reads a TensorFlow frozen graph from memory, `config` is the optional text graph definition, pass an empty slice to skip it
+	pub fn from_tensorflow_bytes(model: &[u8], config: &[u8]) -> Result<Self> {
+		dnn::read_net_from_tensorflow_slice(model, config)
+	}
+
+	/// Reads a Caffe model from memory, `model` is the optional `.caffemodel` with the learned weights, pass an empty slice to skip
+	/// it
+	pub fn from_caffe_bytes(prototxt: &[u8], model: &[u8]) -> Result<Self> {
+		dnn::read_net_from_caffe_slice(prototxt, model)
+	}
+
+	/// Reads a Darknet model from memory, `model` is the optional `.weights` file, pass an empty slice to skip it
+	#[cfg(not(ocvrs_opencv_branch_32))]
+	pub fn from_darknet_bytes(cfg: &[u8], model: &[u8]) -> Result<Self> {
+		dnn::read_net_from_darknet_slice(cfg, model)
+	}
+
+	/// Reads a single-file model from `reader`
+	///
+	/// Only ONNX and TensorFlow models can be read this way, Caffe and Darknet also need a network definition, use
+	/// [Net::from_caffe_bytes] and [Net::from_darknet_bytes] for them.
+	///
+	/// ## Example
+	/// ```no_run
+	/// use std::fs::File;
+	/// use opencv::dnn::{Framework, Net};
+	///
+	/// let net = Net::from_reader(File::open("model.onnx").unwrap(), Framework::Auto)?;
+	/// # Ok::<_, opencv::Error>(())
+	/// ```
+	pub fn from_reader(mut reader: impl Read, framework: Framework) -> Result<Self> {
+		let mut model = vec![];
+		reader
+			.read_to_end(&mut model)
+			.map_err(|e| Error::new(core::StsError, format!("Can't read model: {}", e)))?;
+		let framework = match framework {
+			Framework::Auto => Framework::detect(&model)
+				.ok_or_else(|| Error::new(core::StsUnsupportedFormat, "Can't detect the framework of the model"))?,
+			framework => framework,
+		};
+		match framework {
+			#[cfg(not(ocvrs_opencv_branch_32))]
+			Framework::Onnx => Self::from_onnx_bytes(&model),
+			Framework::TensorFlow => Self::from_tensorflow_bytes(&model, &[]),
+			Framework::Caffe | Framework::Darknet => Err(Error::new(
+				core::StsBadArg,
+				format!(
+					"{:?} model needs a separate network definition and can't be read from a single reader",
+					framework
+				),
+			)),
+			_ => Err(Error::new(
+				core::StsNotImplemented,
+				format!("Reading {:?} models is not supported by this OpenCV version", framework),
+			)),
+		}
+	}
+}
@@ -0,0 +1,239 @@
+use std::{
+	cmp,
+	ffi::{c_void, CString},
+	fmt,
+	marker::PhantomData,
+	mem::ManuallyDrop,
+	os::raw::c_char,
+	slice,
+};
+
+use crate::{
+	core::{Mat, Vector},
+	dnn::{LayerParams, MatShape},
+	platform_types::size_t,
+	prelude::*,
+	sys,
+	types::Unit,
+	Result,
+};
+
+/// Custom DNN layer implemented in Rust
+///
+/// Register it with [register_layer] to make the model importers use it for the layers of the specified type.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/dc/db1/tutorial_dnn_custom_layers.html)
+pub trait RustLayer: Send + 'static {
+	/// Creates the layer instance from the parameters read from the model file
+	fn new(params: &LayerParams) -> Result<Self>
+	where
+		Self: Sized;
+
+	/// Calculates the output shapes from the input shapes
+	///
+	/// The default implementation makes every output the same shape as the first input (the same as the C++ version does).
+	/// Return `true` if the layer can work in-place.
+	fn get_memory_shapes(
+		&self,
+		inputs: &Vector<MatShape>,
+		required_outputs: i32,
+		outputs: &mut Vector<MatShape>,
+		_internals: &mut Vector<MatShape>,
+	) -> Result<bool> {
+		outputs.clear();
+		for _ in 0..cmp::max(required_outputs as usize, inputs.len()) {
+			outputs.push(inputs.get(0)?);
+		}
+		Ok(false)
+	}
+
+	/// Called once the input shapes are known and the output blobs are allocated
+	fn finalize(&mut self, _inputs: &[Mat], _outputs: &mut [Mat]) -> Result<()> {
+		Ok(())
+	}
+
+	/// Calculates the outputs from the inputs
+	///
+	/// `outputs` are already allocated with the shapes returned by `get_memory_shapes()`. It's preferable to write the result into
+	/// the existing data, if an output is replaced with another `Mat` its contents is copied into the output blob.
+	fn forward(&mut self, inputs: &[Mat], outputs: &mut [Mat]) -> Result<()>;
+}
+
+#[repr(C)]
+struct Vtable {
+	create: unsafe extern "C" fn(*mut c_void, *mut sys::Result<*mut c_void>),
+	drop: unsafe extern "C" fn(*mut c_void),
+	get_memory_shapes: unsafe extern "C" fn(*const c_void, *const c_void, i32, *mut c_void, *mut c_void, *mut sys::Result<bool>),
+	finalize: MatsFn,
+	forward: MatsFn,
+}
+
+type MatsFn = unsafe extern "C" fn(*mut c_void, *const *mut c_void, size_t, *mut *mut c_void, size_t, *mut sys::Result_void);
+
+impl Vtable {
+	fn new<L: RustLayer>() -> Self {
+		Self {
+			create: vtable_create::<L>,
+			drop: vtable_drop::<L>,
+			get_memory_shapes: vtable_get_memory_shapes::<L>,
+			finalize: vtable_finalize::<L>,
+			forward: vtable_forward::<L>,
+		}
+	}
+}
+
+unsafe extern "C" fn vtable_create<L: RustLayer>(params: *mut c_void, ocvrs_return: *mut sys::Result<*mut c_void>) {
+	let params = ManuallyDrop::new(LayerParams::from_raw(params));
+	ocvrs_return.write(sys::Result::from_callback(|| {
+		L::new(&params).map(|layer| Box::into_raw(Box::new(layer)) as *mut c_void)
+	}));
+}
+
+unsafe extern "C" fn vtable_drop<L: RustLayer>(instance: *mut c_void) {
+	drop(Box::from_raw(instance as *mut L));
+}
+
+unsafe extern "C" fn vtable_get_memory_shapes<L: RustLayer>(
+	instance: *const c_void,
+	inputs: *const c_void,
+	required_outputs: i32,
+	outputs: *mut c_void,
+	internals: *mut c_void,
+	ocvrs_return: *mut sys::Result<bool>,
+) {
+	let instance = &*(instance as *const L);
+	let inputs = ManuallyDrop::new(Vector::<MatShape>::from_raw(inputs as *mut c_void));
+	let mut outputs = ManuallyDrop::new(Vector::<MatShape>::from_raw(outputs));
+	let mut internals = ManuallyDrop::new(Vector::<MatShape>::from_raw(internals));
+	ocvrs_return.write(sys::Result::from_callback(|| {
+		instance.get_memory_shapes(&inputs, required_outputs, &mut outputs, &mut internals)
+	}));
+}
+
+/// Calls `f` with the `Mat`s passed from C++, the inputs are borrowed and the outputs are owned for the duration of the call
+unsafe fn with_mats(
+	inputs: *const *mut c_void,
+	inputs_len: size_t,
+	outputs: *mut *mut c_void,
+	outputs_len: size_t,
+	ocvrs_return: *mut sys::Result_void,
+	f: impl FnOnce(&[Mat], &mut [Mat]) -> Result<()>,
+) {
+	// ManuallyDrop is repr(transparent) so the slice can be safely reinterpreted
+	let inputs = slice::from_raw_parts(inputs, inputs_len)
+		.iter()
+		.map(|&ptr| ManuallyDrop::new(Mat::from_raw(ptr)))
+		.collect::<Vec<_>>();
+	let inputs = slice::from_raw_parts(inputs.as_ptr() as *const Mat, inputs.len());
+	let outputs = slice::from_raw_parts_mut(outputs, outputs_len);
+	let mut output_mats = outputs.iter().map(|&ptr| Mat::from_raw(ptr)).collect::<Vec<_>>();
+	ocvrs_return.write(sys::Result::<Unit, ()>::from_callback(|| f(inputs, &mut output_mats)));
+	// give the ownership back to C++ even if the user code has failed
+	for (ptr, mat) in outputs.iter_mut().zip(output_mats) {
+		*ptr = mat.into_raw();
+	}
+}
+
+unsafe extern "C" fn vtable_finalize<L: RustLayer>(
+	instance: *mut c_void,
+	inputs: *const *mut c_void,
+	inputs_len: size_t,
+	outputs: *mut *mut c_void,
+	outputs_len: size_t,
+	ocvrs_return: *mut sys::Result_void,
+) {
+	let instance = &mut *(instance as *mut L);
+	with_mats(inputs, inputs_len, outputs, outputs_len, ocvrs_return, |inputs, outputs| {
+		instance.finalize(inputs, outputs)
+	});
+}
+
+unsafe extern "C" fn vtable_forward<L: RustLayer>(
+	instance: *mut c_void,
+	inputs: *const *mut c_void,
+	inputs_len: size_t,
+	outputs: *mut *mut c_void,
+	outputs_len: size_t,
+	ocvrs_return: *mut sys::Result_void,
+) {
+	let instance = &mut *(instance as *mut L);
+	with_mats(inputs, inputs_len, outputs, outputs_len, ocvrs_return, |inputs, outputs| {
+		instance.forward(inputs, outputs)
+	});
+}
+
+/// Registers the Rust implementation `L` for the layers of type `type_name`
+///
+/// After this call networks read from model files create `L` for each layer of the specified type. The registration is active
+/// while the returned guard is alive, the layers that are already created continue to work after the guard is dropped.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{core, dnn::{self, LayerParams, RustLayer}, prelude::*, Result};
+///
+/// struct Relu6;
+///
+/// impl RustLayer for Relu6 {
+///     fn new(_params: &LayerParams) -> Result<Self> {
+///         Ok(Self)
+///     }
+///
+///     fn forward(&mut self, inputs: &[Mat], outputs: &mut [Mat]) -> Result<()> {
+///         core::min(&inputs[0], &6., &mut outputs[0])?;
+///         Ok(())
+///     }
+/// }
+///
+/// let _registration = dnn::register_layer::<Relu6>("Relu6")?;
+/// let net = dnn::read_net_from_onnx("model.onnx")?;
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub fn register_layer<L: RustLayer>(type_name: &str) -> Result<LayerRegistration<L>> {
+	extern "C" {
+		fn cv_manual_dnn_registerRustLayer(typ: *const c_char, vtable: *const Vtable, ocvrs_return: *mut sys::Result_void);
+	}
+	let typ = CString::new(type_name)?;
+	let vtable = Vtable::new::<L>();
+	return_send!(via ocvrs_return);
+	unsafe { cv_manual_dnn_registerRustLayer(typ.as_ptr(), &vtable, ocvrs_return.as_mut_ptr()) };
+	return_receive!(unsafe ocvrs_return => ret);
+	ret.into_result().map(|_| LayerRegistration {
+		typ,
+		_d: PhantomData,
+	})
+}
+
+/// Guard that unregisters the Rust layer implementation when dropped, see [register_layer]
+///
+/// Use [std::mem::forget] to keep the layer registered for the lifetime of the process.
+#[must_use = "layer is unregistered immediately if the guard is not kept"]
+pub struct LayerRegistration<L: RustLayer> {
+	typ: CString,
+	_d: PhantomData<fn() -> L>,
+}
+
+impl<L: RustLayer> LayerRegistration<L> {
+	/// Layer type name that the implementation is registered for
+	pub fn type_name(&self) -> &str {
+		self.typ.to_str().expect("Layer type name was created from &str")
+	}
+}
+
+impl<L: RustLayer> Drop for LayerRegistration<L> {
+	fn drop(&mut self) {
+		extern "C" {
+			fn cv_manual_dnn_unregisterRustLayer(typ: *const c_char, ocvrs_return: *mut sys::Result_void);
+		}
+		return_send!(via ocvrs_return);
+		unsafe { cv_manual_dnn_unregisterRustLayer(self.typ.as_ptr(), ocvrs_return.as_mut_ptr()) };
+		return_receive!(unsafe ocvrs_return => ret);
+		// there is nothing sensible to do with the error in drop
+		let _ = ret.into_result();
+	}
+}
+
+impl<L: RustLayer> fmt::Debug for LayerRegistration<L> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("LayerRegistration").field("type_name", &self.typ).finish()
+	}
+}
@@ -0,0 +1,455 @@
+use std::{
+	cmp::Ordering,
+	convert::TryFrom,
+	fmt,
+	ops::{Index, IndexMut},
+	slice,
+};
+
+use crate::{
+	core::{self, DataType, Mat, Mat_, Scalar},
+	prelude::*,
+	Error, Result,
+};
+
+mod private {
+	pub trait Sealed {}
+}
+
+/// Floating point element of a [Tensor], required for the operations like [Tensor::softmax]
+pub trait TensorFloat: DataType + PartialOrd + private::Sealed {
+	fn to_f64(self) -> f64;
+	fn from_f64(val: f64) -> Self;
+}
+
+impl private::Sealed for f32 {}
+
+impl TensorFloat for f32 {
+	#[inline]
+	fn to_f64(self) -> f64 {
+		f64::from(self)
+	}
+
+	#[inline]
+	fn from_f64(val: f64) -> Self {
+		val as f32
+	}
+}
+
+impl private::Sealed for f64 {}
+
+impl TensorFloat for f64 {
+	#[inline]
+	fn to_f64(self) -> f64 {
+		self
+	}
+
+	#[inline]
+	fn from_f64(val: f64) -> Self {
+		val
+	}
+}
+
+/// Typed n-dimensional blob, e.g. an input or an output of a `Net`
+///
+/// The tensor is a thin wrapper around a continuous `Mat` with the element type `T`, the conversions from and into `Mat` don't copy
+/// the data. The elements are stored in the row-major order, so for the usual `[N, C, H, W]` blob the last axis is the fastest
+/// changing one.
+///
+/// The shape is tracked separately from the `Mat` because `Mat` can't have less than 2 dimensions, so the tensors with 0 or 1
+/// dimensions (e.g. the result of [Tensor::argmax]) keep their logical shape.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{dnn::Tensor, prelude::*};
+///
+/// # fn classify(mut net: opencv::dnn::Net) -> opencv::Result<()> {
+/// let out = Tensor::<f32>::from_mat(net.forward_single("")?)?;
+/// // [1, 1000] class scores
+/// let probs = out.softmax(1)?;
+/// let (scores, classes) = probs.topk(1, 5)?;
+/// for (score, class) in scores.as_slice().iter().zip(classes.as_slice()) {
+///     println!("class {}: {:.3}", class, score);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Tensor<T> {
+	mat: Mat_<T>,
+	shape: Vec<usize>,
+}
+
+fn mat_sizes(shape: &[usize]) -> Result<Vec<i32>> {
+	if shape.is_empty() {
+		return Ok(vec![1]);
+	}
+	shape
+		.iter()
+		.map(|&dim| {
+			i32::try_from(dim).map_err(|_| Error::new(core::StsOutOfRange, format!("Tensor dimension is too large: {}", dim)))
+		})
+		.collect()
+}
+
+fn out_of_range_axis(axis: usize, ndim: usize) -> Error {
+	Error::new(
+		core::StsOutOfRange,
+		format!("Axis: {} is out of range for the tensor with {} dimensions", axis, ndim),
+	)
+}
+
+impl<T: DataType> Tensor<T> {
+	/// Wraps the `Mat`, its type must match `T` and it must be continuous
+	pub fn from_mat(mat: Mat) -> Result<Self> {
+		if !mat.is_continuous() {
+			return Err(Error::new(
+				core::StsUnmatchedSizes,
+				"Mat is not continuous, can't use it as a tensor",
+			));
+		}
+		let mat = Mat_::<T>::try_from(mat)?;
+		let shape = if mat.dims() == 0 {
+			// empty Mat
+			vec![0]
+		} else {
+			mat.mat_size().iter().map(|&dim| dim as usize).collect()
+		};
+		Ok(Self { mat, shape })
+	}
+
+	/// Creates the tensor of the specified shape with all elements set to zero
+	pub fn zeros(shape: &[usize]) -> Result<Self> {
+		let mat = Mat::new_nd_with_default(&mat_sizes(shape)?, T::typ(), Scalar::all(0.))?;
+		Ok(Self {
+			mat: Mat_::try_from(mat)?,
+			shape: shape.to_vec(),
+		})
+	}
+
+	/// Creates the tensor of the specified shape copying the elements from `data` in the row-major order
+	pub fn from_slice(shape: &[usize], data: &[T]) -> Result<Self> {
+		let len = shape.iter().product::<usize>();
+		if len != data.len() {
+			return Err(Error::new(
+				core::StsUnmatchedSizes,
+				format!("Tensor shape: {:?} requires {} elements, but got: {}", shape, len, data.len()),
+			));
+		}
+		let mut out = Self::zeros(shape)?;
+		out.as_mut_slice().copy_from_slice(data);
+		Ok(out)
+	}
+
+	#[inline]
+	pub fn as_mat(&self) -> &Mat {
+		self.mat.as_untyped()
+	}
+
+	#[inline]
+	pub fn into_mat(self) -> Mat {
+		self.mat.into_untyped()
+	}
+
+	#[inline]
+	pub fn shape(&self) -> &[usize] {
+		&self.shape
+	}
+
+	/// Number of dimensions
+	#[inline]
+	pub fn ndim(&self) -> usize {
+		self.shape.len()
+	}
+
+	/// Size of the specified axis
+	#[inline]
+	pub fn dim(&self, axis: usize) -> Option<usize> {
+		self.shape.get(axis).copied()
+	}
+
+	/// Shape of the 4-dimensional blob as `[N, C, H, W]`
+	pub fn nchw(&self) -> Result<[usize; 4]> {
+		match *self.shape {
+			[n, c, h, w] => Ok([n, c, h, w]),
+			_ => Err(Error::new(
+				core::StsUnmatchedSizes,
+				format!("Expected 4-dimensional tensor, but its shape is: {:?}", self.shape),
+			)),
+		}
+	}
+
+	/// Total number of elements
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.shape.iter().product()
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// All elements in the row-major order
+	#[inline]
+	pub fn as_slice(&self) -> &[T] {
+		if self.is_empty() {
+			&[]
+		} else {
+			// continuity and type are checked on construction
+			unsafe { slice::from_raw_parts(self.mat.data() as *const T, self.len()) }
+		}
+	}
+
+	#[inline]
+	pub fn as_mut_slice(&mut self) -> &mut [T] {
+		if self.is_empty() {
+			&mut []
+		} else {
+			let len = self.len();
+			unsafe { slice::from_raw_parts_mut(self.mat.data_mut() as *mut T, len) }
+		}
+	}
+
+	fn offset(&self, idx: &[usize]) -> Option<usize> {
+		if idx.len() != self.shape.len() {
+			return None;
+		}
+		idx.iter().zip(&self.shape).try_fold(0, |offset, (&i, &dim)| {
+			if i < dim {
+				Some(offset * dim + i)
+			} else {
+				None
+			}
+		})
+	}
+
+	/// Element at the specified index, returns `None` if the index is out of bounds or doesn't match the number of dimensions
+	#[inline]
+	pub fn get(&self, idx: &[usize]) -> Option<&T> {
+		self.offset(idx).map(|offset| &self.as_slice()[offset])
+	}
+
+	#[inline]
+	pub fn get_mut(&mut self, idx: &[usize]) -> Option<&mut T> {
+		self.offset(idx).map(move |offset| &mut self.as_mut_slice()[offset])
+	}
+
+	/// Elements of the `i`-th item along the first axis, e.g. the `i`-th image of the batch or the `i`-th row of the detections
+	pub fn outer(&self, i: usize) -> Option<&[T]> {
+		let (&first, rest) = self.shape.split_first()?;
+		if i < first {
+			let len = rest.iter().product::<usize>();
+			Some(&self.as_slice()[i * len..(i + 1) * len])
+		} else {
+			None
+		}
+	}
+
+	/// Consumes the tensor and returns it with the new shape, the data is not copied
+	///
+	/// One of the dimensions can be -1, it's then inferred from the number of elements.
+	pub fn reshape(self, shape: &[isize]) -> Result<Self> {
+		let invalid = || {
+			Error::new(
+				core::StsUnmatchedSizes,
+				format!("Can't reshape tensor of shape: {:?} to: {:?}", self.shape, shape),
+			)
+		};
+		if shape.iter().any(|&dim| dim < -1) || shape.iter().filter(|&&dim| dim == -1).count() > 1 {
+			return Err(invalid());
+		}
+		let len = self.len();
+		let known = shape
+			.iter()
+			.filter(|&&dim| dim != -1)
+			.try_fold(1usize, |acc, &dim| acc.checked_mul(dim as usize))
+			.ok_or_else(invalid)?;
+		let new_shape = shape
+			.iter()
+			.map(|&dim| match dim {
+				-1 if known != 0 => len / known,
+				-1 => 0,
+				dim => dim as usize,
+			})
+			.collect::<Vec<_>>();
+		if new_shape.iter().product::<usize>() != len {
+			return Err(invalid());
+		}
+		let mat = self.mat.reshape_nd(0, &mat_sizes(&new_shape)?)?;
+		Ok(Self {
+			mat: Mat_::try_from(mat)?,
+			shape: new_shape,
+		})
+	}
+
+	/// Splits the shape around the axis into the number of outer lanes, the axis size and the number of inner elements
+	fn lanes(&self, axis: usize) -> Result<(usize, usize, usize)> {
+		if axis >= self.ndim() {
+			return Err(out_of_range_axis(axis, self.ndim()));
+		}
+		Ok((
+			self.shape[..axis].iter().product(),
+			self.shape[axis],
+			self.shape[axis + 1..].iter().product(),
+		))
+	}
+
+	/// Shape with the size of the axis replaced, or the axis removed if `size` is `None`
+	fn shape_with(&self, axis: usize, size: Option<usize>) -> Vec<usize> {
+		let mut out = self.shape.clone();
+		match size {
+			Some(size) => out[axis] = size,
+			None => {
+				out.remove(axis);
+			}
+		}
+		out
+	}
+}
+
+/// `true` for the values that are not comparable even to themselves, i.e. NaNs of the floating point types
+fn is_nan<T: PartialOrd>(value: T) -> bool {
+	value.partial_cmp(&value).is_none()
+}
+
+impl<T: DataType + PartialOrd> Tensor<T> {
+	/// Indices of the maximum elements along the axis, the axis is removed from the shape of the result
+	///
+	/// The first index is returned if there are several equal maximums. The axis must not be empty.
+	pub fn argmax(&self, axis: usize) -> Result<Tensor<i32>> {
+		let (outer, len, inner) = self.lanes(axis)?;
+		if len == 0 {
+			return Err(Error::new(
+				core::StsBadArg,
+				format!("Can't find maximum along the empty axis: {}", axis),
+			));
+		}
+		let data = self.as_slice();
+		let mut out = Tensor::zeros(&self.shape_with(axis, None))?;
+		let out_data = out.as_mut_slice();
+		for o in 0..outer {
+			for i in 0..inner {
+				let lane = |a: usize| data[(o * len + a) * inner + i];
+				let mut best = 0;
+				for a in 1..len {
+					if lane(a) > lane(best) {
+						best = a;
+					}
+				}
+				out_data[o * inner + i] = best as i32;
+			}
+		}
+		Ok(out)
+	}
+
+	/// `k` largest elements along the axis and their indices, sorted in the descending order
+	///
+	/// The axis in the shape of the results has the size `k`. The equal elements are ordered by their index, NaNs are ordered after
+	/// all the other elements.
+	pub fn topk(&self, axis: usize, k: usize) -> Result<(Self, Tensor<i32>)> {
+		let (outer, len, inner) = self.lanes(axis)?;
+		if k > len {
+			return Err(Error::new(
+				core::StsOutOfRange,
+				format!("Can't select top {} elements from the axis: {} of size: {}", k, axis, len),
+			));
+		}
+		let data = self.as_slice();
+		let shape = self.shape_with(axis, Some(k));
+		let mut values = Self::zeros(&shape)?;
+		let mut indices = Tensor::<i32>::zeros(&shape)?;
+		let values_data = values.as_mut_slice();
+		let indices_data = indices.as_mut_slice();
+		let mut order = Vec::with_capacity(len);
+		for o in 0..outer {
+			for i in 0..inner {
+				let lane = |a: usize| data[(o * len + a) * inner + i];
+				order.clear();
+				order.extend(0..len);
+				order.sort_by(|&a, &b| match (is_nan(lane(a)), is_nan(lane(b))) {
+					(false, false) => lane(b).partial_cmp(&lane(a)).unwrap_or(Ordering::Equal),
+					(a_nan, b_nan) => a_nan.cmp(&b_nan),
+				});
+				for (j, &a) in order[..k].iter().enumerate() {
+					let pos = (o * k + j) * inner + i;
+					values_data[pos] = lane(a);
+					indices_data[pos] = a as i32;
+				}
+			}
+		}
+		Ok((values, indices))
+	}
+}
+
+impl<T: TensorFloat> Tensor<T> {
+	/// Softmax along the axis, the result has the same shape
+	pub fn softmax(&self, axis: usize) -> Result<Self> {
+		let (outer, len, inner) = self.lanes(axis)?;
+		let data = self.as_slice();
+		let mut out = Self::zeros(&self.shape)?;
+		let out_data = out.as_mut_slice();
+		for o in 0..outer {
+			for i in 0..inner {
+				let pos = |a: usize| (o * len + a) * inner + i;
+				// subtracting the maximum keeps exp() from overflowing
+				let max = (0..len).map(|a| data[pos(a)].to_f64()).fold(f64::NEG_INFINITY, f64::max);
+				let mut sum = 0.;
+				for a in 0..len {
+					let exp = (data[pos(a)].to_f64() - max).exp();
+					sum += exp;
+					out_data[pos(a)] = T::from_f64(exp);
+				}
+				for a in 0..len {
+					out_data[pos(a)] = T::from_f64(out_data[pos(a)].to_f64() / sum);
+				}
+			}
+		}
+		Ok(out)
+	}
+}
+
+impl<T: DataType> TryFrom<Mat> for Tensor<T> {
+	type Error = Error;
+
+	#[inline]
+	fn try_from(mat: Mat) -> Result<Self> {
+		Self::from_mat(mat)
+	}
+}
+
+impl<T: DataType> From<Tensor<T>> for Mat {
+	#[inline]
+	fn from(s: Tensor<T>) -> Self {
+		s.into_mat()
+	}
+}
+
+impl<T: DataType, const N: usize> Index<[usize; N]> for Tensor<T> {
+	type Output = T;
+
+	fn index(&self, idx: [usize; N]) -> &Self::Output {
+		match self.get(&idx) {
+			Some(val) => val,
+			None => panic!("Index: {:?} is out of bounds for the tensor of shape: {:?}", idx, self.shape),
+		}
+	}
+}
+
+impl<T: DataType, const N: usize> IndexMut<[usize; N]> for Tensor<T> {
+	fn index_mut(&mut self, idx: [usize; N]) -> &mut Self::Output {
+		let shape = self.shape.clone();
+		match self.get_mut(&idx) {
+			Some(val) => val,
+			None => panic!("Index: {:?} is out of bounds for the tensor of shape: {:?}", idx, shape),
+		}
+	}
+}
+
+impl<T: DataType + fmt::Debug> fmt::Debug for Tensor<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Tensor")
+			.field("shape", &self.shape)
+			.field("data", &self.as_slice())
+			.finish()
+	}
+}
@@ -0,0 +1,434 @@
+//! Golden-output tests for the DNN models
+//!
+//! A [GoldenCase] holds the input tensors of a model together with the reference outputs, e.g. the ones produced by the original
+//! framework or by the previous OpenCV version. [run] feeds the inputs to a [Net], compares every output with its reference using
+//! the [Tolerance] and returns a [GoldenReport] with the error statistics per output.
+//!
+//! The tensors can be loaded from the ONNX `TensorProto` files or from the NumPy `.npy` files (see [core::npy]).
+//!
+//! ## Example
+//! ```no_run
+//! use opencv::dnn::{self, testing::{self, GoldenCase, Tolerance}};
+//!
+//! let mut net = dnn::read_net_from_onnx("model.onnx")?;
+//! let case = GoldenCase::new()
+//!     .input_from_onnx("input", "test_data_set_0/input_0.pb")?
+//!     .expected_from_onnx("output", "test_data_set_0/output_0.pb")?;
+//! let report = testing::run(&mut net, &case, &Tolerance::default().rel(1e-4).ulp(4))?;
+//! assert!(report.passed(), "{}", report);
+//! # Ok::<_, opencv::Error>(())
+//! ```
+//!
+//! Requires the `dnn-testing` feature.
+
+use std::{convert::TryFrom, fmt, path::Path};
+
+use crate::{
+	core::{self, Mat, Scalar, Vector},
+	dnn::{self, Net},
+	prelude::*,
+	Error, Result,
+};
+
+/// Allowed difference between the actual and the expected element
+///
+/// The element matches if it satisfies at least one of the tolerances: the absolute difference is at most `abs`, or the
+/// difference relative to the expected value is at most `rel`, or the values are at most `ulp` representable values apart. NaNs
+/// match only other NaNs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tolerance {
+	pub abs: f64,
+	pub rel: f64,
+	pub ulp: u64,
+}
+
+impl Default for Tolerance {
+	/// Absolute tolerance of `1e-5`, no relative and ULP tolerance
+	fn default() -> Self {
+		Self {
+			abs: 1e-5,
+			rel: 0.,
+			ulp: 0,
+		}
+	}
+}
+
+impl Tolerance {
+	/// Tolerance that only accepts the identical values
+	pub fn exact() -> Self {
+		Self {
+			abs: 0.,
+			rel: 0.,
+			ulp: 0,
+		}
+	}
+
+	pub fn abs(mut self, abs: f64) -> Self {
+		self.abs = abs;
+		self
+	}
+
+	pub fn rel(mut self, rel: f64) -> Self {
+		self.rel = rel;
+		self
+	}
+
+	pub fn ulp(mut self, ulp: u64) -> Self {
+		self.ulp = ulp;
+		self
+	}
+
+	fn accepts(&self, diff: &ElementDiff) -> bool {
+		diff.abs <= self.abs || diff.rel <= self.rel || diff.ulp <= self.ulp
+	}
+}
+
+struct ElementDiff {
+	abs: f64,
+	rel: f64,
+	ulp: u64,
+}
+
+/// Maps the float bits to integers that have the same order as the floats, so that the difference is the distance in ULPs
+fn ordered_f32(val: f32) -> i64 {
+	let bits = val.to_bits() as i32;
+	i64::from(if bits < 0 {
+		i32::MIN - bits
+	} else {
+		bits
+	})
+}
+
+fn ordered_f64(val: f64) -> i128 {
+	let bits = val.to_bits() as i64;
+	i128::from(if bits < 0 {
+		i64::MIN - bits
+	} else {
+		bits
+	})
+}
+
+fn element_diff(actual: f64, expected: f64, depth: i32) -> ElementDiff {
+	if actual.is_nan() || expected.is_nan() {
+		return if actual.is_nan() && expected.is_nan() {
+			ElementDiff {
+				abs: 0.,
+				rel: 0.,
+				ulp: 0,
+			}
+		} else {
+			ElementDiff {
+				abs: f64::INFINITY,
+				rel: f64::INFINITY,
+				ulp: u64::MAX,
+			}
+		};
+	}
+	let abs = if actual == expected {
+		0.
+	} else {
+		(actual - expected).abs()
+	};
+	let rel = if abs == 0. {
+		0.
+	} else {
+		abs / expected.abs()
+	};
+	let ulp = match depth {
+		core::CV_32F => (ordered_f32(actual as f32) - ordered_f32(expected as f32)).unsigned_abs(),
+		core::CV_64F => {
+			let dist = (ordered_f64(actual) - ordered_f64(expected)).unsigned_abs();
+			u64::try_from(dist).unwrap_or(u64::MAX)
+		}
+		// integer types, every value is 1 ULP apart
+		_ => abs.min(u64::MAX as f64) as u64,
+	};
+	ElementDiff { abs, rel, ulp }
+}
+
+/// Reference data of a single model run
+#[derive(Debug, Default)]
+pub struct GoldenCase {
+	inputs: Vec<(String, Mat)>,
+	expected: Vec<(String, Mat, Option<Tolerance>)>,
+}
+
+impl GoldenCase {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds the tensor for the network input `name`, empty `name` is the default input
+	pub fn input(mut self, name: &str, tensor: Mat) -> Self {
+		self.inputs.push((name.to_string(), tensor));
+		self
+	}
+
+	/// Adds the reference tensor for the output layer `name`
+	pub fn expected(mut self, name: &str, tensor: Mat) -> Self {
+		self.expected.push((name.to_string(), tensor, None));
+		self
+	}
+
+	/// Same as [GoldenCase::expected], but overrides the tolerance passed to [run] for this output
+	pub fn expected_with_tolerance(mut self, name: &str, tensor: Mat, tolerance: Tolerance) -> Self {
+		self.expected.push((name.to_string(), tensor, Some(tolerance)));
+		self
+	}
+
+	/// Adds the input tensor from the ONNX `TensorProto` file, e.g. `input_0.pb` of the ONNX test data sets
+	pub fn input_from_onnx(self, name: &str, path: impl AsRef<Path>) -> Result<Self> {
+		Ok(self.input(name, read_onnx_tensor(path.as_ref())?))
+	}
+
+	/// Adds the reference tensor from the ONNX `TensorProto` file, e.g. `output_0.pb` of the ONNX test data sets
+	pub fn expected_from_onnx(self, name: &str, path: impl AsRef<Path>) -> Result<Self> {
+		Ok(self.expected(name, read_onnx_tensor(path.as_ref())?))
+	}
+
+	/// Adds the input tensor from the NumPy `.npy` file, see [core::npy]
+	pub fn input_from_npy(self, name: &str, path: impl AsRef<Path>) -> Result<Self> {
+		Ok(self.input(name, core::npy::read(path)?))
+	}
+
+	/// Adds the reference tensor from the NumPy `.npy` file, see [core::npy]
+	pub fn expected_from_npy(self, name: &str, path: impl AsRef<Path>) -> Result<Self> {
+		Ok(self.expected(name, core::npy::read(path)?))
+	}
+
+	/// Names of the outputs that have the reference tensors
+	pub fn output_names(&self) -> impl Iterator<Item = &str> {
+		self.expected.iter().map(|(name, ..)| name.as_str())
+	}
+}
+
+fn read_onnx_tensor(path: &Path) -> Result<Mat> {
+	let path_str = path
+		.to_str()
+		.ok_or_else(|| Error::new(core::StsBadArg, format!("Path is not valid UTF-8: {}", path.display())))?;
+	dnn::read_tensor_from_onnx(path_str)
+}
+
+/// Number of the elements with the absolute error in `(previous bin upper bound, upper]`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HistogramBin {
+	pub upper: f64,
+	pub count: usize,
+}
+
+/// Upper bounds of the error histogram bins, the last bin catches the rest including the NaN mismatches
+const HISTOGRAM_BOUNDS: [f64; 10] = [0., 1e-8, 1e-7, 1e-6, 1e-5, 1e-4, 1e-3, 1e-2, 1e-1, 1.];
+
+/// Comparison result of a single output
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutputReport {
+	pub name: String,
+	/// Shape of the actual output, the channels are the last dimension for the multi-channel `Mat`s
+	pub shape: Vec<usize>,
+	/// Shape of the reference tensor
+	pub expected_shape: Vec<usize>,
+	pub tolerance: Tolerance,
+	/// Number of the compared elements
+	pub elements: usize,
+	/// Number of the elements that are outside of the tolerance
+	pub mismatches: usize,
+	pub max_abs_error: f64,
+	/// Index of the element with the maximum absolute error
+	pub max_abs_location: Vec<usize>,
+	/// Actual and expected values of the element with the maximum absolute error
+	pub max_abs_values: (f64, f64),
+	pub max_rel_error: f64,
+	pub max_ulp_error: u64,
+	/// Distribution of the absolute errors on the logarithmic scale
+	pub histogram: Vec<HistogramBin>,
+}
+
+impl OutputReport {
+	/// Returns `true` if the shapes are equal and all elements are within the tolerance
+	pub fn passed(&self) -> bool {
+		self.shape == self.expected_shape && self.mismatches == 0
+	}
+}
+
+impl fmt::Display for OutputReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{}: {}",
+			self.name,
+			if self.passed() {
+				"PASS"
+			} else {
+				"FAIL"
+			}
+		)?;
+		if self.shape != self.expected_shape {
+			return write!(f, ", shape {:?} != expected {:?}", self.shape, self.expected_shape);
+		}
+		writeln!(
+			f,
+			", shape {:?}, {} of {} elements mismatched",
+			self.shape, self.mismatches, self.elements
+		)?;
+		writeln!(
+			f,
+			"  max abs error: {:e} at {:?} (actual: {}, expected: {})",
+			self.max_abs_error, self.max_abs_location, self.max_abs_values.0, self.max_abs_values.1
+		)?;
+		writeln!(
+			f,
+			"  max rel error: {:e}, max ULP error: {}",
+			self.max_rel_error, self.max_ulp_error
+		)?;
+		write!(f, "  abs error histogram:")?;
+		for bin in self.histogram.iter().filter(|bin| bin.count > 0) {
+			if bin.upper == 0. {
+				write!(f, " 0: {}", bin.count)?;
+			} else if bin.upper.is_infinite() {
+				write!(f, " >{:e}: {}", HISTOGRAM_BOUNDS[HISTOGRAM_BOUNDS.len() - 1], bin.count)?;
+			} else {
+				write!(f, " <={:e}: {}", bin.upper, bin.count)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Results of [run] for all outputs of the [GoldenCase]
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoldenReport {
+	pub outputs: Vec<OutputReport>,
+}
+
+impl GoldenReport {
+	/// Returns `true` if all outputs passed
+	pub fn passed(&self) -> bool {
+		self.outputs.iter().all(OutputReport::passed)
+	}
+
+	/// Returns the outputs that didn't pass
+	pub fn failures(&self) -> impl Iterator<Item = &OutputReport> {
+		self.outputs.iter().filter(|output| !output.passed())
+	}
+}
+
+impl fmt::Display for GoldenReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (i, output) in self.outputs.iter().enumerate() {
+			if i > 0 {
+				writeln!(f)?;
+			}
+			write!(f, "{}", output)?;
+		}
+		Ok(())
+	}
+}
+
+fn mat_shape(mat: &Mat) -> Result<Vec<usize>> {
+	let mut out = mat.mat_size().iter().map(|&d| d as usize).collect::<Vec<_>>();
+	let channels = mat.channels();
+	if channels > 1 {
+		out.push(channels as usize);
+	}
+	Ok(out)
+}
+
+fn to_f64_vec(mat: &Mat) -> Result<Vec<f64>> {
+	let mut converted = Mat::default();
+	mat.convert_to(&mut converted, core::CV_64F, 1., 0.)?;
+	Ok(converted
+		.data_bytes()?
+		.chunks_exact(8)
+		.map(|b| f64::from_ne_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+		.collect())
+}
+
+fn unravel_index(mut index: usize, shape: &[usize]) -> Vec<usize> {
+	let mut out = vec![0; shape.len()];
+	for (dim, &size) in out.iter_mut().zip(shape).rev() {
+		if size > 0 {
+			*dim = index % size;
+			index /= size;
+		}
+	}
+	out
+}
+
+/// Compares the `actual` tensor with the `expected` one element by element
+///
+/// The elements are compared after the conversion to `f64`, the ULP distance is calculated for the depth of the `expected` tensor.
+pub fn compare(name: &str, actual: &Mat, expected: &Mat, tolerance: Tolerance) -> Result<OutputReport> {
+	let mut out = OutputReport {
+		name: name.to_string(),
+		shape: mat_shape(actual)?,
+		expected_shape: mat_shape(expected)?,
+		tolerance,
+		elements: 0,
+		mismatches: 0,
+		max_abs_error: 0.,
+		max_abs_location: vec![],
+		max_abs_values: (0., 0.),
+		max_rel_error: 0.,
+		max_ulp_error: 0,
+		histogram: HISTOGRAM_BOUNDS
+			.iter()
+			.copied()
+			.chain(Some(f64::INFINITY))
+			.map(|upper| HistogramBin { upper, count: 0 })
+			.collect(),
+	};
+	if out.shape != out.expected_shape {
+		return Ok(out);
+	}
+	let depth = expected.depth();
+	let actual = to_f64_vec(actual)?;
+	let expected = to_f64_vec(expected)?;
+	let mut max_abs_index = None;
+	for (i, (&a, &e)) in actual.iter().zip(&expected).enumerate() {
+		let diff = element_diff(a, e, depth);
+		if !tolerance.accepts(&diff) {
+			out.mismatches += 1;
+		}
+		if max_abs_index.is_none() || diff.abs > out.max_abs_error {
+			out.max_abs_error = diff.abs;
+			max_abs_index = Some(i);
+		}
+		out.max_rel_error = out.max_rel_error.max(diff.rel);
+		out.max_ulp_error = out.max_ulp_error.max(diff.ulp);
+		let bin = HISTOGRAM_BOUNDS
+			.iter()
+			.position(|&upper| diff.abs <= upper)
+			.unwrap_or(HISTOGRAM_BOUNDS.len());
+		out.histogram[bin].count += 1;
+	}
+	out.elements = expected.len();
+	if let Some(i) = max_abs_index {
+		out.max_abs_location = unravel_index(i, &out.shape);
+		out.max_abs_values = (actual[i], expected[i]);
+	}
+	Ok(out)
+}
+
+/// Runs the `net` on the inputs of the `case` and compares the outputs with the reference tensors
+///
+/// `tolerance` is used for the outputs that were added without an explicit tolerance.
+pub fn run(net: &mut Net, case: &GoldenCase, tolerance: &Tolerance) -> Result<GoldenReport> {
+	if case.expected.is_empty() {
+		return Err(Error::new(core::StsBadArg, "Golden case has no expected outputs"));
+	}
+	for (name, tensor) in &case.inputs {
+		net.set_input(tensor, name, 1., Scalar::default())?;
+	}
+	let names = case.output_names().collect::<Vector<String>>();
+	let mut outputs = Vector::<Mat>::new();
+	net.forward(&mut outputs, &names)?;
+	let outputs = case
+		.expected
+		.iter()
+		.zip(outputs)
+		.map(|((name, expected, output_tolerance), actual)| {
+			compare(name, &actual, expected, output_tolerance.unwrap_or(*tolerance))
+		})
+		.collect::<Result<Vec<_>>>()?;
+	Ok(GoldenReport { outputs })
+}
@@ -1,6 +1,13 @@
-use std::ffi::c_void;
+use std::{ffi::c_void, mem::ManuallyDrop};
 
-use crate::{features2d::ORB, sys, traits::Boxed, types, Result};
+use crate::{
+	core::{self, KeyPoint, Ptr, Vector, _InputArray, _OutputArray},
+	features2d::{Feature2D, ORB},
+	sys,
+	traits::Boxed,
+	types::{self, Unit},
+	Result,
+};
 
 impl dyn ORB + '_ {
 	pub fn default() -> Result<types::PtrOfORB> {
@@ -13,3 +20,115 @@ impl dyn ORB + '_ {
 		ret.into_result().map(|ptr| unsafe { types::PtrOfORB::from_raw(ptr) })
 	}
 }
+
+/// Rust implementation of the `Feature2D` keypoint detector and descriptor extractor
+///
+/// Use [Ptr::from_rust](crate::core::Ptr::<Feature2D>::from_rust) to pass it to the APIs that take a `Feature2D`. `detect()`
+/// and `compute()` of the resulting object are implemented in terms of `detect_and_compute()`.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/d0/d13/classcv_1_1Feature2D.html)
+pub trait Feature2DImpl: Send + Sync {
+	/// Detects the keypoints and computes their descriptors
+	///
+	/// ## Parameters
+	/// * image: the input image
+	/// * mask: optional mask specifying where to look for the keypoints, can be empty
+	/// * keypoints: the detected keypoints, when `use_provided_keypoints` is `true` they are the input keypoints instead
+	/// * descriptors: output descriptors, not needed when only the detection is requested (check with `descriptors.needed()`)
+	fn detect_and_compute(
+		&mut self,
+		image: &_InputArray,
+		mask: &_InputArray,
+		keypoints: &mut Vector<KeyPoint>,
+		descriptors: &mut _OutputArray,
+		use_provided_keypoints: bool,
+	) -> Result<()>;
+
+	/// Size of a single descriptor in elements
+	fn descriptor_size(&self) -> i32 {
+		0
+	}
+
+	/// Type of the descriptor elements, e.g. `CV_8U` or `CV_32F`
+	fn descriptor_type(&self) -> i32 {
+		0
+	}
+
+	/// Norm to use when matching the descriptors
+	fn default_norm(&self) -> i32 {
+		core::NORM_L2
+	}
+}
+
+#[repr(C)]
+struct Vtable {
+	drop: unsafe extern "C" fn(*mut c_void),
+	detect_and_compute:
+		unsafe extern "C" fn(*mut c_void, *const c_void, *const c_void, *mut c_void, *const c_void, bool, *mut sys::Result_void),
+	descriptor_size: unsafe extern "C" fn(*const c_void, *mut sys::Result<i32>),
+	descriptor_type: unsafe extern "C" fn(*const c_void, *mut sys::Result<i32>),
+	default_norm: unsafe extern "C" fn(*const c_void, *mut sys::Result<i32>),
+}
+
+static VTABLE: Vtable = Vtable {
+	drop: sys::drop_instance::<dyn Feature2DImpl>,
+	detect_and_compute: vtable_detect_and_compute,
+	descriptor_size: vtable_descriptor_size,
+	descriptor_type: vtable_descriptor_type,
+	default_norm: vtable_default_norm,
+};
+
+unsafe extern "C" fn vtable_detect_and_compute(
+	instance: *mut c_void,
+	image: *const c_void,
+	mask: *const c_void,
+	keypoints: *mut c_void,
+	descriptors: *const c_void,
+	use_provided_keypoints: bool,
+	ocvrs_return: *mut sys::Result_void,
+) {
+	let instance = sys::instance_mut::<dyn Feature2DImpl>(instance);
+	// arrays and keypoints are owned by the C++ caller
+	let image = ManuallyDrop::new(_InputArray::from_raw(image as *mut c_void));
+	let mask = ManuallyDrop::new(_InputArray::from_raw(mask as *mut c_void));
+	let mut keypoints = ManuallyDrop::new(Vector::<KeyPoint>::from_raw(keypoints));
+	let mut descriptors = ManuallyDrop::new(_OutputArray::from_raw(descriptors as *mut c_void));
+	ocvrs_return.write(sys::Result::<Unit, ()>::from_callback(|| {
+		instance.detect_and_compute(&image, &mask, &mut keypoints, &mut descriptors, use_provided_keypoints)
+	}));
+}
+
+unsafe extern "C" fn vtable_descriptor_size(instance: *const c_void, ocvrs_return: *mut sys::Result<i32>) {
+	let instance = sys::instance_ref::<dyn Feature2DImpl>(instance);
+	ocvrs_return.write(sys::Result::from_callback(|| Ok(instance.descriptor_size())));
+}
+
+unsafe extern "C" fn vtable_descriptor_type(instance: *const c_void, ocvrs_return: *mut sys::Result<i32>) {
+	let instance = sys::instance_ref::<dyn Feature2DImpl>(instance);
+	ocvrs_return.write(sys::Result::from_callback(|| Ok(instance.descriptor_type())));
+}
+
+unsafe extern "C" fn vtable_default_norm(instance: *const c_void, ocvrs_return: *mut sys::Result<i32>) {
+	let instance = sys::instance_ref::<dyn Feature2DImpl>(instance);
+	ocvrs_return.write(sys::Result::from_callback(|| Ok(instance.default_norm())));
+}
+
+impl Ptr<Feature2D> {
+	/// Wraps the Rust implementation of the detector into the C++ `Feature2D` object
+	pub fn from_rust(feature2d: impl Feature2DImpl + 'static) -> Result<Self> {
+		extern "C" {
+			fn cv_manual_PtrOfFeature2D_from_rust(
+				instance: *mut c_void,
+				vtable: *const Vtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		let feature2d: Box<dyn Feature2DImpl> = Box::new(feature2d);
+		unsafe {
+			sys::into_extern(feature2d, |instance, ocvrs_return| {
+				cv_manual_PtrOfFeature2D_from_rust(instance, &VTABLE, ocvrs_return)
+			})
+		}
+		.map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
@@ -0,0 +1,5 @@
+pub use key::*;
+pub use window::*;
+
+mod key;
+mod window;
@@ -0,0 +1,219 @@
+use std::{convert::TryFrom, time::Duration};
+
+use crate::{highgui, Result};
+
+/// Key decoded from the platform-dependent code returned by `waitKeyEx()`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+	/// Printable character
+	Char(char),
+	Escape,
+	Enter,
+	Tab,
+	Backspace,
+	Delete,
+	Insert,
+	Home,
+	End,
+	PageUp,
+	PageDown,
+	Left,
+	Up,
+	Right,
+	Down,
+	/// Function key, `F(1)` is F1
+	F(u8),
+	/// Code that couldn't be decoded, e.g. a modifier key pressed alone
+	Unknown(i32),
+}
+
+/// Modifier keys held during the key press
+///
+/// Only the GTK backend reports the modifiers, with the other backends the fields are always `false`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeyModifiers {
+	pub shift: bool,
+	pub ctrl: bool,
+	pub alt: bool,
+}
+
+impl KeyModifiers {
+	/// Returns `true` if no modifier is held
+	pub fn is_empty(self) -> bool {
+		self == Self::default()
+	}
+}
+
+/// Key press returned by [wait_key_event]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyEvent {
+	pub code: KeyCode,
+	pub modifiers: KeyModifiers,
+	/// Code returned by `waitKeyEx()`
+	pub raw: i32,
+}
+
+/// GDK modifier state bits, shifted by 16 in the GTK key codes
+const GDK_SHIFT_MASK: i32 = 1;
+const GDK_CONTROL_MASK: i32 = 1 << 2;
+const GDK_MOD1_MASK: i32 = 1 << 3;
+
+/// `Qt::Key` values of the special keys, the Qt backend returns them for the non-character keys
+fn decode_qt(raw: i32) -> Option<KeyCode> {
+	Some(match raw {
+		0x0100_0000 => KeyCode::Escape,
+		0x0100_0001 => KeyCode::Tab,
+		0x0100_0003 => KeyCode::Backspace,
+		// Return and keypad Enter
+		0x0100_0004 | 0x0100_0005 => KeyCode::Enter,
+		0x0100_0006 => KeyCode::Insert,
+		0x0100_0007 => KeyCode::Delete,
+		0x0100_0010 => KeyCode::Home,
+		0x0100_0011 => KeyCode::End,
+		0x0100_0012 => KeyCode::Left,
+		0x0100_0013 => KeyCode::Up,
+		0x0100_0014 => KeyCode::Right,
+		0x0100_0015 => KeyCode::Down,
+		0x0100_0016 => KeyCode::PageUp,
+		0x0100_0017 => KeyCode::PageDown,
+		0x0100_0030..=0x0100_0052 => KeyCode::F((raw - 0x0100_0030 + 1) as u8),
+		_ => return None,
+	})
+}
+
+/// Windows virtual key codes, the Win32 backend returns them shifted by 16 for the non-character keys
+fn decode_win32(vk: i32) -> Option<KeyCode> {
+	Some(match vk {
+		0x21 => KeyCode::PageUp,
+		0x22 => KeyCode::PageDown,
+		0x23 => KeyCode::End,
+		0x24 => KeyCode::Home,
+		0x25 => KeyCode::Left,
+		0x26 => KeyCode::Up,
+		0x27 => KeyCode::Right,
+		0x28 => KeyCode::Down,
+		0x2D => KeyCode::Insert,
+		0x2E => KeyCode::Delete,
+		0x70..=0x87 => KeyCode::F((vk - 0x70 + 1) as u8),
+		_ => return None,
+	})
+}
+
+/// X11/GDK keysyms of the special keys, returned by the GTK backend and by the Qt backend on X11
+fn decode_keysym(keysym: i32) -> Option<KeyCode> {
+	Some(match keysym {
+		0xFF08 => KeyCode::Backspace,
+		0xFF09 => KeyCode::Tab,
+		// Return and keypad Enter
+		0xFF0D | 0xFF8D => KeyCode::Enter,
+		0xFF1B => KeyCode::Escape,
+		0xFF50 => KeyCode::Home,
+		0xFF51 => KeyCode::Left,
+		0xFF52 => KeyCode::Up,
+		0xFF53 => KeyCode::Right,
+		0xFF54 => KeyCode::Down,
+		0xFF55 => KeyCode::PageUp,
+		0xFF56 => KeyCode::PageDown,
+		0xFF57 => KeyCode::End,
+		0xFF63 => KeyCode::Insert,
+		0xFFFF => KeyCode::Delete,
+		0xFFBE..=0xFFE0 => KeyCode::F((keysym - 0xFFBE + 1) as u8),
+		_ => return None,
+	})
+}
+
+/// Function key characters of `NSEvent`, returned by the Cocoa backend
+fn decode_cocoa(c: i32) -> Option<KeyCode> {
+	Some(match c {
+		0xF700 => KeyCode::Up,
+		0xF701 => KeyCode::Down,
+		0xF702 => KeyCode::Left,
+		0xF703 => KeyCode::Right,
+		0xF704..=0xF726 => KeyCode::F((c - 0xF704 + 1) as u8),
+		0xF727 => KeyCode::Insert,
+		0xF728 => KeyCode::Delete,
+		0xF729 => KeyCode::Home,
+		0xF72B => KeyCode::End,
+		0xF72C => KeyCode::PageUp,
+		0xF72D => KeyCode::PageDown,
+		_ => return None,
+	})
+}
+
+/// ASCII control codes and characters, common for all backends
+fn decode_char(c: i32) -> KeyCode {
+	match c {
+		8 | 127 => KeyCode::Backspace,
+		9 => KeyCode::Tab,
+		10 | 13 => KeyCode::Enter,
+		27 => KeyCode::Escape,
+		// keysyms of the remaining function keys like Shift or Caps Lock
+		0xFF00..=0xFFFF => KeyCode::Unknown(c),
+		_ => u32::try_from(c)
+			.ok()
+			.and_then(char::from_u32)
+			.filter(|c| !c.is_control())
+			.map_or(KeyCode::Unknown(c), KeyCode::Char),
+	}
+}
+
+impl KeyEvent {
+	/// Decodes the code returned by `waitKeyEx()`, returns `None` for -1 meaning that no key was pressed
+	///
+	/// The code ranges of the GTK, Qt, Win32 and Cocoa backends don't overlap so the codes are decoded the same way regardless of the
+	/// backend that OpenCV uses.
+	pub fn from_raw(raw: i32) -> Option<Self> {
+		if raw == -1 {
+			return None;
+		}
+		let mut modifiers = KeyModifiers::default();
+		let code = if let Some(code) = decode_qt(raw) {
+			code
+		} else if let Some(code) = Some(raw >> 16).filter(|_| raw & 0xFFFF == 0).and_then(decode_win32) {
+			code
+		} else {
+			// GTK puts the modifier state into the upper 16 bits, the other backends don't use them for the remaining codes
+			let state = raw >> 16;
+			modifiers.shift = state & GDK_SHIFT_MASK != 0;
+			modifiers.ctrl = state & GDK_CONTROL_MASK != 0;
+			modifiers.alt = state & GDK_MOD1_MASK != 0;
+			let key = raw & 0xFFFF;
+			decode_keysym(key)
+				.or_else(|| decode_cocoa(key))
+				.unwrap_or_else(|| decode_char(key))
+		};
+		Some(Self { code, modifiers, raw })
+	}
+}
+
+/// Waits for a key press like `wait_key_ex()` and decodes it
+///
+/// Zero `delay` waits indefinitely, otherwise it's rounded up to whole milliseconds. Returns `None` if no key was pressed during
+/// the delay.
+///
+/// ## Example
+/// ```no_run
+/// use std::time::Duration;
+/// use opencv::highgui::{self, KeyCode};
+///
+/// loop {
+///     let key = match highgui::wait_key_event(Duration::from_millis(30))? {
+///         Some(key) => key,
+///         None => continue,
+///     };
+///     match key.code {
+///         KeyCode::Left if key.modifiers.shift => println!("Fast rewind"),
+///         KeyCode::Left => println!("Rewind"),
+///         KeyCode::Char('q') | KeyCode::Escape => break,
+///         _ => {}
+///     }
+/// }
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub fn wait_key_event(delay: Duration) -> Result<Option<KeyEvent>> {
+	let mut millis = delay.as_millis().min(i32::MAX as u128) as i32;
+	if Duration::from_millis(millis as u64) < delay && millis < i32::MAX {
+		millis += 1;
+	}
+	highgui::wait_key_ex(millis).map(KeyEvent::from_raw)
+}
@@ -0,0 +1,313 @@
+use std::{
+	cell::Cell,
+	fmt,
+	sync::{mpsc, Mutex, PoisonError},
+	time::Duration,
+};
+
+use crate::{
+	core::{self, Point},
+	highgui::{self, KeyEvent, MouseEventFlags, MouseEventTypes, WindowFlags, WindowPropertyFlags},
+	Result,
+};
+
+/// Combination of the [MouseEventFlags] accompanying a mouse event
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct MouseFlags(i32);
+
+impl MouseFlags {
+	pub fn from_bits(bits: i32) -> Self {
+		Self(bits)
+	}
+
+	/// Raw value as passed to the mouse callback
+	pub fn bits(self) -> i32 {
+		self.0
+	}
+
+	/// Returns `true` if the flag is set, e.g. if the left button or the Ctrl key is held down
+	pub fn contains(self, flag: MouseEventFlags) -> bool {
+		// wheel delta occupies the upper 16 bits
+		self.0 & 0xFFFF & flag as i32 != 0
+	}
+
+	/// Wheel rotation for `EVENT_MOUSEWHEEL` and `EVENT_MOUSEHWHEEL`, same as `getMouseWheelDelta()`
+	///
+	/// Positive values mean forward rotation (away from the user) or scrolling to the right, one notch is usually 120.
+	pub fn wheel_delta(self) -> i32 {
+		self.0 >> 16
+	}
+}
+
+impl fmt::Debug for MouseFlags {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let flags = [
+			MouseEventFlags::EVENT_FLAG_LBUTTON,
+			MouseEventFlags::EVENT_FLAG_RBUTTON,
+			MouseEventFlags::EVENT_FLAG_MBUTTON,
+			MouseEventFlags::EVENT_FLAG_CTRLKEY,
+			MouseEventFlags::EVENT_FLAG_SHIFTKEY,
+			MouseEventFlags::EVENT_FLAG_ALTKEY,
+		];
+		let mut out = f.debug_set();
+		for flag in flags.iter().filter(|&&flag| self.contains(flag)) {
+			out.entry(flag);
+		}
+		out.finish()
+	}
+}
+
+fn mouse_event_type(event: i32) -> Option<MouseEventTypes> {
+	Some(match event {
+		0 => MouseEventTypes::EVENT_MOUSEMOVE,
+		1 => MouseEventTypes::EVENT_LBUTTONDOWN,
+		2 => MouseEventTypes::EVENT_RBUTTONDOWN,
+		3 => MouseEventTypes::EVENT_MBUTTONDOWN,
+		4 => MouseEventTypes::EVENT_LBUTTONUP,
+		5 => MouseEventTypes::EVENT_RBUTTONUP,
+		6 => MouseEventTypes::EVENT_MBUTTONUP,
+		7 => MouseEventTypes::EVENT_LBUTTONDBLCLK,
+		8 => MouseEventTypes::EVENT_RBUTTONDBLCLK,
+		9 => MouseEventTypes::EVENT_MBUTTONDBLCLK,
+		10 => MouseEventTypes::EVENT_MOUSEWHEEL,
+		11 => MouseEventTypes::EVENT_MOUSEHWHEEL,
+		_ => return None,
+	})
+}
+
+/// Event delivered through [Window::events]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WindowEvent {
+	Mouse {
+		kind: MouseEventTypes,
+		/// Position in the image coordinates
+		pos: Point,
+		flags: MouseFlags,
+	},
+	/// Key press decoded from the code returned by `waitKeyEx()`
+	///
+	/// OpenCV doesn't report which window has the keyboard focus, so the key presses are delivered to the window that called
+	/// [Window::wait].
+	Key(KeyEvent),
+	/// Trackbar position was changed
+	Trackbar { name: String, pos: i32 },
+	/// Window was closed by the user, sent only once
+	Closed,
+}
+
+/// Sender usable from the OpenCV callbacks that need to be `Sync`
+struct EventSender(Mutex<mpsc::Sender<WindowEvent>>);
+
+impl EventSender {
+	fn new(sender: &mpsc::Sender<WindowEvent>) -> Self {
+		Self(Mutex::new(sender.clone()))
+	}
+
+	fn send(&self, event: WindowEvent) {
+		// the receiver is dropped only together with the window so the error is not possible while the callbacks are registered
+		let _ = self.0.lock().unwrap_or_else(PoisonError::into_inner).send(event);
+	}
+}
+
+/// HighGUI window that is destroyed when dropped
+///
+/// Mouse, keyboard, trackbar and window close events are delivered through the single channel returned by [Window::events]. The
+/// events are only produced while the GUI event loop is running, so [Window::wait] (or `wait_key()`) must be called regularly.
+///
+/// OpenCV identifies the windows by name so creating two `Window`s with the same name refers to the same native window.
+///
+/// ## Example
+/// ```no_run
+/// use std::time::Duration;
+/// use opencv::{core::Mat, highgui::{KeyCode, Window, WindowEvent, WindowFlags}};
+///
+/// let window = Window::new("preview", WindowFlags::WINDOW_AUTOSIZE)?;
+/// let threshold = window.create_trackbar("threshold", 255)?;
+/// window.show(&Mat::default())?;
+/// 'main: loop {
+///     window.wait(Duration::from_millis(30))?;
+///     for event in window.events().try_iter() {
+///         match event {
+///             WindowEvent::Key(key) if key.code == KeyCode::Escape => break 'main,
+///             WindowEvent::Closed => break 'main,
+///             WindowEvent::Mouse { kind, pos, .. } => println!("{:?} at {:?}", kind, pos),
+///             WindowEvent::Trackbar { name, pos } => println!("{} = {}", name, pos),
+///             _ => {}
+///         }
+///     }
+/// }
+/// println!("Final threshold: {}", threshold.pos()?);
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub struct Window {
+	name: String,
+	sender: mpsc::Sender<WindowEvent>,
+	receiver: mpsc::Receiver<WindowEvent>,
+	closed: Cell<bool>,
+}
+
+impl Window {
+	/// Creates the window, same as `named_window()`
+	pub fn new(name: &str, flags: WindowFlags) -> Result<Self> {
+		highgui::named_window(name, flags as i32)?;
+		let (sender, receiver) = mpsc::channel();
+		let mouse_sender = EventSender::new(&sender);
+		let out = Self {
+			name: name.to_string(),
+			sender,
+			receiver,
+			closed: Cell::new(false),
+		};
+		highgui::set_mouse_callback(
+			name,
+			Some(Box::new(move |event, x, y, flags| {
+				if let Some(kind) = mouse_event_type(event) {
+					mouse_sender.send(WindowEvent::Mouse {
+						kind,
+						pos: Point::new(x, y),
+						flags: MouseFlags(flags),
+					});
+				}
+			})),
+		)?;
+		Ok(out)
+	}
+
+	/// Name of the window
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Displays the image in the window, same as `imshow()`
+	pub fn show(&self, img: &dyn core::ToInputArray) -> Result<()> {
+		highgui::imshow(&self.name, img)
+	}
+
+	/// Receiver of the events of the window
+	pub fn events(&self) -> &mpsc::Receiver<WindowEvent> {
+		&self.receiver
+	}
+
+	/// Runs the GUI event loop for the specified time, delivering the key press and the window close events to [Window::events]
+	///
+	/// Waits at least 1 ms because zero delay means waiting indefinitely for `waitKey()`. Returns early on a key press.
+	pub fn wait(&self, delay: Duration) -> Result<()> {
+		let delay = delay.as_millis().clamp(1, i32::MAX as u128) as i32;
+		let key = highgui::wait_key_ex(delay)?;
+		if let Some(key) = KeyEvent::from_raw(key) {
+			let _ = self.sender.send(WindowEvent::Key(key));
+		}
+		if !self.closed.get() && !self.is_visible()? {
+			self.closed.set(true);
+			let _ = self.sender.send(WindowEvent::Closed);
+		}
+		Ok(())
+	}
+
+	/// Returns `false` if the window was closed by the user
+	///
+	/// Some backends report the property of the window destroyed by the user as `-1` instead of `0`, so any value below `1` is
+	/// treated as closed.
+	pub fn is_visible(&self) -> Result<bool> {
+		self
+			.property(WindowPropertyFlags::WND_PROP_VISIBLE)
+			.map(|visible| visible >= 1.)
+	}
+
+	/// Creates a trackbar with the range `0..=max` attached to the window, its changes are delivered as [WindowEvent::Trackbar]
+	pub fn create_trackbar(&self, name: &str, max: i32) -> Result<Trackbar> {
+		let sender = EventSender::new(&self.sender);
+		let trackbar_name = name.to_string();
+		highgui::create_trackbar(
+			name,
+			&self.name,
+			None,
+			max,
+			Some(Box::new(move |pos| {
+				sender.send(WindowEvent::Trackbar {
+					name: trackbar_name.clone(),
+					pos,
+				})
+			})),
+		)?;
+		Ok(Trackbar {
+			window: self.name.clone(),
+			name: name.to_string(),
+		})
+	}
+
+	pub fn set_title(&self, title: &str) -> Result<()> {
+		highgui::set_window_title(&self.name, title)
+	}
+
+	pub fn move_to(&self, x: i32, y: i32) -> Result<()> {
+		highgui::move_window(&self.name, x, y)
+	}
+
+	pub fn resize(&self, width: i32, height: i32) -> Result<()> {
+		highgui::resize_window(&self.name, width, height)
+	}
+
+	pub fn property(&self, prop: WindowPropertyFlags) -> Result<f64> {
+		highgui::get_window_property(&self.name, prop as i32)
+	}
+
+	pub fn set_property(&self, prop: WindowPropertyFlags, value: f64) -> Result<()> {
+		highgui::set_window_property(&self.name, prop as i32, value)
+	}
+}
+
+impl Drop for Window {
+	fn drop(&mut self) {
+		// unregister the callback before the window goes away so that it doesn't outlive the channel
+		let _ = highgui::set_mouse_callback(&self.name, None);
+		let _ = highgui::destroy_window(&self.name);
+	}
+}
+
+impl fmt::Debug for Window {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Window")
+			.field("name", &self.name)
+			.field("closed", &self.closed.get())
+			.finish()
+	}
+}
+
+/// Handle of a trackbar created by [Window::create_trackbar]
+///
+/// The handle doesn't keep the window alive, after the window is destroyed its methods return errors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trackbar {
+	window: String,
+	name: String,
+}
+
+impl Trackbar {
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// Name of the window the trackbar is attached to
+	pub fn window_name(&self) -> &str {
+		&self.window
+	}
+
+	/// Current position
+	pub fn pos(&self) -> Result<i32> {
+		highgui::get_trackbar_pos(&self.name, &self.window)
+	}
+
+	/// Moves the slider, this triggers [WindowEvent::Trackbar]
+	pub fn set_pos(&self, pos: i32) -> Result<()> {
+		highgui::set_trackbar_pos(&self.name, &self.window, pos)
+	}
+
+	pub fn set_min(&self, min: i32) -> Result<()> {
+		highgui::set_trackbar_min(&self.name, &self.window, min)
+	}
+
+	pub fn set_max(&self, max: i32) -> Result<()> {
+		highgui::set_trackbar_max(&self.name, &self.window, max)
+	}
+}
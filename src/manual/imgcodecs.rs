@@ -0,0 +1,15 @@
+#[cfg(feature = "async")]
+pub use asynchronous::*;
+pub use codec::*;
+pub use encode_params::*;
+pub use metadata::*;
+pub use multi_page::*;
+pub use probe::*;
+
+#[cfg(feature = "async")]
+mod asynchronous;
+mod codec;
+mod encode_params;
+mod metadata;
+mod multi_page;
+mod probe;
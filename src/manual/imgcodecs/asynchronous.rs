@@ -0,0 +1,30 @@
+use crate::{
+	core::{self, spawn_blocking, Blocking, Mat, Vector},
+	imgcodecs, Error,
+};
+
+/// Asynchronous version of `imread()` that reads the image on a separate thread
+///
+/// See [spawn_blocking](crate::core::spawn_blocking) for the details about execution and cancellation.
+pub fn imread_async(filename: &str, flags: i32) -> Blocking<Mat> {
+	let filename = filename.to_string();
+	spawn_blocking(move || imgcodecs::imread(&filename, flags))
+}
+
+/// Asynchronous version of `imencode()` that encodes the image on a separate thread
+///
+/// `img` is moved to the encoding thread, pass a shallow copy (`Mat::copy()`) to keep using it in the meantime. Unlike
+/// `imencode()` it fails with an error if the image can't be encoded.
+///
+/// See [spawn_blocking](crate::core::spawn_blocking) for the details about execution and cancellation.
+pub fn imencode_async(ext: &str, img: Mat, params: Vector<i32>) -> Blocking<Vector<u8>> {
+	let ext = ext.to_string();
+	spawn_blocking(move || {
+		let mut buf = Vector::new();
+		if imgcodecs::imencode(&ext, &img, &mut buf, &params)? {
+			Ok(buf)
+		} else {
+			Err(Error::new(core::StsError, format!("Can't encode image as: {}", ext)))
+		}
+	})
+}
@@ -0,0 +1,74 @@
+use std::{ffi::c_void, io::Write};
+
+use crate::{
+	core::{self, Mat, Vector},
+	imgcodecs::{self, EncodeParams, ImreadModes},
+	prelude::*,
+	Error, Result,
+};
+
+/// Decodes the image from the encoded bytes in memory without copying them
+///
+/// Unlike `imdecode()` it fails with an error when the data can't be decoded instead of returning an empty `Mat`.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::imgcodecs::{self, ImreadModes};
+///
+/// let bytes = std::fs::read("image.jpg").unwrap();
+/// let img = imgcodecs::decode(&bytes, ImreadModes::IMREAD_COLOR)?;
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub fn decode(buf: &[u8], flags: ImreadModes) -> Result<Mat> {
+	decode_raw(buf, flags as i32)
+}
+
+/// [decode] accepting the combination of `IMREAD_*` flags
+pub(super) fn decode_raw(buf: &[u8], flags: i32) -> Result<Mat> {
+	if buf.is_empty() {
+		return Err(Error::new(core::StsBadArg, "Can't decode image from an empty buffer"));
+	}
+	// the header borrows `buf` only for the duration of the call, imdecode() doesn't write to its input
+	let src = unsafe {
+		Mat::new_rows_cols_with_data(
+			1,
+			buf.len() as i32,
+			core::CV_8UC1,
+			buf.as_ptr() as *mut c_void,
+			core::Mat_AUTO_STEP,
+		)
+	}?;
+	let out = imgcodecs::imdecode(&src, flags)?;
+	if out.empty() {
+		Err(Error::new(
+			core::StsError,
+			"Can't decode image, the format is unknown or the data is corrupted",
+		))
+	} else {
+		Ok(out)
+	}
+}
+
+/// Encodes the image into the format specified by the file extension `ext` (e.g. `".png"`) and writes it to `writer`
+///
+/// Fails with an error if `params` are meant for a different format or the image can't be encoded.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{core::Mat, imgcodecs::{self, EncodeParams, PngParams}};
+///
+/// let img = Mat::default();
+/// let mut out = vec![];
+/// imgcodecs::encode_to(&mut out, ".png", &img, &EncodeParams::Png(PngParams { compression: Some(9), ..PngParams::default() }))?;
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub fn encode_to(mut writer: impl Write, ext: &str, img: &Mat, params: &EncodeParams) -> Result<()> {
+	params.check_extension(ext)?;
+	let mut buf = Vector::<u8>::new();
+	if !imgcodecs::imencode(ext, img, &mut buf, &params.to_vector()?)? {
+		return Err(Error::new(core::StsError, format!("Can't encode image as: {}", ext)));
+	}
+	writer
+		.write_all(buf.as_slice())
+		.map_err(|e| Error::new(core::StsError, format!("Can't write encoded image: {}", e)))
+}
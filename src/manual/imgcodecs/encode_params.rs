@@ -0,0 +1,265 @@
+use crate::{
+	core::{self, Vector},
+	imgcodecs::{self, ImwriteEXRTypeFlags, ImwritePNGFlags},
+	Error, Result,
+};
+
+/// Chroma subsampling of the JPEG encoder, supported since OpenCV 4.7.0
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum JpegChromaSubsampling {
+	/// 4:1:1
+	S411,
+	/// 4:2:0
+	S420,
+	/// 4:2:2
+	S422,
+	/// 4:4:4, no subsampling
+	S444,
+}
+
+#[cfg(ocvrs_opencv_4_7_0)]
+impl JpegChromaSubsampling {
+	/// Value of the `IMWRITE_JPEG_SAMPLING_FACTOR` parameter
+	fn sampling_factor(self) -> i32 {
+		match self {
+			Self::S411 => imgcodecs::IMWRITE_JPEG_SAMPLING_FACTOR_411,
+			Self::S420 => imgcodecs::IMWRITE_JPEG_SAMPLING_FACTOR_420,
+			Self::S422 => imgcodecs::IMWRITE_JPEG_SAMPLING_FACTOR_422,
+			Self::S444 => imgcodecs::IMWRITE_JPEG_SAMPLING_FACTOR_444,
+		}
+	}
+}
+
+/// JPEG encoder parameters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct JpegParams {
+	/// Quality from 0 to 100, higher is better, OpenCV default is 95
+	pub quality: Option<u8>,
+	/// Write a progressive JPEG
+	pub progressive: bool,
+	/// Optimize the Huffman tables
+	pub optimize: bool,
+	/// Chroma subsampling, OpenCV default is 4:2:0, requires OpenCV 4.7.0
+	pub chroma_subsampling: Option<JpegChromaSubsampling>,
+}
+
+/// PNG encoder parameters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PngParams {
+	/// Compression level from 0 to 9, higher means smaller size and longer compression time, OpenCV default is 1
+	pub compression: Option<u8>,
+	/// Compression strategy
+	pub strategy: Option<ImwritePNGFlags>,
+	/// Write a binary (1 bit per pixel) image
+	pub bilevel: bool,
+}
+
+/// WebP encoder parameters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct WebpParams {
+	/// Quality from 1 to 100, higher is better, the default is lossless compression
+	pub quality: Option<u8>,
+}
+
+/// TIFF compression scheme, the values are the libtiff `COMPRESSION_*` constants
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TiffCompression {
+	None,
+	Lzw,
+	Jpeg,
+	Deflate,
+	PackBits,
+	/// Any other libtiff compression scheme
+	Other(i32),
+}
+
+impl TiffCompression {
+	fn code(self) -> i32 {
+		match self {
+			Self::None => 1,
+			Self::Lzw => 5,
+			Self::Jpeg => 7,
+			Self::Deflate => 8,
+			Self::PackBits => 32773,
+			Self::Other(code) => code,
+		}
+	}
+}
+
+/// OpenEXR compression scheme, supported since OpenCV 4.5.5
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ExrCompression {
+	None,
+	Rle,
+	Zips,
+	Zip,
+	Piz,
+	Pxr24,
+	B44,
+	B44a,
+	Dwaa,
+	Dwab,
+}
+
+#[cfg(ocvrs_opencv_4_5_5)]
+impl ExrCompression {
+	/// Value of the `IMWRITE_EXR_COMPRESSION` parameter
+	fn code(self) -> i32 {
+		match self {
+			Self::None => imgcodecs::IMWRITE_EXR_COMPRESSION_NO,
+			Self::Rle => imgcodecs::IMWRITE_EXR_COMPRESSION_RLE,
+			Self::Zips => imgcodecs::IMWRITE_EXR_COMPRESSION_ZIPS,
+			Self::Zip => imgcodecs::IMWRITE_EXR_COMPRESSION_ZIP,
+			Self::Piz => imgcodecs::IMWRITE_EXR_COMPRESSION_PIZ,
+			Self::Pxr24 => imgcodecs::IMWRITE_EXR_COMPRESSION_PXR24,
+			Self::B44 => imgcodecs::IMWRITE_EXR_COMPRESSION_B44,
+			Self::B44a => imgcodecs::IMWRITE_EXR_COMPRESSION_B44A,
+			Self::Dwaa => imgcodecs::IMWRITE_EXR_COMPRESSION_DWAA,
+			Self::Dwab => imgcodecs::IMWRITE_EXR_COMPRESSION_DWAB,
+		}
+	}
+}
+
+/// TIFF encoder parameters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TiffParams {
+	/// Compression scheme, OpenCV default is LZW
+	pub compression: Option<TiffCompression>,
+}
+
+/// OpenEXR encoder parameters
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExrParams {
+	/// Storage type of the pixel values
+	pub typ: Option<ImwriteEXRTypeFlags>,
+	/// Compression scheme, requires OpenCV 4.5.5
+	pub compression: Option<ExrCompression>,
+}
+
+/// Typed image encoding parameters, replacement for the flat list of `IMWRITE_*` key-value pairs
+///
+/// ## Example
+/// ```
+/// use opencv::imgcodecs::{EncodeParams, JpegParams};
+///
+/// let params = EncodeParams::Jpeg(JpegParams {
+///     quality: Some(85),
+///     progressive: true,
+///     ..JpegParams::default()
+/// });
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EncodeParams {
+	/// Use the defaults of the encoder chosen by the file extension
+	Default,
+	Jpeg(JpegParams),
+	Png(PngParams),
+	Webp(WebpParams),
+	Tiff(TiffParams),
+	Exr(ExrParams),
+}
+
+impl Default for EncodeParams {
+	fn default() -> Self {
+		Self::Default
+	}
+}
+
+fn check_range(name: &str, value: u8, min: u8, max: u8) -> Result<i32> {
+	if (min..=max).contains(&value) {
+		Ok(i32::from(value))
+	} else {
+		Err(Error::new(
+			core::StsOutOfRange,
+			format!("{} must be in range {}..={}, got: {}", name, min, max, value),
+		))
+	}
+}
+
+#[cfg(not(all(ocvrs_opencv_4_5_5, ocvrs_opencv_4_7_0)))]
+fn not_supported(name: &str, min_version: &str, value: impl std::fmt::Debug) -> Error {
+	Error::new(
+		core::StsNotImplemented,
+		format!("{} ({:?}) requires OpenCV {} or newer", name, value, min_version),
+	)
+}
+
+impl EncodeParams {
+	/// File extensions of the encoder that these parameters are meant for, empty for [EncodeParams::Default]
+	pub fn extensions(&self) -> &'static [&'static str] {
+		match self {
+			Self::Default => &[],
+			Self::Jpeg(_) => &["jpg", "jpeg", "jpe"],
+			Self::Png(_) => &["png"],
+			Self::Webp(_) => &["webp"],
+			Self::Tiff(_) => &["tif", "tiff"],
+			Self::Exr(_) => &["exr"],
+		}
+	}
+
+	/// Checks that the parameters can be used for the file extension `ext` (with or without the leading dot)
+	pub fn check_extension(&self, ext: &str) -> Result<()> {
+		let extensions = self.extensions();
+		let ext = ext.trim_start_matches('.');
+		if extensions.is_empty() || extensions.iter().any(|e| e.eq_ignore_ascii_case(ext)) {
+			Ok(())
+		} else {
+			Err(Error::new(
+				core::StsBadArg,
+				format!("Encoding parameters for {:?} can't be used for: {}", extensions, ext),
+			))
+		}
+	}
+
+	/// Validates the values and converts them into the list of `IMWRITE_*` key-value pairs accepted by `imwrite()`/`imencode()`
+	pub fn to_vector(&self) -> Result<Vector<i32>> {
+		let mut out = Vec::with_capacity(8);
+		match self {
+			Self::Default => {}
+			Self::Jpeg(params) => {
+				if let Some(quality) = params.quality {
+					out.extend([imgcodecs::IMWRITE_JPEG_QUALITY, check_range("JPEG quality", quality, 0, 100)?]);
+				}
+				out.extend([imgcodecs::IMWRITE_JPEG_PROGRESSIVE, i32::from(params.progressive)]);
+				out.extend([imgcodecs::IMWRITE_JPEG_OPTIMIZE, i32::from(params.optimize)]);
+				if let Some(subsampling) = params.chroma_subsampling {
+					#[cfg(ocvrs_opencv_4_7_0)]
+					out.extend([imgcodecs::IMWRITE_JPEG_SAMPLING_FACTOR, subsampling.sampling_factor()]);
+					#[cfg(not(ocvrs_opencv_4_7_0))]
+					return Err(not_supported("JPEG chroma subsampling", "4.7.0", subsampling));
+				}
+			}
+			Self::Png(params) => {
+				if let Some(compression) = params.compression {
+					out.extend([imgcodecs::IMWRITE_PNG_COMPRESSION, check_range("PNG compression", compression, 0, 9)?]);
+				}
+				if let Some(strategy) = params.strategy {
+					out.extend([imgcodecs::IMWRITE_PNG_STRATEGY, strategy as i32]);
+				}
+				out.extend([imgcodecs::IMWRITE_PNG_BILEVEL, i32::from(params.bilevel)]);
+			}
+			Self::Webp(params) => {
+				// values above 100 mean lossless compression
+				let quality = params.quality.map_or(Ok(101), |quality| check_range("WebP quality", quality, 1, 100))?;
+				out.extend([imgcodecs::IMWRITE_WEBP_QUALITY, quality]);
+			}
+			Self::Tiff(params) => {
+				if let Some(compression) = params.compression {
+					out.extend([imgcodecs::IMWRITE_TIFF_COMPRESSION, compression.code()]);
+				}
+			}
+			Self::Exr(params) => {
+				if let Some(typ) = params.typ {
+					out.extend([imgcodecs::IMWRITE_EXR_TYPE, typ as i32]);
+				}
+				if let Some(compression) = params.compression {
+					#[cfg(ocvrs_opencv_4_5_5)]
+					out.extend([imgcodecs::IMWRITE_EXR_COMPRESSION, compression.code()]);
+					#[cfg(not(ocvrs_opencv_4_5_5))]
+					return Err(not_supported("OpenEXR compression", "4.5.5", compression));
+				}
+			}
+		}
+		Ok(Vector::from_iter(out))
+	}
+}
@@ -0,0 +1,217 @@
+use std::fs;
+
+use crate::{
+	core::{self, Mat},
+	imgcodecs::{self, EncodeParams, ImreadModes},
+	Error, Result,
+};
+
+pub(super) mod exif;
+pub(super) mod jpeg;
+pub(super) mod png;
+pub(super) mod webp;
+
+/// Orientation of the stored pixels as specified by the EXIF `Orientation` tag
+///
+/// Each variant describes the transformation that [apply_orientation] performs to get the image displayed correctly.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Orientation {
+	/// No transformation is needed
+	Normal = 1,
+	/// Mirror around the vertical axis
+	FlipHorizontal = 2,
+	/// Rotate by 180 degrees
+	Rotate180 = 3,
+	/// Mirror around the horizontal axis
+	FlipVertical = 4,
+	/// Mirror around the main (top-left to bottom-right) diagonal
+	Transpose = 5,
+	/// Rotate by 90 degrees clockwise
+	Rotate90 = 6,
+	/// Mirror around the secondary (top-right to bottom-left) diagonal
+	Transverse = 7,
+	/// Rotate by 270 degrees clockwise
+	Rotate270 = 8,
+}
+
+impl Default for Orientation {
+	fn default() -> Self {
+		Self::Normal
+	}
+}
+
+impl Orientation {
+	/// Converts the value of the EXIF `Orientation` tag, returns `None` for values outside of `1..=8`
+	pub fn from_exif(value: u16) -> Option<Self> {
+		match value {
+			1 => Some(Self::Normal),
+			2 => Some(Self::FlipHorizontal),
+			3 => Some(Self::Rotate180),
+			4 => Some(Self::FlipVertical),
+			5 => Some(Self::Transpose),
+			6 => Some(Self::Rotate90),
+			7 => Some(Self::Transverse),
+			8 => Some(Self::Rotate270),
+			_ => None,
+		}
+	}
+
+	/// Value of the EXIF `Orientation` tag
+	pub fn to_exif(self) -> u16 {
+		self as u16
+	}
+
+	/// Returns `true` if applying the orientation swaps the width and the height of the image
+	pub fn swaps_dimensions(self) -> bool {
+		matches!(self, Self::Transpose | Self::Rotate90 | Self::Transverse | Self::Rotate270)
+	}
+}
+
+/// GPS position where the image was taken
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpsPosition {
+	/// Degrees, positive to the north of the equator
+	pub latitude: f64,
+	/// Degrees, positive to the east of the prime meridian
+	pub longitude: f64,
+	/// Meters, negative below the sea level
+	pub altitude: Option<f64>,
+}
+
+/// Horizontal and vertical pixel density in dots per inch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Dpi {
+	pub x: f64,
+	pub y: f64,
+}
+
+/// Image metadata that OpenCV doesn't expose, parsed natively from the encoded image
+///
+/// Supported containers are JPEG (JFIF, EXIF, ICC profile and comments), PNG (`eXIf`, `iCCP`, `pHYs` and text chunks), TIFF (the
+/// tags of the first page) and WebP (EXIF and ICC profile). Images in other formats have no metadata.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageMetadata {
+	/// EXIF orientation, `None` if the image doesn't specify it which is equivalent to [Orientation::Normal]
+	pub orientation: Option<Orientation>,
+	/// Date and time when the image was taken in the EXIF format `YYYY:MM:DD HH:MM:SS`
+	pub datetime: Option<String>,
+	/// Manufacturer of the camera
+	pub camera_make: Option<String>,
+	/// Model of the camera
+	pub camera_model: Option<String>,
+	pub gps: Option<GpsPosition>,
+	pub dpi: Option<Dpi>,
+	/// Raw ICC color profile
+	pub icc_profile: Option<Vec<u8>>,
+	/// Textual key-value pairs in the file order, JPEG comments have the key `"Comment"`
+	pub text: Vec<(String, String)>,
+}
+
+impl ImageMetadata {
+	/// Parses the metadata of the encoded image without decoding the pixels
+	///
+	/// Returns empty metadata for the images in unsupported formats and fails with `StsParseError` if the metadata is malformed.
+	pub fn parse(buf: &[u8]) -> Result<Self> {
+		let mut out = Self::default();
+		if jpeg::is_jpeg(buf) {
+			jpeg::parse(buf, &mut out)?;
+		} else if png::is_png(buf) {
+			png::parse(buf, &mut out)?;
+		} else if webp::is_webp(buf) {
+			webp::parse(buf, &mut out)?;
+		} else if exif::is_tiff(buf) {
+			exif::parse(buf, &mut out)?;
+		}
+		Ok(out)
+	}
+
+	/// Returns `true` if none of the fields is set
+	pub fn is_empty(&self) -> bool {
+		*self == Self::default()
+	}
+}
+
+fn parse_error(msg: &str) -> Error {
+	Error::new(core::StsParseError, format!("Can't parse image metadata: {}", msg))
+}
+
+/// Decodes the image and parses its metadata, the orientation is not applied to the decoded image
+///
+/// Use [apply_orientation] to rotate the image into the displayed position.
+pub fn decode_with_metadata(buf: &[u8], flags: ImreadModes) -> Result<(Mat, ImageMetadata)> {
+	let metadata = ImageMetadata::parse(buf)?;
+	let img = super::codec::decode_raw(buf, flags as i32 | imgcodecs::IMREAD_IGNORE_ORIENTATION)?;
+	Ok((img, metadata))
+}
+
+/// Reads the image as `IMREAD_COLOR` together with its metadata, the orientation is not applied to the decoded image
+///
+/// ## Example
+/// ```no_run
+/// use opencv::imgcodecs;
+///
+/// let (mut img, metadata) = imgcodecs::read_with_metadata("photo.jpg")?;
+/// if let Some(orientation) = metadata.orientation {
+///     imgcodecs::apply_orientation(&mut img, orientation)?;
+/// }
+/// println!("Taken at {:?} with {:?}", metadata.datetime, metadata.camera_model);
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub fn read_with_metadata(filename: &str) -> Result<(Mat, ImageMetadata)> {
+	let buf = fs::read(filename).map_err(|e| Error::new(core::StsError, format!("Can't read image: {}: {}", filename, e)))?;
+	decode_with_metadata(&buf, ImreadModes::IMREAD_COLOR)
+}
+
+/// Encodes the image into the format specified by the file extension `ext` and embeds the metadata
+///
+/// Metadata can be written into JPEG and PNG images, for the other formats only empty metadata is accepted. JPEG has no
+/// key-value text storage so the values of [ImageMetadata::text] are written as comments.
+pub fn encode_with_metadata(ext: &str, img: &Mat, params: &EncodeParams, metadata: &ImageMetadata) -> Result<Vec<u8>> {
+	let mut buf = vec![];
+	imgcodecs::encode_to(&mut buf, ext, img, params)?;
+	if metadata.is_empty() {
+		Ok(buf)
+	} else if jpeg::is_jpeg(&buf) {
+		jpeg::insert(&buf, metadata)
+	} else if png::is_png(&buf) {
+		png::insert(&buf, metadata)
+	} else {
+		Err(Error::new(
+			core::StsNotImplemented,
+			format!("Writing image metadata is not supported for: {}", ext),
+		))
+	}
+}
+
+/// Writes the image into the file together with the metadata, see [encode_with_metadata]
+pub fn write_with_metadata(filename: &str, img: &Mat, params: &EncodeParams, metadata: &ImageMetadata) -> Result<()> {
+	let ext = filename.rfind('.').map(|pos| &filename[pos..]).ok_or_else(|| {
+		Error::new(
+			core::StsBadArg,
+			format!("Can't determine image format without extension: {}", filename),
+		)
+	})?;
+	let buf = encode_with_metadata(ext, img, params, metadata)?;
+	fs::write(filename, buf).map_err(|e| Error::new(core::StsError, format!("Can't write image: {}: {}", filename, e)))
+}
+
+/// Transforms the image decoded with the orientation ignored into the position it's supposed to be displayed in
+pub fn apply_orientation(img: &mut Mat, orientation: Orientation) -> Result<()> {
+	let mut out = Mat::default();
+	match orientation {
+		Orientation::Normal => return Ok(()),
+		Orientation::FlipHorizontal => core::flip(img, &mut out, 1)?,
+		Orientation::Rotate180 => core::rotate(img, &mut out, core::ROTATE_180)?,
+		Orientation::FlipVertical => core::flip(img, &mut out, 0)?,
+		Orientation::Transpose => core::transpose(img, &mut out)?,
+		Orientation::Rotate90 => core::rotate(img, &mut out, core::ROTATE_90_CLOCKWISE)?,
+		Orientation::Transverse => {
+			let mut transposed = Mat::default();
+			core::transpose(img, &mut transposed)?;
+			core::flip(&transposed, &mut out, -1)?;
+		}
+		Orientation::Rotate270 => core::rotate(img, &mut out, core::ROTATE_90_COUNTERCLOCKWISE)?,
+	}
+	*img = out;
+	Ok(())
+}
@@ -0,0 +1,430 @@
+//! EXIF is stored as a TIFF structure, so the same code handles both the EXIF blocks and the tags of the TIFF images
+
+use std::convert::TryFrom;
+
+use super::{parse_error, Dpi, GpsPosition, ImageMetadata, Orientation};
+use crate::Result;
+
+const TYPE_BYTE: u16 = 1;
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+const TYPE_UNDEFINED: u16 = 7;
+const TYPE_SRATIONAL: u16 = 10;
+
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_ORIENTATION: u16 = 0x0112;
+const TAG_X_RESOLUTION: u16 = 0x011A;
+const TAG_Y_RESOLUTION: u16 = 0x011B;
+const TAG_RESOLUTION_UNIT: u16 = 0x0128;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_ICC_PROFILE: u16 = 0x8773;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+
+const TAG_GPS_VERSION_ID: u16 = 0x0000;
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+const TAG_GPS_ALTITUDE_REF: u16 = 0x0005;
+const TAG_GPS_ALTITUDE: u16 = 0x0006;
+
+const RESOLUTION_UNIT_INCH: u32 = 2;
+const RESOLUTION_UNIT_CM: u32 = 3;
+
+pub fn is_tiff(buf: &[u8]) -> bool {
+	buf.starts_with(b"II*\0") || buf.starts_with(b"MM\0*")
+}
+
+fn type_size(typ: u16) -> Option<usize> {
+	match typ {
+		TYPE_BYTE | TYPE_ASCII | TYPE_UNDEFINED | 6 => Some(1),
+		TYPE_SHORT | 8 => Some(2),
+		TYPE_LONG | 9 | 11 => Some(4),
+		TYPE_RATIONAL | TYPE_SRATIONAL | 12 => Some(8),
+		_ => None,
+	}
+}
+
+pub struct Entry<'d> {
+	pub tag: u16,
+	pub typ: u16,
+	pub value: &'d [u8],
+}
+
+pub struct Tiff<'d> {
+	data: &'d [u8],
+	little_endian: bool,
+}
+
+impl<'d> Tiff<'d> {
+	pub fn new(data: &'d [u8]) -> Result<Self> {
+		if !is_tiff(data) {
+			return Err(parse_error("invalid TIFF header"));
+		}
+		Ok(Self {
+			data,
+			little_endian: data[0] == b'I',
+		})
+	}
+
+	fn bytes(&self, offset: usize, len: usize) -> Result<&'d [u8]> {
+		offset
+			.checked_add(len)
+			.and_then(|end| self.data.get(offset..end))
+			.ok_or_else(|| parse_error("EXIF data is truncated"))
+	}
+
+	fn u16(&self, bytes: &[u8]) -> u16 {
+		let bytes = [bytes[0], bytes[1]];
+		if self.little_endian {
+			u16::from_le_bytes(bytes)
+		} else {
+			u16::from_be_bytes(bytes)
+		}
+	}
+
+	fn u32(&self, bytes: &[u8]) -> u32 {
+		let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+		if self.little_endian {
+			u32::from_le_bytes(bytes)
+		} else {
+			u32::from_be_bytes(bytes)
+		}
+	}
+
+	/// Offset of the first IFD
+	pub fn first_ifd(&self) -> Result<u32> {
+		Ok(self.u32(self.bytes(4, 4)?))
+	}
+
+	/// Offset of the IFD following the one at `offset`, 0 if it's the last one
+	pub fn next_ifd(&self, offset: u32) -> Result<u32> {
+		let offset = offset as usize;
+		let count = usize::from(self.u16(self.bytes(offset, 2)?));
+		Ok(self.u32(self.bytes(offset + 2 + count * 12, 4)?))
+	}
+
+	pub fn entries(&self, offset: u32) -> Result<Vec<Entry<'d>>> {
+		let offset = offset as usize;
+		let count = usize::from(self.u16(self.bytes(offset, 2)?));
+		let ifd = self.bytes(offset + 2, count * 12)?;
+		let mut out = Vec::with_capacity(count);
+		for entry in ifd.chunks_exact(12) {
+			let typ = self.u16(&entry[2..]);
+			// entries of unknown types are skipped as the TIFF specification requires, entries pointing outside of the data (usually
+			// broken vendor tags) are skipped too so that they don't prevent reading the rest
+			let len = type_size(typ).and_then(|size| (self.u32(&entry[4..]) as usize).checked_mul(size));
+			let value = match len {
+				Some(len) if len <= 4 => &entry[8..8 + len],
+				Some(len) => match self.bytes(self.u32(&entry[8..]) as usize, len) {
+					Ok(value) => value,
+					Err(_) => continue,
+				},
+				None => continue,
+			};
+			out.push(Entry {
+				tag: self.u16(entry),
+				typ,
+				value,
+			});
+		}
+		Ok(out)
+	}
+
+	pub fn uint(&self, entry: &Entry, i: usize) -> Option<u32> {
+		match entry.typ {
+			TYPE_BYTE => entry.value.get(i).map(|&v| u32::from(v)),
+			TYPE_SHORT => entry.value.get(i * 2..i * 2 + 2).map(|v| u32::from(self.u16(v))),
+			TYPE_LONG => entry.value.get(i * 4..i * 4 + 4).map(|v| self.u32(v)),
+			_ => None,
+		}
+	}
+
+	fn rational(&self, entry: &Entry, i: usize) -> Option<f64> {
+		let value = entry.value.get(i * 8..i * 8 + 8)?;
+		let (num, den) = match entry.typ {
+			TYPE_RATIONAL => (f64::from(self.u32(value)), f64::from(self.u32(&value[4..]))),
+			TYPE_SRATIONAL => (f64::from(self.u32(value) as i32), f64::from(self.u32(&value[4..]) as i32)),
+			_ => return None,
+		};
+		if den == 0. {
+			None
+		} else {
+			Some(num / den)
+		}
+	}
+
+	fn ascii(&self, entry: &Entry) -> Option<String> {
+		if entry.typ != TYPE_ASCII {
+			return None;
+		}
+		let value = entry.value.split(|&b| b == 0).next().unwrap_or_default();
+		let value = String::from_utf8_lossy(value).trim().to_string();
+		if value.is_empty() {
+			None
+		} else {
+			Some(value)
+		}
+	}
+
+	fn degrees(&self, entry: &Entry) -> Option<f64> {
+		Some(self.rational(entry, 0)? + self.rational(entry, 1).unwrap_or(0.) / 60. + self.rational(entry, 2).unwrap_or(0.) / 3600.)
+	}
+
+	fn gps(&self, offset: u32) -> Result<Option<GpsPosition>> {
+		let mut latitude = None;
+		let mut longitude = None;
+		let mut altitude = None;
+		let mut latitude_sign = 1.;
+		let mut longitude_sign = 1.;
+		let mut altitude_sign = 1.;
+		for entry in self.entries(offset)? {
+			match entry.tag {
+				TAG_GPS_LATITUDE_REF if entry.value.first() == Some(&b'S') => latitude_sign = -1.,
+				TAG_GPS_LATITUDE => latitude = self.degrees(&entry),
+				TAG_GPS_LONGITUDE_REF if entry.value.first() == Some(&b'W') => longitude_sign = -1.,
+				TAG_GPS_LONGITUDE => longitude = self.degrees(&entry),
+				TAG_GPS_ALTITUDE_REF if self.uint(&entry, 0) == Some(1) => altitude_sign = -1.,
+				TAG_GPS_ALTITUDE => altitude = self.rational(&entry, 0),
+				_ => {}
+			}
+		}
+		Ok(latitude.zip(longitude).map(|(latitude, longitude)| GpsPosition {
+			latitude: latitude * latitude_sign,
+			longitude: longitude * longitude_sign,
+			altitude: altitude.map(|altitude| altitude * altitude_sign),
+		}))
+	}
+}
+
+/// Parses the EXIF block (TIFF header and the following IFDs) into `out`
+pub fn parse(data: &[u8], out: &mut ImageMetadata) -> Result<()> {
+	let tiff = Tiff::new(data)?;
+	let mut x_resolution = None;
+	let mut y_resolution = None;
+	let mut resolution_unit = RESOLUTION_UNIT_INCH;
+	let mut datetime = None;
+	let mut datetime_original = None;
+	for entry in tiff.entries(tiff.first_ifd()?)? {
+		match entry.tag {
+			TAG_MAKE => out.camera_make = tiff.ascii(&entry),
+			TAG_MODEL => out.camera_model = tiff.ascii(&entry),
+			TAG_ORIENTATION => {
+				out.orientation = tiff
+					.uint(&entry, 0)
+					.and_then(|v| u16::try_from(v).ok())
+					.and_then(Orientation::from_exif)
+			}
+			TAG_X_RESOLUTION => x_resolution = tiff.rational(&entry, 0),
+			TAG_Y_RESOLUTION => y_resolution = tiff.rational(&entry, 0),
+			TAG_RESOLUTION_UNIT => resolution_unit = tiff.uint(&entry, 0).unwrap_or(RESOLUTION_UNIT_INCH),
+			TAG_DATE_TIME => datetime = tiff.ascii(&entry),
+			TAG_ICC_PROFILE => out.icc_profile = Some(entry.value.to_vec()),
+			TAG_EXIF_IFD => {
+				if let Some(offset) = tiff.uint(&entry, 0) {
+					if let Some(entry) = tiff.entries(offset)?.iter().find(|e| e.tag == TAG_DATE_TIME_ORIGINAL) {
+						datetime_original = tiff.ascii(entry);
+					}
+				}
+			}
+			TAG_GPS_IFD => {
+				if let Some(offset) = tiff.uint(&entry, 0) {
+					out.gps = tiff.gps(offset)?;
+				}
+			}
+			_ => {}
+		}
+	}
+	let scale = match resolution_unit {
+		RESOLUTION_UNIT_INCH => Some(1.),
+		RESOLUTION_UNIT_CM => Some(2.54),
+		_ => None,
+	};
+	if let (Some(x), Some(y), Some(scale)) = (x_resolution, y_resolution, scale) {
+		out.dpi = Some(Dpi {
+			x: x * scale,
+			y: y * scale,
+		});
+	}
+	if let Some(datetime) = datetime_original.or(datetime) {
+		out.datetime = Some(datetime);
+	}
+	Ok(())
+}
+
+#[derive(Default)]
+struct IfdBuilder {
+	/// Tag, type, count and the little-endian value
+	entries: Vec<(u16, u16, u32, Vec<u8>)>,
+}
+
+impl IfdBuilder {
+	fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	fn ascii(&mut self, tag: u16, value: &str) {
+		let mut bytes = value.as_bytes().to_vec();
+		bytes.push(0);
+		self.entries.push((tag, TYPE_ASCII, bytes.len() as u32, bytes));
+	}
+
+	fn bytes(&mut self, tag: u16, value: &[u8]) {
+		self.entries.push((tag, TYPE_BYTE, value.len() as u32, value.to_vec()));
+	}
+
+	fn short(&mut self, tag: u16, value: u16) {
+		self.entries.push((tag, TYPE_SHORT, 1, value.to_le_bytes().to_vec()));
+	}
+
+	fn long(&mut self, tag: u16, value: u32) {
+		self.entries.push((tag, TYPE_LONG, 1, value.to_le_bytes().to_vec()));
+	}
+
+	fn set_long(&mut self, tag: u16, value: u32) {
+		if let Some(entry) = self.entries.iter_mut().find(|e| e.0 == tag) {
+			entry.3 = value.to_le_bytes().to_vec();
+		}
+	}
+
+	fn rationals(&mut self, tag: u16, values: &[f64]) {
+		let mut bytes = Vec::with_capacity(values.len() * 8);
+		for &value in values {
+			let (num, den) = to_rational(value);
+			bytes.extend(num.to_le_bytes());
+			bytes.extend(den.to_le_bytes());
+		}
+		self.entries.push((tag, TYPE_RATIONAL, values.len() as u32, bytes));
+	}
+
+	fn size(&self) -> usize {
+		2 + 12 * self.entries.len() + 4 + self.entries.iter().map(|e| padded_len(&e.3)).sum::<usize>()
+	}
+
+	/// Writes the IFD at the current end of `out` that must start at the TIFF header
+	fn write(mut self, out: &mut Vec<u8>) {
+		self.entries.sort_by_key(|e| e.0);
+		let mut data_offset = out.len() + 2 + 12 * self.entries.len() + 4;
+		let mut data = vec![];
+		out.extend((self.entries.len() as u16).to_le_bytes());
+		for (tag, typ, count, value) in &self.entries {
+			out.extend(tag.to_le_bytes());
+			out.extend(typ.to_le_bytes());
+			out.extend(count.to_le_bytes());
+			if value.len() <= 4 {
+				out.extend(value);
+				out.extend(&[0; 4][value.len()..]);
+			} else {
+				out.extend((data_offset as u32).to_le_bytes());
+				data.extend(value);
+				data.resize(data.len() + padded_len(value) - value.len(), 0);
+				data_offset += padded_len(value);
+			}
+		}
+		out.extend(0u32.to_le_bytes());
+		out.extend(data);
+	}
+}
+
+fn padded_len(value: &[u8]) -> usize {
+	if value.len() <= 4 {
+		0
+	} else {
+		value.len() + value.len() % 2
+	}
+}
+
+fn to_rational(value: f64) -> (u32, u32) {
+	let value = value.abs();
+	if value.fract() == 0. && value <= f64::from(u32::MAX) {
+		(value as u32, 1)
+	} else {
+		let den = 10_000;
+		((value * f64::from(den)).round().min(f64::from(u32::MAX)) as u32, den)
+	}
+}
+
+fn to_dms(degrees: f64) -> [f64; 3] {
+	let degrees = degrees.abs();
+	let minutes = degrees.fract() * 60.;
+	[degrees.trunc(), minutes.trunc(), minutes.fract() * 60.]
+}
+
+/// Serializes the EXIF fields of the metadata into a little-endian TIFF structure, `None` if there is nothing to write
+pub fn write(metadata: &ImageMetadata) -> Option<Vec<u8>> {
+	let mut ifd0 = IfdBuilder::default();
+	let mut exif_ifd = IfdBuilder::default();
+	let mut gps_ifd = IfdBuilder::default();
+	if let Some(make) = &metadata.camera_make {
+		ifd0.ascii(TAG_MAKE, make);
+	}
+	if let Some(model) = &metadata.camera_model {
+		ifd0.ascii(TAG_MODEL, model);
+	}
+	if let Some(orientation) = metadata.orientation {
+		ifd0.short(TAG_ORIENTATION, orientation.to_exif());
+	}
+	if let Some(dpi) = metadata.dpi {
+		ifd0.rationals(TAG_X_RESOLUTION, &[dpi.x]);
+		ifd0.rationals(TAG_Y_RESOLUTION, &[dpi.y]);
+		ifd0.short(TAG_RESOLUTION_UNIT, RESOLUTION_UNIT_INCH as u16);
+	}
+	if let Some(datetime) = &metadata.datetime {
+		ifd0.ascii(TAG_DATE_TIME, datetime);
+		exif_ifd.ascii(TAG_DATE_TIME_ORIGINAL, datetime);
+	}
+	if let Some(gps) = metadata.gps {
+		gps_ifd.bytes(TAG_GPS_VERSION_ID, &[2, 3, 0, 0]);
+		let latitude_ref = if gps.latitude < 0. {
+			"S"
+		} else {
+			"N"
+		};
+		let longitude_ref = if gps.longitude < 0. {
+			"W"
+		} else {
+			"E"
+		};
+		gps_ifd.ascii(TAG_GPS_LATITUDE_REF, latitude_ref);
+		gps_ifd.rationals(TAG_GPS_LATITUDE, &to_dms(gps.latitude));
+		gps_ifd.ascii(TAG_GPS_LONGITUDE_REF, longitude_ref);
+		gps_ifd.rationals(TAG_GPS_LONGITUDE, &to_dms(gps.longitude));
+		if let Some(altitude) = gps.altitude {
+			gps_ifd.bytes(TAG_GPS_ALTITUDE_REF, &[u8::from(altitude < 0.)]);
+			gps_ifd.rationals(TAG_GPS_ALTITUDE, &[altitude]);
+		}
+	}
+	if !exif_ifd.is_empty() {
+		ifd0.long(TAG_EXIF_IFD, 0);
+	}
+	if !gps_ifd.is_empty() {
+		ifd0.long(TAG_GPS_IFD, 0);
+	}
+	if ifd0.is_empty() {
+		return None;
+	}
+	let exif_offset = 8 + ifd0.size();
+	let gps_offset = exif_offset
+		+ if exif_ifd.is_empty() {
+			0
+		} else {
+			exif_ifd.size()
+		};
+	ifd0.set_long(TAG_EXIF_IFD, exif_offset as u32);
+	ifd0.set_long(TAG_GPS_IFD, gps_offset as u32);
+
+	let mut out = b"II*\0".to_vec();
+	out.extend(8u32.to_le_bytes());
+	ifd0.write(&mut out);
+	for ifd in [exif_ifd, gps_ifd] {
+		if !ifd.is_empty() {
+			ifd.write(&mut out);
+		}
+	}
+	Some(out)
+}
@@ -0,0 +1,177 @@
+use std::{convert::TryFrom, ops::Range};
+
+use super::{exif, parse_error, Dpi, ImageMetadata};
+use crate::{core, Error, Result};
+
+const MARKER_SOI: u8 = 0xD8;
+const MARKER_EOI: u8 = 0xD9;
+const MARKER_SOS: u8 = 0xDA;
+const MARKER_APP0: u8 = 0xE0;
+const MARKER_APP1: u8 = 0xE1;
+const MARKER_APP2: u8 = 0xE2;
+const MARKER_COM: u8 = 0xFE;
+
+const JFIF_ID: &[u8] = b"JFIF\0";
+const EXIF_ID: &[u8] = b"Exif\0\0";
+const ICC_ID: &[u8] = b"ICC_PROFILE\0";
+
+/// Maximum payload of a segment, the length field counts itself
+const MAX_SEGMENT_LEN: usize = u16::MAX as usize - 2;
+/// ICC profile chunks are prefixed by the identifier, the chunk index and the chunk count
+const MAX_ICC_CHUNK_LEN: usize = MAX_SEGMENT_LEN - ICC_ID.len() - 2;
+
+pub fn is_jpeg(buf: &[u8]) -> bool {
+	buf.starts_with(&[0xFF, MARKER_SOI, 0xFF])
+}
+
+pub struct Segment<'d> {
+	pub marker: u8,
+	/// Range of the whole segment including the marker
+	pub range: Range<usize>,
+	pub payload: &'d [u8],
+}
+
+/// Splits the header of the JPEG into segments, returns them together with the offset of the image data (SOS or EOI marker)
+pub fn segments(buf: &[u8]) -> Result<(Vec<Segment<'_>>, usize)> {
+	let mut out = vec![];
+	let mut pos = 2;
+	loop {
+		let start = pos;
+		if buf.get(pos) != Some(&0xFF) {
+			return Err(parse_error("invalid JPEG marker"));
+		}
+		// markers can be preceded by any number of fill bytes
+		while buf.get(pos) == Some(&0xFF) {
+			pos += 1;
+		}
+		let marker = *buf.get(pos).ok_or_else(|| parse_error("JPEG data is truncated"))?;
+		pos += 1;
+		match marker {
+			MARKER_SOS | MARKER_EOI => return Ok((out, start)),
+			// standalone markers without payload
+			MARKER_SOI | 0x01 | 0xD0..=0xD7 => {}
+			_ => {
+				let len = buf
+					.get(pos..pos + 2)
+					.map(|len| usize::from(u16::from_be_bytes([len[0], len[1]])))
+					.filter(|&len| len >= 2)
+					.ok_or_else(|| parse_error("invalid JPEG segment length"))?;
+				let payload = buf
+					.get(pos + 2..pos + len)
+					.ok_or_else(|| parse_error("JPEG data is truncated"))?;
+				pos += len;
+				out.push(Segment {
+					marker,
+					range: start..pos,
+					payload,
+				});
+			}
+		}
+	}
+}
+
+fn jfif_dpi(payload: &[u8]) -> Option<Dpi> {
+	let density = payload.get(JFIF_ID.len() + 2..JFIF_ID.len() + 7)?;
+	let x = f64::from(u16::from_be_bytes([density[1], density[2]]));
+	let y = f64::from(u16::from_be_bytes([density[3], density[4]]));
+	match density[0] {
+		1 => Some(Dpi { x, y }),
+		2 => Some(Dpi {
+			x: x * 2.54,
+			y: y * 2.54,
+		}),
+		// the values only specify the aspect ratio
+		_ => None,
+	}
+}
+
+pub fn parse(buf: &[u8], out: &mut ImageMetadata) -> Result<()> {
+	let (segments, _) = segments(buf)?;
+	let mut icc_chunks = vec![];
+	let mut jfif_dpi_value = None;
+	for segment in segments {
+		let payload = segment.payload;
+		match segment.marker {
+			MARKER_APP0 if payload.starts_with(JFIF_ID) => jfif_dpi_value = jfif_dpi(payload),
+			MARKER_APP1 if payload.starts_with(EXIF_ID) => exif::parse(&payload[EXIF_ID.len()..], out)?,
+			MARKER_APP2 if payload.starts_with(ICC_ID) && payload.len() >= ICC_ID.len() + 2 => {
+				icc_chunks.push((payload[ICC_ID.len()], &payload[ICC_ID.len() + 2..]));
+			}
+			MARKER_COM => out
+				.text
+				.push(("Comment".to_string(), String::from_utf8_lossy(payload).into_owned())),
+			_ => {}
+		}
+	}
+	if !icc_chunks.is_empty() {
+		icc_chunks.sort_by_key(|chunk| chunk.0);
+		out.icc_profile = Some(icc_chunks.into_iter().flat_map(|chunk| chunk.1).copied().collect());
+	}
+	// EXIF resolution takes precedence
+	if out.dpi.is_none() {
+		out.dpi = jfif_dpi_value;
+	}
+	Ok(())
+}
+
+fn write_segment(out: &mut Vec<u8>, marker: u8, payload: &[&[u8]]) -> Result<()> {
+	let len = payload.iter().map(|p| p.len()).sum::<usize>();
+	if len > MAX_SEGMENT_LEN {
+		return Err(Error::new(
+			core::StsOutOfRange,
+			format!("JPEG segment is too large: {} bytes, maximum is: {}", len, MAX_SEGMENT_LEN),
+		));
+	}
+	out.extend([0xFF, marker]);
+	out.extend((len as u16 + 2).to_be_bytes());
+	for part in payload {
+		out.extend(*part);
+	}
+	Ok(())
+}
+
+/// Inserts the metadata into the encoded JPEG replacing the EXIF and ICC profile segments that are already there
+pub fn insert(buf: &[u8], metadata: &ImageMetadata) -> Result<Vec<u8>> {
+	let (segments, data_start) = segments(buf)?;
+	let mut out = Vec::with_capacity(buf.len() + 1024);
+	out.extend([0xFF, MARKER_SOI]);
+	let mut segments = segments.into_iter().peekable();
+	// JFIF must stay the first segment
+	if let Some(jfif) = segments.next_if(|s| s.marker == MARKER_APP0 && s.payload.starts_with(JFIF_ID)) {
+		let mut payload = jfif.payload.to_vec();
+		if let (Some(dpi), Some(density)) = (metadata.dpi, payload.get_mut(JFIF_ID.len() + 2..JFIF_ID.len() + 7)) {
+			let x = u16::try_from(dpi.x.round() as i64).unwrap_or(u16::MAX);
+			let y = u16::try_from(dpi.y.round() as i64).unwrap_or(u16::MAX);
+			density[0] = 1;
+			density[1..3].copy_from_slice(&x.to_be_bytes());
+			density[3..5].copy_from_slice(&y.to_be_bytes());
+		}
+		write_segment(&mut out, MARKER_APP0, &[&payload])?;
+	}
+	if let Some(exif) = exif::write(metadata) {
+		write_segment(&mut out, MARKER_APP1, &[EXIF_ID, &exif])?;
+	}
+	if let Some(icc_profile) = &metadata.icc_profile {
+		let count = u8::try_from(icc_profile.chunks(MAX_ICC_CHUNK_LEN).len()).map_err(|_| {
+			Error::new(
+				core::StsOutOfRange,
+				format!("ICC profile is too large: {} bytes", icc_profile.len()),
+			)
+		})?;
+		for (i, chunk) in icc_profile.chunks(MAX_ICC_CHUNK_LEN).enumerate() {
+			write_segment(&mut out, MARKER_APP2, &[ICC_ID, &[i as u8 + 1, count], chunk])?;
+		}
+	}
+	for (_, comment) in &metadata.text {
+		write_segment(&mut out, MARKER_COM, &[comment.as_bytes()])?;
+	}
+	for segment in segments {
+		let replaced = (segment.marker == MARKER_APP1 && segment.payload.starts_with(EXIF_ID))
+			|| (segment.marker == MARKER_APP2 && segment.payload.starts_with(ICC_ID));
+		if !replaced {
+			out.extend(&buf[segment.range]);
+		}
+	}
+	out.extend(&buf[data_start..]);
+	Ok(out)
+}
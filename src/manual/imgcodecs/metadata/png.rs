@@ -0,0 +1,204 @@
+use std::convert::TryFrom;
+
+use miniz_oxide::{deflate::compress_to_vec_zlib, inflate::decompress_to_vec_zlib_with_limit};
+
+use super::{exif, parse_error, Dpi, ImageMetadata};
+use crate::{core, manual::core::checksum::crc32, Error, Result};
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Limit for the decompressed size of a single chunk to protect against decompression bombs
+const MAX_INFLATED_LEN: usize = 64 * 1024 * 1024;
+
+const METERS_PER_INCH: f64 = 0.0254;
+
+pub fn is_png(buf: &[u8]) -> bool {
+	buf.starts_with(SIGNATURE)
+}
+
+struct Chunk<'d> {
+	typ: &'d [u8],
+	/// The whole chunk including the length, the type and the CRC
+	raw: &'d [u8],
+	data: &'d [u8],
+}
+
+fn chunks(buf: &[u8]) -> Result<Vec<Chunk<'_>>> {
+	let mut out = vec![];
+	let mut pos = SIGNATURE.len();
+	while pos < buf.len() {
+		let header = buf
+			.get(pos..pos + 8)
+			.ok_or_else(|| parse_error("PNG chunk header is truncated"))?;
+		let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+		let raw = len
+			.checked_add(pos + 12)
+			.and_then(|end| buf.get(pos..end))
+			.ok_or_else(|| parse_error("PNG chunk is truncated"))?;
+		let typ = &header[4..];
+		out.push(Chunk {
+			typ,
+			raw,
+			data: &raw[8..8 + len],
+		});
+		pos += raw.len();
+		if typ == b"IEND" {
+			break;
+		}
+	}
+	Ok(out)
+}
+
+fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+	decompress_to_vec_zlib_with_limit(data, MAX_INFLATED_LEN).map_err(|_| parse_error("can't decompress PNG chunk"))
+}
+
+fn latin1(data: &[u8]) -> String {
+	data.iter().map(|&c| char::from(c)).collect()
+}
+
+/// Splits the data at the first null byte
+fn split_null(data: &[u8]) -> Result<(&[u8], &[u8])> {
+	let pos = data
+		.iter()
+		.position(|&c| c == 0)
+		.ok_or_else(|| parse_error("PNG chunk is missing a null separator"))?;
+	Ok((&data[..pos], &data[pos + 1..]))
+}
+
+/// Splits the data at the null separator and the compression method byte that follows it
+fn split_compressed(data: &[u8]) -> Result<(&[u8], Vec<u8>)> {
+	let (keyword, rest) = split_null(data)?;
+	match rest.split_first() {
+		Some((0, compressed)) => Ok((keyword, inflate(compressed)?)),
+		_ => Err(parse_error("unknown PNG compression method")),
+	}
+}
+
+fn international_text(data: &[u8]) -> Result<(String, String)> {
+	let (keyword, rest) = split_null(data)?;
+	let (compressed, rest) = match rest {
+		[compressed, 0, rest @ ..] => (*compressed != 0, rest),
+		_ => return Err(parse_error("invalid iTXt chunk")),
+	};
+	let (_language, rest) = split_null(rest)?;
+	let (_translated_keyword, text) = split_null(rest)?;
+	let text = if compressed {
+		String::from_utf8_lossy(&inflate(text)?).into_owned()
+	} else {
+		String::from_utf8_lossy(text).into_owned()
+	};
+	Ok((latin1(keyword), text))
+}
+
+pub fn parse(buf: &[u8], out: &mut ImageMetadata) -> Result<()> {
+	let mut phys_dpi = None;
+	for chunk in chunks(buf)? {
+		let data = chunk.data;
+		match chunk.typ {
+			b"tEXt" => {
+				let (keyword, text) = split_null(data)?;
+				out.text.push((latin1(keyword), latin1(text)));
+			}
+			b"zTXt" => {
+				let (keyword, text) = split_compressed(data)?;
+				out.text.push((latin1(keyword), latin1(&text)));
+			}
+			b"iTXt" => out.text.push(international_text(data)?),
+			b"iCCP" => out.icc_profile = Some(split_compressed(data)?.1),
+			b"pHYs" if data.len() == 9 && data[8] == 1 => {
+				let x = u32::from_be_bytes([data[0], data[1], data[2], data[3]]);
+				let y = u32::from_be_bytes([data[4], data[5], data[6], data[7]]);
+				phys_dpi = Some(Dpi {
+					x: f64::from(x) * METERS_PER_INCH,
+					y: f64::from(y) * METERS_PER_INCH,
+				});
+			}
+			b"eXIf" => exif::parse(data, out)?,
+			_ => {}
+		}
+	}
+	// EXIF resolution takes precedence, pHYs loses precision by storing it in pixels per meter
+	if out.dpi.is_none() {
+		out.dpi = phys_dpi;
+	}
+	Ok(())
+}
+
+fn write_chunk(out: &mut Vec<u8>, typ: &[u8], data: &[u8]) {
+	out.extend((data.len() as u32).to_be_bytes());
+	out.extend(typ);
+	out.extend(data);
+	out.extend(crc32(&[typ, data]).to_be_bytes());
+}
+
+fn check_keyword(keyword: &str) -> Result<()> {
+	if (1..=79).contains(&keyword.chars().count()) && keyword.chars().all(|c| (' '..='\u{ff}').contains(&c) && c != '\u{7f}') {
+		Ok(())
+	} else {
+		Err(Error::new(
+			core::StsBadArg,
+			format!(
+				"PNG text keyword must be 1-79 printable Latin-1 characters, got: {:?}",
+				keyword
+			),
+		))
+	}
+}
+
+fn to_latin1(text: &str) -> Option<Vec<u8>> {
+	text.chars().map(|c| u8::try_from(u32::from(c)).ok()).collect()
+}
+
+/// Inserts the metadata after the header of the encoded PNG replacing the metadata chunks that are already there
+pub fn insert(buf: &[u8], metadata: &ImageMetadata) -> Result<Vec<u8>> {
+	let chunks = chunks(buf)?;
+	let (header, chunks) = chunks
+		.split_first()
+		.filter(|(header, _)| header.typ == b"IHDR")
+		.ok_or_else(|| parse_error("PNG must start with the IHDR chunk"))?;
+	let mut out = Vec::with_capacity(buf.len() + 1024);
+	out.extend(SIGNATURE);
+	out.extend(header.raw);
+	if let Some(icc_profile) = &metadata.icc_profile {
+		let mut data = b"ICC profile\0\0".to_vec();
+		data.extend(compress_to_vec_zlib(icc_profile, 6));
+		write_chunk(&mut out, b"iCCP", &data);
+	}
+	if let Some(dpi) = metadata.dpi {
+		let mut data = Vec::with_capacity(9);
+		data.extend(((dpi.x / METERS_PER_INCH).round() as u32).to_be_bytes());
+		data.extend(((dpi.y / METERS_PER_INCH).round() as u32).to_be_bytes());
+		data.push(1);
+		write_chunk(&mut out, b"pHYs", &data);
+	}
+	if let Some(exif) = exif::write(metadata) {
+		write_chunk(&mut out, b"eXIf", &exif);
+	}
+	for (keyword, text) in &metadata.text {
+		check_keyword(keyword)?;
+		let mut data = to_latin1(keyword).unwrap_or_default();
+		data.push(0);
+		if let Some(text) = to_latin1(text) {
+			data.extend(text);
+			write_chunk(&mut out, b"tEXt", &data);
+		} else {
+			// uncompressed, no language tag and no translated keyword
+			data.extend([0, 0, 0, 0]);
+			data.extend(text.as_bytes());
+			write_chunk(&mut out, b"iTXt", &data);
+		}
+	}
+	for chunk in chunks {
+		let replaced = match chunk.typ {
+			b"iCCP" | b"sRGB" => metadata.icc_profile.is_some(),
+			b"pHYs" => metadata.dpi.is_some(),
+			b"eXIf" => true,
+			_ => false,
+		};
+		if !replaced {
+			out.extend(chunk.raw);
+		}
+	}
+	Ok(out)
+}
@@ -0,0 +1,27 @@
+use super::{exif, parse_error, ImageMetadata};
+use crate::Result;
+
+pub fn is_webp(buf: &[u8]) -> bool {
+	buf.len() >= 12 && buf.starts_with(b"RIFF") && &buf[8..12] == b"WEBP"
+}
+
+pub fn parse(buf: &[u8], out: &mut ImageMetadata) -> Result<()> {
+	let mut pos = 12;
+	while pos + 8 <= buf.len() {
+		let typ = &buf[pos..pos + 4];
+		let len = u32::from_le_bytes([buf[pos + 4], buf[pos + 5], buf[pos + 6], buf[pos + 7]]) as usize;
+		let data = len
+			.checked_add(pos + 8)
+			.and_then(|end| buf.get(pos + 8..end))
+			.ok_or_else(|| parse_error("WebP chunk is truncated"))?;
+		match typ {
+			b"ICCP" => out.icc_profile = Some(data.to_vec()),
+			// some writers keep the JPEG APP1 identifier
+			b"EXIF" => exif::parse(data.strip_prefix(b"Exif\0\0").unwrap_or(data), out)?,
+			_ => {}
+		}
+		// chunks are padded to the even size
+		pos += 8 + len + len % 2;
+	}
+	Ok(())
+}
@@ -0,0 +1,264 @@
+#[cfg(ocvrs_opencv_4_5_2)]
+use std::{collections::VecDeque, iter::FusedIterator, ops::Range};
+use std::{ffi::OsStr, path::Path};
+
+#[cfg(ocvrs_opencv_4_5_2)]
+use crate::imgcodecs::ImreadModes;
+
+use crate::{
+	core::{self, Mat, Vector},
+	imgcodecs::{self, EncodeParams},
+	prelude::*,
+	Error, Result,
+};
+
+/// Lazy reader of multi-page images (e.g. TIFF) that decodes one page at a time
+///
+/// Unlike `imreadmulti()` it doesn't keep all the pages in memory. Each access reopens the file and skips the preceding pages
+/// without decoding them. The iterator decodes the pages in batches of [MultiPageReader::DEFAULT_BATCH_SIZE] (see
+/// [MultiPageReader::iter_batched]) so that the file is not reopened for every page, the memory usage stays bounded by the size
+/// of a single batch.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{imgcodecs::MultiPageReader, prelude::*};
+///
+/// let reader = MultiPageReader::open("scan.tiff")?;
+/// println!("{} pages", reader.len());
+/// for page in &reader {
+///     println!("{:?}", page?.size()?);
+/// }
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[cfg(ocvrs_opencv_4_5_2)]
+#[derive(Clone, Debug)]
+pub struct MultiPageReader {
+	filename: String,
+	flags: i32,
+	len: usize,
+}
+
+#[cfg(ocvrs_opencv_4_5_2)]
+impl MultiPageReader {
+	/// Number of pages decoded at once by [MultiPageReader::iter]
+	pub const DEFAULT_BATCH_SIZE: usize = 8;
+
+	/// Opens the file reading the pages with `IMREAD_ANYCOLOR` like `imreadmulti()` does by default
+	pub fn open(filename: &str) -> Result<Self> {
+		Self::open_with_flags(filename, ImreadModes::IMREAD_ANYCOLOR)
+	}
+
+	/// Opens the file reading the pages with the specified flags
+	pub fn open_with_flags(filename: &str, flags: ImreadModes) -> Result<Self> {
+		let flags = flags as i32;
+		let len = imgcodecs::imcount(filename, flags)?;
+		if len == 0 {
+			return Err(Error::new(core::StsError, format!("Can't read pages from: {}", filename)));
+		}
+		Ok(Self {
+			filename: filename.to_string(),
+			flags,
+			len,
+		})
+	}
+
+	/// Name of the opened file
+	pub fn filename(&self) -> &str {
+		&self.filename
+	}
+
+	/// Number of pages in the file
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	/// Always `false` because opening a file without pages fails
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// Decodes the page with the specified index
+	pub fn get(&self, index: usize) -> Result<Mat> {
+		if index >= self.len {
+			return Err(Error::new(
+				core::StsOutOfRange,
+				format!("Page index: {} is out of bounds: {}", index, self.len),
+			));
+		}
+		self.read_pages(index, 1)?.get(0)
+	}
+
+	/// Iterator that decodes the pages in batches of [MultiPageReader::DEFAULT_BATCH_SIZE]
+	pub fn iter(&self) -> MultiPageIter<'_> {
+		self.iter_batched(Self::DEFAULT_BATCH_SIZE)
+	}
+
+	/// Iterator that decodes `batch_size` pages at once, a larger batch means fewer passes over the file and more pages kept in
+	/// memory, `0` is treated as `1`
+	pub fn iter_batched(&self, batch_size: usize) -> MultiPageIter<'_> {
+		MultiPageIter {
+			reader: self,
+			range: 0..self.len,
+			batch_size: batch_size.max(1),
+			batch: VecDeque::new(),
+		}
+	}
+
+	/// Decodes `count` pages starting from `start`
+	fn read_pages(&self, start: usize, count: usize) -> Result<Vector<Mat>> {
+		let mut mats = Vector::<Mat>::new();
+		let read = imgcodecs::imreadmulti_range(&self.filename, &mut mats, start as i32, count as i32, self.flags)?;
+		if !read || mats.len() != count {
+			return Err(Error::new(
+				core::StsError,
+				format!("Can't read pages: {}..{} from: {}", start, start + count, self.filename),
+			));
+		}
+		Ok(mats)
+	}
+}
+
+#[cfg(ocvrs_opencv_4_5_2)]
+impl<'r> IntoIterator for &'r MultiPageReader {
+	type Item = Result<Mat>;
+	type IntoIter = MultiPageIter<'r>;
+
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+/// Iterator over the pages of a [MultiPageReader]
+#[cfg(ocvrs_opencv_4_5_2)]
+#[derive(Clone, Debug)]
+pub struct MultiPageIter<'r> {
+	reader: &'r MultiPageReader,
+	range: Range<usize>,
+	batch_size: usize,
+	/// Decoded pages at the start of `range`
+	batch: VecDeque<Mat>,
+}
+
+#[cfg(ocvrs_opencv_4_5_2)]
+impl Iterator for MultiPageIter<'_> {
+	type Item = Result<Mat>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let index = self.range.next()?;
+		if let Some(page) = self.batch.pop_front() {
+			return Some(Ok(page));
+		}
+		let count = self.batch_size.min(self.range.len() + 1);
+		match self.reader.read_pages(index, count) {
+			Ok(pages) => {
+				let mut pages = pages.into_iter();
+				let page = pages.next();
+				self.batch.extend(pages);
+				page.map(Ok)
+			}
+			// report the error for the failing page only
+			Err(_) if count > 1 => Some(self.reader.get(index)),
+			Err(e) => Some(Err(e)),
+		}
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.range.size_hint()
+	}
+
+	fn nth(&mut self, n: usize) -> Option<Self::Item> {
+		// skipped pages are not decoded
+		if n < self.batch.len() {
+			self.batch.drain(..n);
+		} else {
+			self.batch.clear();
+		}
+		self.range.start = self.range.start.saturating_add(n).min(self.range.end);
+		self.next()
+	}
+}
+
+#[cfg(ocvrs_opencv_4_5_2)]
+impl DoubleEndedIterator for MultiPageIter<'_> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		let index = self.range.next_back()?;
+		if self.batch.len() > self.range.len() {
+			self.batch.pop_back().map(Ok)
+		} else {
+			Some(self.reader.get(index))
+		}
+	}
+}
+
+#[cfg(ocvrs_opencv_4_5_2)]
+impl ExactSizeIterator for MultiPageIter<'_> {}
+
+#[cfg(ocvrs_opencv_4_5_2)]
+impl FusedIterator for MultiPageIter<'_> {}
+
+/// Writer of multi-page images, the counterpart of [MultiPageReader]
+///
+/// OpenCV can only encode all the pages at once, so they are accumulated until [MultiPageWriter::finish] is called. It must
+/// be called to write the file, dropping the writer without calling it discards the added pages.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{core::Mat, imgcodecs::{EncodeParams, MultiPageWriter}};
+///
+/// let mut writer = MultiPageWriter::new("out.tiff", EncodeParams::Default)?;
+/// writer.push(Mat::default())?;
+/// writer.finish()?;
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct MultiPageWriter {
+	filename: String,
+	params: Vector<i32>,
+	pages: Vec<Mat>,
+}
+
+impl MultiPageWriter {
+	/// Creates the writer, the format is chosen by the file extension and must support multiple pages (e.g. TIFF)
+	pub fn new(filename: &str, params: EncodeParams) -> Result<Self> {
+		if let Some(ext) = Path::new(filename).extension().and_then(OsStr::to_str) {
+			params.check_extension(ext)?;
+		}
+		Ok(Self {
+			filename: filename.to_string(),
+			params: params.to_vector()?,
+			pages: vec![],
+		})
+	}
+
+	/// Adds the page to the end of the file
+	pub fn push(&mut self, page: Mat) -> Result<()> {
+		if page.empty() {
+			return Err(Error::new(core::StsBadArg, "Can't write an empty page"));
+		}
+		self.pages.push(page);
+		Ok(())
+	}
+
+	/// Number of pages added so far
+	pub fn len(&self) -> usize {
+		self.pages.len()
+	}
+
+	/// Returns `true` if no pages were added yet
+	pub fn is_empty(&self) -> bool {
+		self.pages.is_empty()
+	}
+
+	/// Writes all the added pages into the file
+	pub fn finish(self) -> Result<()> {
+		if self.pages.is_empty() {
+			return Err(Error::new(core::StsBadArg, "No pages to write"));
+		}
+		let pages = self.pages.into_iter().collect::<Vector<Mat>>();
+		if imgcodecs::imwritemulti(&self.filename, &pages, &self.params)? {
+			Ok(())
+		} else {
+			Err(Error::new(core::StsError, format!("Can't write pages to: {}", self.filename)))
+		}
+	}
+}
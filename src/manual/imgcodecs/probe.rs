@@ -0,0 +1,541 @@
+use std::{collections::HashSet, convert::TryFrom, fmt};
+
+use super::metadata::{exif, jpeg, png, webp};
+use crate::{
+	core::{self, Size},
+	Error, Result,
+};
+
+/// Image format detected by [probe]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+	Jpeg,
+	Png,
+	Tiff,
+	Webp,
+	Bmp,
+	/// Portable anymap: PBM, PGM, PPM and PAM
+	Pnm,
+	Exr,
+	/// Radiance HDR
+	Hdr,
+}
+
+impl ImageFormat {
+	/// Canonical file extension including the leading dot, suitable for `imencode()`
+	pub fn extension(self) -> &'static str {
+		match self {
+			Self::Jpeg => ".jpg",
+			Self::Png => ".png",
+			Self::Tiff => ".tiff",
+			Self::Webp => ".webp",
+			Self::Bmp => ".bmp",
+			Self::Pnm => ".pnm",
+			Self::Exr => ".exr",
+			Self::Hdr => ".hdr",
+		}
+	}
+}
+
+impl fmt::Display for ImageFormat {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(&self.extension()[1..])
+	}
+}
+
+/// Image properties read from the header by [probe]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ImageInfo {
+	pub format: ImageFormat,
+	pub width: u32,
+	pub height: u32,
+	/// Number of channels of the image decoded with `IMREAD_UNCHANGED`
+	pub channels: i32,
+	/// Depth (e.g. `CV_8U`) of the image decoded with `IMREAD_UNCHANGED`
+	pub depth: i32,
+	/// Number of pages (TIFF) or animation frames (WebP), 1 for the other formats
+	pub page_count: usize,
+}
+
+impl ImageInfo {
+	/// Type of the image decoded with `IMREAD_UNCHANGED`, e.g. `CV_8UC3`
+	pub fn typ(&self) -> i32 {
+		core::CV_MAKETYPE(self.depth, self.channels)
+	}
+
+	/// Total number of pixels of a single page
+	pub fn pixel_count(&self) -> u64 {
+		u64::from(self.width) * u64::from(self.height)
+	}
+
+	/// Size of a single page, fails if the dimensions don't fit into `i32`
+	pub fn size(&self) -> Result<Size> {
+		match (i32::try_from(self.width), i32::try_from(self.height)) {
+			(Ok(width), Ok(height)) => Ok(Size::new(width, height)),
+			_ => Err(Error::new(
+				core::StsOutOfRange,
+				format!("Image size: {}x{} is too large", self.width, self.height),
+			)),
+		}
+	}
+}
+
+fn parse_error(format: ImageFormat, msg: &str) -> Error {
+	Error::new(core::StsParseError, format!("Can't parse {} header: {}", format, msg))
+}
+
+fn u16_be(buf: &[u8], pos: usize) -> Option<u16> {
+	buf.get(pos..pos + 2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+}
+
+fn u32_be(buf: &[u8], pos: usize) -> Option<u32> {
+	buf.get(pos..pos + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn u16_le(buf: &[u8], pos: usize) -> Option<u16> {
+	buf.get(pos..pos + 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn u32_le(buf: &[u8], pos: usize) -> Option<u32> {
+	buf.get(pos..pos + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn detect(buf: &[u8]) -> Option<ImageFormat> {
+	if jpeg::is_jpeg(buf) {
+		Some(ImageFormat::Jpeg)
+	} else if png::is_png(buf) {
+		Some(ImageFormat::Png)
+	} else if exif::is_tiff(buf) {
+		Some(ImageFormat::Tiff)
+	} else if webp::is_webp(buf) {
+		Some(ImageFormat::Webp)
+	} else if buf.starts_with(b"BM") {
+		Some(ImageFormat::Bmp)
+	} else if matches!(buf, [b'P', b'1'..=b'7', ..]) {
+		Some(ImageFormat::Pnm)
+	} else if buf.starts_with(&[0x76, 0x2f, 0x31, 0x01]) {
+		Some(ImageFormat::Exr)
+	} else if buf.starts_with(b"#?RADIANCE") || buf.starts_with(b"#?RGBE") {
+		Some(ImageFormat::Hdr)
+	} else {
+		None
+	}
+}
+
+fn info(format: ImageFormat, width: u32, height: u32, channels: i32, depth: i32) -> ImageInfo {
+	ImageInfo {
+		format,
+		width,
+		height,
+		channels,
+		depth,
+		page_count: 1,
+	}
+}
+
+fn probe_jpeg(buf: &[u8]) -> Result<ImageInfo> {
+	let format = ImageFormat::Jpeg;
+	let (segments, _) = jpeg::segments(buf)?;
+	segments
+		.iter()
+		// SOF markers, C4 (DHT), C8 (JPG) and CC (DAC) share the range
+		.find(|s| matches!(s.marker, 0xC0..=0xCF) && !matches!(s.marker, 0xC4 | 0xC8 | 0xCC))
+		.and_then(|sof| {
+			let height = u16_be(sof.payload, 1)?;
+			let width = u16_be(sof.payload, 3)?;
+			let components = *sof.payload.get(5)?;
+			let channels = if components == 1 { 1 } else { 3 };
+			Some(info(format, u32::from(width), u32::from(height), channels, core::CV_8U))
+		})
+		.ok_or_else(|| parse_error(format, "missing frame header"))
+}
+
+fn probe_png(buf: &[u8]) -> Result<ImageInfo> {
+	let format = ImageFormat::Png;
+	if buf.get(12..16) != Some(&b"IHDR"[..]) {
+		return Err(parse_error(format, "missing IHDR chunk"));
+	}
+	let (width, height, bit_depth, color_type) = u32_be(buf, 16)
+		.zip(u32_be(buf, 20))
+		.zip(buf.get(24..26))
+		.map(|((width, height), b)| (width, height, b[0], b[1]))
+		.ok_or_else(|| parse_error(format, "IHDR chunk is truncated"))?;
+	let mut has_transparency = false;
+	let mut pos = 8;
+	while let Some(len) = u32_be(buf, pos) {
+		match buf.get(pos + 4..pos + 8) {
+			Some(b"tRNS") => has_transparency = true,
+			Some(b"IDAT") | Some(b"IEND") | None => break,
+			_ => {}
+		}
+		pos += 12 + len as usize;
+	}
+	let channels = match color_type {
+		// RGB and palette
+		2 | 3 if has_transparency => 4,
+		2 | 3 => 3,
+		// gray with alpha and RGBA
+		4 | 6 => 4,
+		_ => 1,
+	};
+	let depth = if bit_depth == 16 {
+		core::CV_16U
+	} else {
+		core::CV_8U
+	};
+	Ok(info(format, width, height, channels, depth))
+}
+
+fn probe_tiff(buf: &[u8]) -> Result<ImageInfo> {
+	const TAG_IMAGE_WIDTH: u16 = 0x0100;
+	const TAG_IMAGE_LENGTH: u16 = 0x0101;
+	const TAG_BITS_PER_SAMPLE: u16 = 0x0102;
+	const TAG_SAMPLES_PER_PIXEL: u16 = 0x0115;
+	const TAG_SAMPLE_FORMAT: u16 = 0x0153;
+	const SAMPLE_FORMAT_INT: u32 = 2;
+	const SAMPLE_FORMAT_FLOAT: u32 = 3;
+
+	let format = ImageFormat::Tiff;
+	let tiff = exif::Tiff::new(buf)?;
+	let first_ifd = tiff.first_ifd()?;
+	let (mut width, mut height, mut bits, mut samples, mut sample_format) = (None, None, 1, 1, 1);
+	for entry in tiff.entries(first_ifd)? {
+		let value = tiff.uint(&entry, 0);
+		match entry.tag {
+			TAG_IMAGE_WIDTH => width = value,
+			TAG_IMAGE_LENGTH => height = value,
+			TAG_BITS_PER_SAMPLE => bits = value.unwrap_or(bits),
+			TAG_SAMPLES_PER_PIXEL => samples = value.unwrap_or(samples),
+			TAG_SAMPLE_FORMAT => sample_format = value.unwrap_or(sample_format),
+			_ => {}
+		}
+	}
+	let depth = match (bits, sample_format) {
+		(64, SAMPLE_FORMAT_FLOAT) => core::CV_64F,
+		(32, SAMPLE_FORMAT_FLOAT) => core::CV_32F,
+		(32, _) => core::CV_32S,
+		(16, SAMPLE_FORMAT_INT) => core::CV_16S,
+		(16, _) => core::CV_16U,
+		(8, SAMPLE_FORMAT_INT) => core::CV_8S,
+		_ => core::CV_8U,
+	};
+	// the pages can only be counted when the whole file is available, a broken chain ends the counting
+	let mut visited = HashSet::new();
+	let mut offset = first_ifd;
+	while offset != 0 && visited.insert(offset) {
+		offset = tiff.next_ifd(offset).unwrap_or(0);
+	}
+	let page_count = visited.len();
+	let (width, height) = width
+		.zip(height)
+		.ok_or_else(|| parse_error(format, "missing image dimensions"))?;
+	Ok(ImageInfo {
+		page_count,
+		..info(format, width, height, samples as i32, depth)
+	})
+}
+
+fn probe_webp(buf: &[u8]) -> Result<ImageInfo> {
+	let format = ImageFormat::Webp;
+	let mut out = None;
+	let mut frames = 0;
+	let mut pos = 12;
+	while let (Some(typ), Some(len)) = (buf.get(pos..pos + 4), u32_le(buf, pos + 4)) {
+		let data = buf.get(pos + 8..).unwrap_or_default();
+		match typ {
+			b"VP8 " if out.is_none() => {
+				if data.get(3..6) != Some(&[0x9d, 0x01, 0x2a][..]) {
+					return Err(parse_error(format, "invalid VP8 frame header"));
+				}
+				out = u16_le(data, 6)
+					.zip(u16_le(data, 8))
+					.map(|(width, height)| info(format, u32::from(width & 0x3fff), u32::from(height & 0x3fff), 3, core::CV_8U));
+			}
+			b"VP8L" if out.is_none() => {
+				if data.first() != Some(&0x2f) {
+					return Err(parse_error(format, "invalid VP8L signature"));
+				}
+				out = u32_le(data, 1).map(|bits| {
+					let channels = if (bits >> 28) & 1 == 1 {
+						4
+					} else {
+						3
+					};
+					info(
+						format,
+						(bits & 0x3fff) + 1,
+						((bits >> 14) & 0x3fff) + 1,
+						channels,
+						core::CV_8U,
+					)
+				});
+			}
+			b"VP8X" => {
+				let canvas = data.get(..10).map(|data| {
+					let width = u32::from_le_bytes([data[4], data[5], data[6], 0]) + 1;
+					let height = u32::from_le_bytes([data[7], data[8], data[9], 0]) + 1;
+					let channels = if data[0] & 0x10 != 0 {
+						4
+					} else {
+						3
+					};
+					info(format, width, height, channels, core::CV_8U)
+				});
+				// canvas size takes precedence over the size of the frames
+				out = canvas.or(out);
+			}
+			b"ANMF" => frames += 1,
+			_ => {}
+		}
+		pos += 8 + len as usize + len as usize % 2;
+	}
+	let out = out.ok_or_else(|| parse_error(format, "missing image data"))?;
+	Ok(ImageInfo {
+		page_count: frames.max(1),
+		..out
+	})
+}
+
+fn probe_bmp(buf: &[u8]) -> Result<ImageInfo> {
+	const BI_BITFIELDS: u32 = 3;
+	const BI_ALPHABITFIELDS: u32 = 6;
+
+	let format = ImageFormat::Bmp;
+	let truncated = || parse_error(format, "header is truncated");
+	let header_size = u32_le(buf, 14).ok_or_else(truncated)?;
+	let (width, height, bpp, compression, palette_entry_size) = if header_size == 12 {
+		// OS/2 BITMAPCOREHEADER
+		let width = u16_le(buf, 18).ok_or_else(truncated)?;
+		let height = u16_le(buf, 20).ok_or_else(truncated)?;
+		(
+			u32::from(width),
+			u32::from(height),
+			u16_le(buf, 24).ok_or_else(truncated)?,
+			0,
+			3,
+		)
+	} else {
+		let width = u32_le(buf, 18).ok_or_else(truncated)? as i32;
+		// negative height means the rows are stored top-down
+		let height = u32_le(buf, 22).ok_or_else(truncated)? as i32;
+		(
+			width.unsigned_abs(),
+			height.unsigned_abs(),
+			u16_le(buf, 28).ok_or_else(truncated)?,
+			u32_le(buf, 30).ok_or_else(truncated)?,
+			4,
+		)
+	};
+	let channels = match bpp {
+		1 | 4 | 8 => {
+			// OpenCV decodes the images with the gray palette as single-channel
+			let colors = 1 << bpp;
+			let palette_start = 14 + header_size as usize;
+			let gray = (0..colors).all(|i| {
+				let entry = palette_start + i * palette_entry_size;
+				matches!(buf.get(entry..entry + 3), Some(bgr) if bgr[0] == bgr[1] && bgr[1] == bgr[2])
+			});
+			if gray {
+				1
+			} else {
+				3
+			}
+		}
+		32 if compression == BI_BITFIELDS || compression == BI_ALPHABITFIELDS => 4,
+		_ => 3,
+	};
+	Ok(info(format, width, height, channels, core::CV_8U))
+}
+
+/// Splits the PNM header into whitespace separated tokens skipping the comments
+fn pnm_tokens(buf: &[u8]) -> impl Iterator<Item = &[u8]> {
+	let mut pos = 2;
+	std::iter::from_fn(move || {
+		loop {
+			match buf.get(pos)? {
+				b'#' => {
+					while !matches!(buf.get(pos), Some(b'\n') | Some(b'\r') | None) {
+						pos += 1;
+					}
+				}
+				c if c.is_ascii_whitespace() => pos += 1,
+				_ => break,
+			}
+		}
+		let start = pos;
+		while matches!(buf.get(pos), Some(c) if !c.is_ascii_whitespace()) {
+			pos += 1;
+		}
+		Some(&buf[start..pos])
+	})
+}
+
+fn parse_number(token: Option<&[u8]>) -> Option<u32> {
+	std::str::from_utf8(token?).ok()?.parse().ok()
+}
+
+fn probe_pnm(buf: &[u8]) -> Result<ImageInfo> {
+	let format = ImageFormat::Pnm;
+	let invalid = || parse_error(format, "invalid header");
+	let mut tokens = pnm_tokens(buf);
+	let (width, height, channels, max_value) = if buf[1] == b'7' {
+		// PAM has named header fields
+		let (mut width, mut height, mut channels, mut max_value) = (None, None, None, None);
+		loop {
+			match tokens.next().ok_or_else(invalid)? {
+				b"WIDTH" => width = parse_number(tokens.next()),
+				b"HEIGHT" => height = parse_number(tokens.next()),
+				b"DEPTH" => channels = parse_number(tokens.next()),
+				b"MAXVAL" => max_value = parse_number(tokens.next()),
+				b"ENDHDR" => break,
+				_ => {}
+			}
+		}
+		(width, height, channels.unwrap_or(1), max_value.unwrap_or(1))
+	} else {
+		let width = parse_number(tokens.next());
+		let height = parse_number(tokens.next());
+		let (channels, max_value) = match buf[1] {
+			// bitmaps have no maximum value
+			b'1' | b'4' => (1, 1),
+			b'2' | b'5' => (1, parse_number(tokens.next()).ok_or_else(invalid)?),
+			_ => (3, parse_number(tokens.next()).ok_or_else(invalid)?),
+		};
+		(width, height, channels, max_value)
+	};
+	let (width, height) = width.zip(height).ok_or_else(invalid)?;
+	let depth = if max_value > 255 {
+		core::CV_16U
+	} else {
+		core::CV_8U
+	};
+	Ok(info(format, width, height, channels as i32, depth))
+}
+
+fn probe_exr(buf: &[u8]) -> Result<ImageInfo> {
+	const PIXEL_TYPE_UINT: u32 = 0;
+
+	let format = ImageFormat::Exr;
+	let truncated = || parse_error(format, "header is truncated");
+	let mut size = None;
+	let mut channels = 0;
+	let mut is_float = true;
+	// magic number and version
+	let mut pos = 8;
+	loop {
+		let name_len = buf
+			.get(pos..)
+			.and_then(|b| b.iter().position(|&c| c == 0))
+			.ok_or_else(truncated)?;
+		if name_len == 0 {
+			break;
+		}
+		let name = &buf[pos..pos + name_len];
+		pos += name_len + 1;
+		let type_len = buf
+			.get(pos..)
+			.and_then(|b| b.iter().position(|&c| c == 0))
+			.ok_or_else(truncated)?;
+		pos += type_len + 1;
+		let len = u32_le(buf, pos).ok_or_else(truncated)? as usize;
+		let value = buf.get(pos + 4..pos + 4 + len).ok_or_else(truncated)?;
+		pos += 4 + len;
+		match name {
+			b"dataWindow" if len == 16 => {
+				let coord = |i: usize| u32_le(value, i * 4).map(|v| i64::from(v as i32)).ok_or_else(truncated);
+				// the window bounds are inclusive and can be negative, compute the extent in i64 to avoid overflows
+				let extent = |min: i64, max: i64| {
+					u32::try_from(max - min + 1)
+						.ok()
+						.filter(|_| max >= min)
+						.ok_or_else(|| parse_error(format, "invalid data window"))
+				};
+				size = Some((extent(coord(0)?, coord(2)?)?, extent(coord(1)?, coord(3)?)?));
+			}
+			b"channels" => {
+				// null-terminated name followed by 16 bytes of the channel attributes, the list ends with an empty name
+				let mut ch_pos = 0;
+				while let Some(ch_name_len) = value.get(ch_pos..).and_then(|v| v.iter().position(|&c| c == 0)) {
+					if ch_name_len == 0 {
+						break;
+					}
+					ch_pos += ch_name_len + 1;
+					if u32_le(value, ch_pos) == Some(PIXEL_TYPE_UINT) {
+						is_float = false;
+					}
+					ch_pos += 16;
+					channels += 1;
+				}
+			}
+			_ => {}
+		}
+	}
+	let (width, height) = size.ok_or_else(|| parse_error(format, "missing data window"))?;
+	let depth = if is_float {
+		core::CV_32F
+	} else {
+		core::CV_32S
+	};
+	Ok(info(format, width, height, channels.clamp(1, 4), depth))
+}
+
+fn probe_hdr(buf: &[u8]) -> Result<ImageInfo> {
+	let format = ImageFormat::Hdr;
+	let mut lines = buf.split(|&c| c == b'\n');
+	// the header ends with an empty line that is followed by the resolution line
+	lines
+		.by_ref()
+		.find(|line| line.is_empty())
+		.ok_or_else(|| parse_error(format, "header is truncated"))?;
+	let resolution = lines.next().ok_or_else(|| parse_error(format, "missing resolution"))?;
+	let tokens = resolution
+		.split(|c| c.is_ascii_whitespace())
+		.filter(|t| !t.is_empty())
+		.collect::<Vec<_>>();
+	let mut width = None;
+	let mut height = None;
+	for pair in tokens.chunks_exact(2) {
+		match pair[0] {
+			b"-Y" | b"+Y" => height = parse_number(Some(pair[1])),
+			b"-X" | b"+X" => width = parse_number(Some(pair[1])),
+			_ => {}
+		}
+	}
+	let (width, height) = width.zip(height).ok_or_else(|| parse_error(format, "invalid resolution"))?;
+	Ok(info(format, width, height, 3, core::CV_32F))
+}
+
+/// Detects the format and reads the image properties from the header without decoding the pixels
+///
+/// Recognizes JPEG, PNG, TIFF, WebP, BMP, PNM (including PAM), OpenEXR and Radiance HDR. Fails with `StsUnsupportedFormat` if the
+/// format is not recognized and with `StsParseError` if the header is malformed. The format is detected by the signature only,
+/// it doesn't check that OpenCV was built with the corresponding decoder, so decoding a successfully probed image can still fail.
+///
+/// ## Example
+/// ```no_run
+/// use opencv::imgcodecs;
+///
+/// let upload = std::fs::read("upload.bin").unwrap();
+/// let info = imgcodecs::probe(&upload)?;
+/// if info.pixel_count() > 50_000_000 {
+///     println!("Rejecting {}x{} {} image", info.width, info.height, info.format);
+/// }
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub fn probe(buf: &[u8]) -> Result<ImageInfo> {
+	let format = detect(buf).ok_or_else(|| Error::new(core::StsUnsupportedFormat, "Unknown image format"))?;
+	let out = match format {
+		ImageFormat::Jpeg => probe_jpeg(buf),
+		ImageFormat::Png => probe_png(buf),
+		ImageFormat::Tiff => probe_tiff(buf),
+		ImageFormat::Webp => probe_webp(buf),
+		ImageFormat::Bmp => probe_bmp(buf),
+		ImageFormat::Pnm => probe_pnm(buf),
+		ImageFormat::Exr => probe_exr(buf),
+		ImageFormat::Hdr => probe_hdr(buf),
+	}?;
+	if out.width == 0 || out.height == 0 {
+		return Err(parse_error(format, "image has zero size"));
+	}
+	Ok(out)
+}
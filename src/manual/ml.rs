@@ -0,0 +1,3 @@
+pub use train_data::*;
+
+mod train_data;
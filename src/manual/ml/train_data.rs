@@ -0,0 +1,414 @@
+use std::{collections::HashMap, convert::TryFrom, hash::Hash};
+
+use crate::{
+	core::{self, Mat, Ptr, Scalar},
+	ml::{self, TrainData},
+	prelude::*,
+	Error, Result,
+};
+
+/// Mapping between the class labels and the integer responses passed to OpenCV
+///
+/// The ids are assigned in the order of the first appearance of the label starting from 0.
+#[derive(Clone, Debug)]
+pub struct ClassLabels<L> {
+	labels: Vec<L>,
+	ids: HashMap<L, i32>,
+}
+
+impl<L> Default for ClassLabels<L> {
+	fn default() -> Self {
+		Self {
+			labels: vec![],
+			ids: HashMap::new(),
+		}
+	}
+}
+
+impl<L: Hash + Eq + Clone> ClassLabels<L> {
+	/// Returns the id of the `label`, the new labels get the next free id
+	fn insert(&mut self, label: L) -> i32 {
+		let next_id = self.labels.len() as i32;
+		let labels = &mut self.labels;
+		*self.ids.entry(label).or_insert_with_key(|label| {
+			labels.push(label.clone());
+			next_id
+		})
+	}
+
+	/// Returns the integer response of the class
+	pub fn id(&self, label: &L) -> Option<i32> {
+		self.ids.get(label).copied()
+	}
+
+	/// Returns the label of the class or `None` if the id is out of bounds
+	pub fn label(&self, id: i32) -> Option<&L> {
+		usize::try_from(id).ok().and_then(|i| self.labels.get(i))
+	}
+
+	/// Returns the label for the response predicted by the model, e.g. the result of `StatModel::predict()`
+	pub fn label_for_response(&self, response: f32) -> Option<&L> {
+		self.label(response.round() as i32)
+	}
+
+	pub fn len(&self) -> usize {
+		self.labels.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.labels.is_empty()
+	}
+
+	/// Labels indexed by the class id
+	pub fn labels(&self) -> &[L] {
+		&self.labels
+	}
+}
+
+#[derive(Clone, Debug)]
+enum Responses {
+	None,
+	Ordered(Vec<f32>),
+	Classes(Vec<i32>),
+}
+
+/// Train and test parts of the data produced by [TrainDataBuilder::split]
+pub struct TrainTestSplit {
+	pub train: Ptr<dyn TrainData>,
+	pub test: Ptr<dyn TrainData>,
+	/// Indices of the training samples in the builder, in ascending order
+	pub train_indices: Vec<usize>,
+	/// Indices of the test samples in the builder, in ascending order
+	pub test_indices: Vec<usize>,
+}
+
+/// Builder of the [TrainData] from the Rust collections
+///
+/// The samples are always laid out as rows (`ROW_SAMPLE`). All features are ordered (numerical) unless declared with
+/// [TrainDataBuilder::categorical]. The responses are either the numerical values for regression
+/// ([TrainDataBuilder::responses]) or the class labels of any `Hash + Eq` type for classification ([TrainDataBuilder::labels]),
+/// the labels are mapped to the integer responses by [ClassLabels].
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{core::Mat, ml::{self, TrainDataBuilder}, prelude::*};
+///
+/// let samples = [[5.1, 3.5, 0.], [6.2, 2.9, 1.], [5.9, 3.0, 1.], [4.9, 3.1, 0.]];
+/// let labels = ["setosa", "versicolor", "versicolor", "setosa"];
+/// let builder = TrainDataBuilder::from_rows(&samples)?.categorical(&[2]).labels(labels)?;
+/// let split = builder.split(0.25, 42)?;
+/// let mut model = <dyn ml::RTrees>::create()?;
+/// model.train_with_data(&split.train, 0)?;
+/// let response = model.predict(&Mat::from_slice(&samples[0])?, &mut Mat::default(), 0)?;
+/// println!("{:?}", builder.classes().label_for_response(response));
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct TrainDataBuilder<L = ()> {
+	samples: Vec<f32>,
+	var_count: usize,
+	var_types: Vec<(usize, i32)>,
+	responses: Responses,
+	classes: ClassLabels<L>,
+	missing_marker: Option<f32>,
+}
+
+impl TrainDataBuilder<()> {
+	/// Creates the builder from the feature rows, e.g. `&[[f32; N]]` or `&Vec<Vec<f32>>`, all rows must have the same length
+	pub fn from_rows<R: AsRef<[f32]>>(rows: impl IntoIterator<Item = R>) -> Result<Self> {
+		let mut samples = vec![];
+		let mut var_count = None;
+		for (i, row) in rows.into_iter().enumerate() {
+			let row = row.as_ref();
+			match var_count {
+				None => var_count = Some(row.len()),
+				Some(var_count) if var_count != row.len() => {
+					return Err(Error::new(
+						core::StsUnmatchedSizes,
+						format!("Row {} has {} values, but the first row has {}", i, row.len(), var_count),
+					))
+				}
+				Some(_) => {}
+			}
+			samples.extend_from_slice(row);
+		}
+		match var_count {
+			Some(var_count) if var_count > 0 => Ok(Self::new(samples, var_count)),
+			_ => Err(Error::new(
+				core::StsBadArg,
+				"Train data must have at least one sample and one feature",
+			)),
+		}
+	}
+
+	/// Creates the builder from the 2-dimensional array with one sample per row
+	#[cfg(feature = "ndarray")]
+	pub fn from_ndarray<S: ndarray::Data<Elem = f32>>(samples: &ndarray::ArrayBase<S, ndarray::Ix2>) -> Result<Self> {
+		if samples.is_empty() {
+			return Err(Error::new(
+				core::StsBadArg,
+				"Train data must have at least one sample and one feature",
+			));
+		}
+		Ok(Self::new(samples.iter().copied().collect(), samples.ncols()))
+	}
+
+	fn new(samples: Vec<f32>, var_count: usize) -> Self {
+		Self {
+			samples,
+			var_count,
+			var_types: vec![],
+			responses: Responses::None,
+			classes: ClassLabels::default(),
+			missing_marker: None,
+		}
+	}
+
+	/// Sets the numerical responses for regression, one per sample
+	pub fn responses(mut self, responses: &[f32]) -> Result<Self> {
+		self.check_response_count(responses.len())?;
+		self.responses = Responses::Ordered(responses.to_vec());
+		Ok(self)
+	}
+
+	/// Sets the class labels for classification, one per sample
+	pub fn labels<L: Hash + Eq + Clone>(self, labels: impl IntoIterator<Item = L>) -> Result<TrainDataBuilder<L>> {
+		let mut classes = ClassLabels::default();
+		let ids = labels.into_iter().map(|label| classes.insert(label)).collect::<Vec<_>>();
+		self.check_response_count(ids.len())?;
+		Ok(TrainDataBuilder {
+			samples: self.samples,
+			var_count: self.var_count,
+			var_types: self.var_types,
+			responses: Responses::Classes(ids),
+			classes,
+			missing_marker: self.missing_marker,
+		})
+	}
+}
+
+impl<L> TrainDataBuilder<L> {
+	pub fn sample_count(&self) -> usize {
+		self.samples.len() / self.var_count
+	}
+
+	pub fn var_count(&self) -> usize {
+		self.var_count
+	}
+
+	/// Class labels of the samples, empty for regression
+	pub fn classes(&self) -> &ClassLabels<L> {
+		&self.classes
+	}
+
+	/// Declares the features with the specified indices as categorical, their values must be integers
+	pub fn categorical(mut self, vars: &[usize]) -> Self {
+		self.var_types.extend(vars.iter().map(|&var| (var, ml::VAR_CATEGORICAL)));
+		self
+	}
+
+	/// Declares the features with the specified indices as ordered (numerical), that's the default for all features
+	pub fn ordered(mut self, vars: &[usize]) -> Self {
+		self.var_types.extend(vars.iter().map(|&var| (var, ml::VAR_ORDERED)));
+		self
+	}
+
+	/// Feature values equal to the `marker` are treated as missing, pass `f32::NAN` to treat NaNs as missing
+	///
+	/// Missing values are replaced by `TrainData::missing_value()`, which is the value the tree-based models (`DTrees`, `RTrees`,
+	/// `Boost`) recognize as a missing measurement. Only the ordered features can have missing values, a missing value in
+	/// a categorical feature is an error.
+	pub fn missing_marker(mut self, marker: f32) -> Self {
+		self.missing_marker = Some(marker);
+		self
+	}
+
+	/// Creates the [TrainData] with all the samples
+	pub fn build(&self) -> Result<Ptr<dyn TrainData>> {
+		let indices = (0..self.sample_count()).collect::<Vec<_>>();
+		self.create(&indices)
+	}
+
+	/// Splits the samples into the train and the test parts, `test_ratio` of the samples go to the test part
+	///
+	/// For classification the split is stratified: the ratio is applied to every class separately, so both parts keep the class
+	/// proportions of the whole data. The samples are shuffled with the generator seeded by `seed`, the same seed always produces
+	/// the same split.
+	pub fn split(&self, test_ratio: f64, seed: u64) -> Result<TrainTestSplit> {
+		if !(test_ratio > 0. && test_ratio < 1.) {
+			return Err(Error::new(
+				core::StsOutOfRange,
+				format!("Test ratio must be between 0 and 1, but it is: {}", test_ratio),
+			));
+		}
+		let groups = match &self.responses {
+			Responses::Classes(ids) => {
+				let mut groups = vec![vec![]; self.classes.labels.len()];
+				for (i, &id) in ids.iter().enumerate() {
+					groups[id as usize].push(i);
+				}
+				groups
+			}
+			Responses::None | Responses::Ordered(_) => vec![(0..self.sample_count()).collect()],
+		};
+		let mut rng = SplitMix64(seed);
+		let mut train_indices = vec![];
+		let mut test_indices = vec![];
+		for mut group in groups {
+			rng.shuffle(&mut group);
+			let test_count = (group.len() as f64 * test_ratio).round() as usize;
+			test_indices.extend_from_slice(&group[..test_count]);
+			train_indices.extend_from_slice(&group[test_count..]);
+		}
+		if train_indices.is_empty() || test_indices.is_empty() {
+			return Err(Error::new(
+				core::StsBadArg,
+				format!(
+					"Not enough samples: {} to split with the test ratio: {}",
+					self.sample_count(),
+					test_ratio
+				),
+			));
+		}
+		train_indices.sort_unstable();
+		test_indices.sort_unstable();
+		Ok(TrainTestSplit {
+			train: self.create(&train_indices)?,
+			test: self.create(&test_indices)?,
+			train_indices,
+			test_indices,
+		})
+	}
+
+	fn check_response_count(&self, count: usize) -> Result<()> {
+		if count == self.sample_count() {
+			Ok(())
+		} else {
+			Err(Error::new(
+				core::StsUnmatchedSizes,
+				format!("Got {} responses for {} samples", count, self.sample_count()),
+			))
+		}
+	}
+
+	fn is_missing(&self, val: f32) -> bool {
+		match self.missing_marker {
+			Some(marker) if marker.is_nan() => val.is_nan(),
+			Some(marker) => val == marker,
+			None => false,
+		}
+	}
+
+	/// Types of the features followed by the type of the response
+	fn var_types(&self) -> Result<Vec<u8>> {
+		let mut out = vec![ml::VAR_ORDERED as u8; self.var_count + 1];
+		for &(var, typ) in &self.var_types {
+			if var >= self.var_count {
+				return Err(Error::new(
+					core::StsOutOfRange,
+					format!(
+						"Feature index: {} is out of bounds, there are {} features",
+						var, self.var_count
+					),
+				));
+			}
+			out[var] = typ as u8;
+		}
+		out[self.var_count] = match self.responses {
+			Responses::None => return Err(Error::new(core::StsBadArg, "Responses or labels must be set")),
+			Responses::Ordered(_) => ml::VAR_ORDERED as u8,
+			Responses::Classes(_) => ml::VAR_CATEGORICAL as u8,
+		};
+		Ok(out)
+	}
+
+	fn create(&self, indices: &[usize]) -> Result<Ptr<dyn TrainData>> {
+		let var_types = self.var_types()?;
+		let missing_value = if self.missing_marker.is_some() {
+			<dyn TrainData>::missing_value()?
+		} else {
+			0.
+		};
+		let too_large = |_| Error::new(core::StsOutOfRange, "Train data is too large");
+		let rows = i32::try_from(indices.len()).map_err(too_large)?;
+		let cols = i32::try_from(self.var_count).map_err(too_large)?;
+
+		let mut samples = Mat::new_rows_cols_with_default(rows, cols, core::CV_32F, Scalar::all(0.))?;
+		let dst_rows = samples.data_typed_mut::<f32>()?.chunks_exact_mut(self.var_count);
+		for (dst_row, &i) in dst_rows.zip(indices) {
+			let src_row = &self.samples[i * self.var_count..(i + 1) * self.var_count];
+			for (var, ((dst, &val), &typ)) in dst_row.iter_mut().zip(src_row).zip(&var_types).enumerate() {
+				let categorical = i32::from(typ) == ml::VAR_CATEGORICAL;
+				*dst = if self.is_missing(val) {
+					if categorical {
+						return Err(Error::new(
+							core::StsBadArg,
+							format!("Categorical feature: {} of sample: {} has missing value", var, i),
+						));
+					}
+					missing_value
+				} else if categorical && val.fract() != 0. {
+					return Err(Error::new(
+						core::StsBadArg,
+						format!("Categorical feature: {} of sample: {} has non-integer value: {}", var, i, val),
+					));
+				} else {
+					val
+				};
+			}
+		}
+
+		let responses = match &self.responses {
+			Responses::None => unreachable!("Checked in var_types()"),
+			Responses::Ordered(values) => {
+				let mut out = Mat::new_rows_cols_with_default(rows, 1, core::CV_32F, Scalar::all(0.))?;
+				out.data_typed_mut::<f32>()?
+					.iter_mut()
+					.zip(indices)
+					.for_each(|(dst, &i)| *dst = values[i]);
+				out
+			}
+			Responses::Classes(ids) => {
+				let mut out = Mat::new_rows_cols_with_default(rows, 1, core::CV_32S, Scalar::all(0.))?;
+				out.data_typed_mut::<i32>()?
+					.iter_mut()
+					.zip(indices)
+					.for_each(|(dst, &i)| *dst = ids[i]);
+				out
+			}
+		};
+
+		let mut var_type = Mat::new_rows_cols_with_default(1, var_types.len() as i32, core::CV_8U, Scalar::all(0.))?;
+		var_type.data_bytes_mut()?.copy_from_slice(&var_types);
+		<dyn TrainData>::create(
+			&samples,
+			ml::ROW_SAMPLE,
+			&responses,
+			&Mat::default(),
+			&Mat::default(),
+			&Mat::default(),
+			&var_type,
+		)
+	}
+}
+
+/// SplitMix64 generator, makes the splits reproducible regardless of the platform and the OpenCV version
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+	fn next_u64(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+
+	/// Fisher-Yates shuffle
+	fn shuffle<T>(&mut self, items: &mut [T]) {
+		for i in (1..items.len()).rev() {
+			let j = (self.next_u64() % (i as u64 + 1)) as usize;
+			items.swap(i, j);
+		}
+	}
+}
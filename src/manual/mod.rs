@@ -1,15 +1,37 @@
+#[cfg(ocvrs_has_module_calib3d)]
+pub mod calib3d;
 #[cfg(ocvrs_has_module_core)]
 pub mod core;
 #[cfg(ocvrs_has_module_dnn)]
 pub mod dnn;
 #[cfg(ocvrs_has_module_features2d)]
 pub mod features2d;
+#[cfg(ocvrs_has_module_highgui)]
+pub mod highgui;
+#[cfg(ocvrs_has_module_imgcodecs)]
+pub mod imgcodecs;
+#[cfg(ocvrs_has_module_ml)]
+pub mod ml;
+#[cfg(ocvrs_has_module_superres)]
+pub mod superres;
 pub mod sys;
+#[cfg(ocvrs_has_module_text)]
+pub mod text;
 pub mod types;
+#[cfg(ocvrs_has_module_videoio)]
+pub mod videoio;
+#[cfg(ocvrs_has_module_videostab)]
+pub mod videostab;
 
 pub mod prelude {
 	#[cfg(all(ocvrs_has_module_core, ocvrs_opencv_branch_32))]
 	pub use super::core::MatSizeTraitConstManual;
 	#[cfg(ocvrs_has_module_core)]
 	pub use super::core::{MatConstIteratorTraitManual, MatTraitConstManual, MatTraitManual, MatxTrait, UMatTraitConstManual};
+	#[cfg(all(ocvrs_has_module_dnn, not(ocvrs_opencv_branch_32)))]
+	pub use super::dnn::{
+		ClassificationModelTraitManual, DetectionModelTraitManual, KeypointsModelTraitManual, SegmentationModelTraitManual,
+	};
+	#[cfg(all(ocvrs_has_module_dnn, ocvrs_opencv_branch_4))]
+	pub use super::dnn::{TextDetectionModelTraitConstManual, TextRecognitionModelTraitConstManual};
 }
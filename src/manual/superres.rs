@@ -0,0 +1,21 @@
+#[cfg(ocvrs_has_module_videoio)]
+impl crate::core::Ptr<dyn crate::superres::Superres_FrameSource> {
+	/// Wraps the Rust [FrameSource](crate::videoio::FrameSource) into the C++ `superres::FrameSource` object
+	///
+	/// `next_frame()` outputs an empty array once `source` reports that there are no more frames.
+	pub fn from_rust(source: impl crate::videoio::FrameSource + 'static) -> crate::Result<Self> {
+		use std::ffi::c_void;
+
+		use crate::{sys, traits::Boxed, videoio::FrameSourceVtable};
+
+		extern "C" {
+			fn cv_manual_PtrOfSuperres_FrameSource_from_rust(
+				instance: *mut c_void,
+				vtable: *const FrameSourceVtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		crate::videoio::frame_source_into_extern(source, cv_manual_PtrOfSuperres_FrameSource_from_rust)
+			.map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
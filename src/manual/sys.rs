@@ -3,9 +3,17 @@
 // note to self, you can't use union here to store both result and error code because C++ side doesn't
 // support non-POD types as union fields
 
-use std::{ffi::c_void, marker::PhantomData, mem::MaybeUninit};
+use std::{
+	any::Any,
+	ffi::{c_void, CString},
+	marker::PhantomData,
+	mem::MaybeUninit,
+	os::raw::c_char,
+	panic::{self, AssertUnwindSafe},
+	ptr,
+};
 
-use crate::{types::Unit, Error, Result as CrateResult};
+use crate::{core, types::Unit, Error, Result as CrateResult};
 
 #[repr(C)]
 pub struct Result<S, O = S> {
@@ -28,4 +36,88 @@ impl<S: Into<O>, O> Result<S, O> {
 	}
 }
 
+impl<S, O: Into<S>> Result<S, O> {
+	/// Runs the Rust implementation of a C++ virtual method and packs its result to be sent back to C++
+	///
+	/// This is the reverse of `into_result()`, `error_msg` is a C string that is read by the `ocvrs_callback_result()` function
+	/// on the C++ side. Panics are caught and reported as `StsError` because they must not unwind into C++.
+	pub fn from_callback(f: impl FnOnce() -> CrateResult<O>) -> Self {
+		match panic::catch_unwind(AssertUnwindSafe(f)) {
+			Ok(Ok(result)) => Self {
+				error_code: 0,
+				error_msg: ptr::null_mut(),
+				result: MaybeUninit::new(result.into()),
+				_p: PhantomData,
+			},
+			Ok(Err(e)) => Self::callback_error(e),
+			Err(e) => Self::callback_error(Error::new(core::StsError, panic_message(e.as_ref()))),
+		}
+	}
+
+	fn callback_error(e: Error) -> Self {
+		let msg = CString::new(e.message.replace('\0', "")).unwrap_or_default();
+		Self {
+			error_code: e.code,
+			error_msg: msg.into_raw() as *mut c_void,
+			result: MaybeUninit::uninit(),
+			_p: PhantomData,
+		}
+	}
+}
+
+fn panic_message(e: &(dyn Any + Send)) -> String {
+	let msg = e
+		.downcast_ref::<&str>()
+		.copied()
+		.or_else(|| e.downcast_ref::<String>().map(|s| s.as_str()))
+		.unwrap_or("Box<dyn Any>");
+	format!("Rust callback panicked: {}", msg)
+}
+
+/// Passes the Rust implementation of a C++ interface to the C++ function that wraps it into the object deriving from that interface
+///
+/// `extern_new` receives the type-erased pointer to the boxed `instance` together with the return slot and must pass them to the
+/// C++ side along with the vtable whose `drop` entry is [drop_instance::<T>]. The C++ object calls `drop` when it's destroyed.
+///
+/// # Safety
+/// `extern_new` must take the ownership of the instance only when it succeeds, on error the instance is freed here.
+pub(crate) unsafe fn into_extern<T: ?Sized>(
+	instance: Box<T>,
+	extern_new: impl FnOnce(*mut c_void, *mut Result<*mut c_void>),
+) -> CrateResult<*mut c_void> {
+	let instance = Box::into_raw(Box::new(instance)) as *mut c_void;
+	return_send!(via ocvrs_return);
+	extern_new(instance, ocvrs_return.as_mut_ptr());
+	return_receive!(ocvrs_return => ret);
+	let ret = ret.into_result();
+	if ret.is_err() {
+		// C++ side didn't take the ownership
+		drop_instance::<T>(instance);
+	}
+	ret
+}
+
+/// `drop` entry of the vtables, frees the instance created by [into_extern]
+pub(crate) unsafe extern "C" fn drop_instance<T: ?Sized>(instance: *mut c_void) {
+	drop(Box::from_raw(instance as *mut Box<T>));
+}
+
+/// Reference to the instance created by [into_extern], used by the vtable entries of the `const` C++ methods
+pub(crate) unsafe fn instance_ref<'i, T: ?Sized>(instance: *const c_void) -> &'i T {
+	&*(instance as *const Box<T>)
+}
+
+/// Mutable reference to the instance created by [into_extern], used by the vtable entries of the non-`const` C++ methods
+pub(crate) unsafe fn instance_mut<'i, T: ?Sized>(instance: *mut c_void) -> &'i mut T {
+	&mut *(instance as *mut Box<T>)
+}
+
+/// Frees the error message created by `Result::from_callback()`
+#[no_mangle]
+unsafe extern "C" fn ocvrs_drop_c_string(s: *mut c_char) {
+	if !s.is_null() {
+		drop(CString::from_raw(s));
+	}
+}
+
 pub type Result_void = Result<Unit, ()>;
@@ -0,0 +1,59 @@
+use std::{ffi::c_void, mem::ManuallyDrop};
+
+use crate::{
+	core::Ptr,
+	sys,
+	text::{ERFilter_Callback, ERStat},
+	traits::Boxed,
+	Result,
+};
+
+/// Rust implementation of the `ERFilter::Callback` that evaluates the probability of an extremal region to be a character
+///
+/// Use [Ptr::from_rust](crate::core::Ptr::<dyn ERFilter_Callback>::from_rust) to pass it to `create_er_filter_nm1()` or
+/// `create_er_filter_nm2()`.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/d3/da6/classcv_1_1text_1_1ERFilter_1_1Callback.html)
+#[allow(non_camel_case_types)]
+pub trait ERFilter_CallbackImpl: Send + Sync {
+	/// Returns the probability (between 0 and 1) that the extremal region `stat` is a character
+	fn eval(&mut self, stat: &ERStat) -> Result<f64>;
+}
+
+#[repr(C)]
+struct Vtable {
+	drop: unsafe extern "C" fn(*mut c_void),
+	eval: unsafe extern "C" fn(*mut c_void, *const c_void, *mut sys::Result<f64>),
+}
+
+static VTABLE: Vtable = Vtable {
+	drop: sys::drop_instance::<dyn ERFilter_CallbackImpl>,
+	eval: vtable_eval,
+};
+
+unsafe extern "C" fn vtable_eval(instance: *mut c_void, stat: *const c_void, ocvrs_return: *mut sys::Result<f64>) {
+	let instance = sys::instance_mut::<dyn ERFilter_CallbackImpl>(instance);
+	// stat is owned by the C++ caller
+	let stat = ManuallyDrop::new(ERStat::from_raw(stat as *mut c_void));
+	ocvrs_return.write(sys::Result::from_callback(|| instance.eval(&stat)));
+}
+
+impl Ptr<dyn ERFilter_Callback> {
+	/// Wraps the Rust implementation of the callback into the C++ `ERFilter::Callback` object
+	pub fn from_rust(callback: impl ERFilter_CallbackImpl + 'static) -> Result<Self> {
+		extern "C" {
+			fn cv_manual_PtrOfERFilter_Callback_from_rust(
+				instance: *mut c_void,
+				vtable: *const Vtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		let callback: Box<dyn ERFilter_CallbackImpl> = Box::new(callback);
+		unsafe {
+			sys::into_extern(callback, |instance, ocvrs_return| {
+				cv_manual_PtrOfERFilter_Callback_from_rust(instance, &VTABLE, ocvrs_return)
+			})
+		}
+		.map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
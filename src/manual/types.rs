@@ -13,6 +13,12 @@ impl From<Unit> for () {
 	fn from(_: Unit) -> Self {}
 }
 
+impl From<()> for Unit {
+	fn from(_: ()) -> Self {
+		Self([])
+	}
+}
+
 input_output_array! { types::VectorOfMat, from_mat_vec, from_mat_vec_mut }
 
 input_output_array! { types::VectorOfUMat, from_umat_vec, from_umat_vec_mut }
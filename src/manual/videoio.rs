@@ -0,0 +1,17 @@
+#[cfg(ocvrs_opencv_4_5_2)]
+pub use builder::*;
+pub use fourcc::*;
+pub use frame_source::*;
+pub use frames::*;
+#[cfg(feature = "async")]
+pub use stream::*;
+pub use threaded::*;
+
+#[cfg(ocvrs_opencv_4_5_2)]
+mod builder;
+mod fourcc;
+mod frame_source;
+mod frames;
+#[cfg(feature = "async")]
+mod stream;
+mod threaded;
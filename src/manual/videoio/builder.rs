@@ -0,0 +1,449 @@
+use std::{convert::TryFrom, time::Duration};
+
+use crate::{
+	core::{self, Size, Vector},
+	prelude::*,
+	videoio::{self, FourCC, VideoAccelerationType, VideoCapture, VideoCaptureAPIs, VideoWriter},
+	Error, Result,
+};
+
+/// Outcome of applying a single property when opening a capture or a writer with a builder
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PropertyStatus {
+	/// `CAP_PROP_*` or `VIDEOWRITER_PROP_*` property id
+	pub prop_id: i32,
+	/// Value requested with the builder
+	pub requested: f64,
+	/// Value reported by the backend after opening
+	pub actual: f64,
+	/// Whether the backend accepted the property
+	pub accepted: bool,
+}
+
+/// Report of the properties applied by [VideoCaptureBuilder] or [VideoWriterBuilder]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PropertyReport {
+	statuses: Vec<PropertyStatus>,
+}
+
+impl PropertyReport {
+	/// Statuses of all the requested properties in the order they were applied
+	pub fn statuses(&self) -> &[PropertyStatus] {
+		&self.statuses
+	}
+
+	/// Returns the status of the specified property if it was requested
+	pub fn status(&self, prop_id: i32) -> Option<&PropertyStatus> {
+		self.statuses.iter().find(|s| s.prop_id == prop_id)
+	}
+
+	/// Properties that the backend has accepted
+	pub fn accepted(&self) -> impl Iterator<Item = &PropertyStatus> {
+		self.statuses.iter().filter(|s| s.accepted)
+	}
+
+	/// Properties that the backend has ignored or rejected
+	pub fn rejected(&self) -> impl Iterator<Item = &PropertyStatus> {
+		self.statuses.iter().filter(|s| !s.accepted)
+	}
+
+	/// Returns `true` if every requested property was accepted
+	pub fn all_accepted(&self) -> bool {
+		self.statuses.iter().all(|s| s.accepted)
+	}
+
+	/// Records the status of the property passed to the constructor, `actual` is read back from the opened object
+	fn push_open_param(&mut self, prop_id: i32, requested: f64, actual: f64, hw_acceleration_prop: i32) {
+		let accepted = if prop_id == hw_acceleration_prop && requested == f64::from(VideoAccelerationType::VIDEO_ACCELERATION_ANY as i32)
+		{
+			// ANY is resolved to the actually used acceleration type
+			actual != f64::from(VideoAccelerationType::VIDEO_ACCELERATION_NONE as i32)
+		} else {
+			actual == requested
+		};
+		self.statuses.push(PropertyStatus {
+			prop_id,
+			requested,
+			actual,
+			accepted,
+		});
+	}
+
+	/// Records the status of the property set after opening
+	fn push_set(&mut self, prop_id: i32, requested: f64, set: bool, actual: f64) {
+		self.statuses.push(PropertyStatus {
+			prop_id,
+			requested,
+			actual,
+			accepted: set,
+		});
+	}
+}
+
+fn validate_fps(fps: f64) -> Result<f64> {
+	if fps.is_finite() && fps > 0. {
+		Ok(fps)
+	} else {
+		Err(Error::new(core::StsOutOfRange, format!("Invalid fps: {}, must be positive", fps)))
+	}
+}
+
+fn validate_frame_size(size: Size) -> Result<Size> {
+	if size.width > 0 && size.height > 0 {
+		Ok(size)
+	} else {
+		Err(Error::new(core::StsOutOfRange, format!("Invalid frame size: {:?}, must be positive", size)))
+	}
+}
+
+fn validate_quality(quality: f64) -> Result<f64> {
+	if (0. ..=100.).contains(&quality) {
+		Ok(quality)
+	} else {
+		Err(Error::new(core::StsOutOfRange, format!("Invalid quality: {}, must be in 0..=100", quality)))
+	}
+}
+
+fn duration_to_msec(duration: Duration) -> Result<i32> {
+	i32::try_from(duration.as_millis())
+		.map_err(|_| Error::new(core::StsOutOfRange, format!("Timeout is too long: {:?}", duration)))
+}
+
+fn acceleration_from_f64(value: f64) -> Result<VideoAccelerationType> {
+	Ok(match value as i32 {
+		videoio::VIDEO_ACCELERATION_NONE => VideoAccelerationType::VIDEO_ACCELERATION_NONE,
+		videoio::VIDEO_ACCELERATION_ANY => VideoAccelerationType::VIDEO_ACCELERATION_ANY,
+		videoio::VIDEO_ACCELERATION_D3D11 => VideoAccelerationType::VIDEO_ACCELERATION_D3D11,
+		videoio::VIDEO_ACCELERATION_VAAPI => VideoAccelerationType::VIDEO_ACCELERATION_VAAPI,
+		videoio::VIDEO_ACCELERATION_MFX => VideoAccelerationType::VIDEO_ACCELERATION_MFX,
+		_ => return Err(Error::new(core::StsBadArg, format!("Unknown video acceleration type: {}", value))),
+	})
+}
+
+/// Typed builder for [VideoCapture]
+///
+/// Properties that OpenCV only supports at open time (hardware acceleration and timeouts) are passed to the constructor, the
+/// rest is set on the opened capture. The returned [PropertyReport] tells which of them the chosen backend has accepted.
+///
+/// ## Example
+/// ```no_run
+/// use std::time::Duration;
+/// use opencv::{core::Size, videoio::{self, VideoCaptureBuilder}};
+///
+/// let (cap, report) = VideoCaptureBuilder::new()
+///     .backend(videoio::VideoCaptureAPIs::CAP_FFMPEG)
+///     .hw_acceleration(videoio::VideoAccelerationType::VIDEO_ACCELERATION_ANY)
+///     .open_timeout(Duration::from_secs(5))?
+///     .frame_size(Size::new(1280, 720))?
+///     .open_file("rtsp://camera.local/stream")?;
+/// for rejected in report.rejected() {
+///     eprintln!("Property {} is not supported", rejected.prop_id);
+/// }
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct VideoCaptureBuilder {
+	backend: VideoCaptureAPIs,
+	open_params: Vec<(i32, i32)>,
+	props: Vec<(i32, f64)>,
+}
+
+impl Default for VideoCaptureBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl VideoCaptureBuilder {
+	/// Creates a builder with automatic backend selection and no properties
+	pub fn new() -> Self {
+		Self {
+			backend: VideoCaptureAPIs::CAP_ANY,
+			open_params: vec![],
+			props: vec![],
+		}
+	}
+
+	/// Preferred capture backend
+	pub fn backend(mut self, backend: VideoCaptureAPIs) -> Self {
+		self.backend = backend;
+		self
+	}
+
+	/// Requested frame size (`CAP_PROP_FRAME_WIDTH` and `CAP_PROP_FRAME_HEIGHT`)
+	pub fn frame_size(self, size: Size) -> Result<Self> {
+		let size = validate_frame_size(size)?;
+		Ok(self
+			.prop(videoio::CAP_PROP_FRAME_WIDTH, f64::from(size.width))
+			.prop(videoio::CAP_PROP_FRAME_HEIGHT, f64::from(size.height)))
+	}
+
+	/// Requested frame rate (`CAP_PROP_FPS`)
+	pub fn fps(self, fps: f64) -> Result<Self> {
+		let fps = validate_fps(fps)?;
+		Ok(self.prop(videoio::CAP_PROP_FPS, fps))
+	}
+
+	/// Requested pixel format code (`CAP_PROP_FOURCC`)
+	pub fn fourcc(self, fourcc: FourCC) -> Self {
+		self.prop(videoio::CAP_PROP_FOURCC, f64::from(fourcc.code()))
+	}
+
+	/// Hardware acceleration for decoding (`CAP_PROP_HW_ACCELERATION`)
+	pub fn hw_acceleration(mut self, acceleration: VideoAccelerationType) -> Self {
+		self.open_params.push((videoio::CAP_PROP_HW_ACCELERATION, acceleration as i32));
+		self
+	}
+
+	/// Index of the hardware device to use for the acceleration (`CAP_PROP_HW_DEVICE`)
+	pub fn hw_device(mut self, device: i32) -> Self {
+		self.open_params.push((videoio::CAP_PROP_HW_DEVICE, device));
+		self
+	}
+
+	/// Timeout for opening the stream (`CAP_PROP_OPEN_TIMEOUT_MSEC`)
+	pub fn open_timeout(mut self, timeout: Duration) -> Result<Self> {
+		self.open_params.push((videoio::CAP_PROP_OPEN_TIMEOUT_MSEC, duration_to_msec(timeout)?));
+		Ok(self)
+	}
+
+	/// Timeout for reading a frame (`CAP_PROP_READ_TIMEOUT_MSEC`)
+	pub fn read_timeout(mut self, timeout: Duration) -> Result<Self> {
+		self.open_params.push((videoio::CAP_PROP_READ_TIMEOUT_MSEC, duration_to_msec(timeout)?));
+		Ok(self)
+	}
+
+	/// Arbitrary `CAP_PROP_*` property to set after the capture is opened
+	pub fn prop(mut self, prop_id: i32, value: f64) -> Self {
+		self.props.push((prop_id, value));
+		self
+	}
+
+	/// Opens the video file, image sequence or URL
+	pub fn open_file(&self, filename: &str) -> Result<(VideoCapture, PropertyReport)> {
+		let cap = VideoCapture::from_file_with_params(filename, self.backend as i32, &self.open_params_vector())?;
+		self.finish(cap, filename)
+	}
+
+	/// Opens the camera with the specified index
+	pub fn open_camera(&self, index: i32) -> Result<(VideoCapture, PropertyReport)> {
+		let cap = VideoCapture::new_with_params(index, self.backend as i32, &self.open_params_vector())?;
+		self.finish(cap, &format!("camera {}", index))
+	}
+
+	fn open_params_vector(&self) -> Vector<i32> {
+		self.open_params.iter().flat_map(|&(prop_id, value)| [prop_id, value]).collect()
+	}
+
+	fn finish(&self, mut cap: VideoCapture, source: &str) -> Result<(VideoCapture, PropertyReport)> {
+		if !cap.is_opened()? {
+			return Err(Error::new(core::StsError, format!("Can't open video capture for {}", source)));
+		}
+		let mut report = PropertyReport::default();
+		for &(prop_id, value) in &self.open_params {
+			let actual = cap.get(prop_id)?;
+			report.push_open_param(prop_id, f64::from(value), actual, videoio::CAP_PROP_HW_ACCELERATION);
+		}
+		for &(prop_id, value) in &self.props {
+			let set = cap.set(prop_id, value)?;
+			let actual = cap.get(prop_id)?;
+			report.push_set(prop_id, value, set, actual);
+		}
+		Ok((cap, report))
+	}
+}
+
+/// Typed builder for [VideoWriter]
+///
+/// Frame size, frame rate and codec must be specified before calling [VideoWriterBuilder::open].
+///
+/// ## Example
+/// ```no_run
+/// use opencv::{core::Size, videoio::{FourCC, VideoWriterBuilder}};
+///
+/// let (writer, _report) = VideoWriterBuilder::new()
+///     .fourcc(FourCC::MJPG)
+///     .fps(25.)?
+///     .frame_size(Size::new(640, 480))?
+///     .quality(90.)?
+///     .open("out.avi")?;
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct VideoWriterBuilder {
+	backend: VideoCaptureAPIs,
+	fourcc: Option<FourCC>,
+	fps: Option<f64>,
+	frame_size: Option<Size>,
+	open_params: Vec<(i32, i32)>,
+	props: Vec<(i32, f64)>,
+}
+
+impl Default for VideoWriterBuilder {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl VideoWriterBuilder {
+	/// Creates a builder with automatic backend selection and no properties
+	pub fn new() -> Self {
+		Self {
+			backend: VideoCaptureAPIs::CAP_ANY,
+			fourcc: None,
+			fps: None,
+			frame_size: None,
+			open_params: vec![],
+			props: vec![],
+		}
+	}
+
+	/// Preferred writer backend
+	pub fn backend(mut self, backend: VideoCaptureAPIs) -> Self {
+		self.backend = backend;
+		self
+	}
+
+	/// Size of the video frames
+	pub fn frame_size(mut self, size: Size) -> Result<Self> {
+		self.frame_size = Some(validate_frame_size(size)?);
+		Ok(self)
+	}
+
+	/// Frame rate of the created video stream
+	pub fn fps(mut self, fps: f64) -> Result<Self> {
+		self.fps = Some(validate_fps(fps)?);
+		Ok(self)
+	}
+
+	/// Code of the codec used to compress the frames
+	pub fn fourcc(mut self, fourcc: FourCC) -> Self {
+		self.fourcc = Some(fourcc);
+		self
+	}
+
+	/// Whether the frames are color or grayscale (`VIDEOWRITER_PROP_IS_COLOR`), color by default
+	pub fn is_color(mut self, is_color: bool) -> Self {
+		self.open_params.push((videoio::VIDEOWRITER_PROP_IS_COLOR, i32::from(is_color)));
+		self
+	}
+
+	/// Hardware acceleration for encoding (`VIDEOWRITER_PROP_HW_ACCELERATION`)
+	pub fn hw_acceleration(mut self, acceleration: VideoAccelerationType) -> Self {
+		self.open_params.push((videoio::VIDEOWRITER_PROP_HW_ACCELERATION, acceleration as i32));
+		self
+	}
+
+	/// Index of the hardware device to use for the acceleration (`VIDEOWRITER_PROP_HW_DEVICE`)
+	pub fn hw_device(mut self, device: i32) -> Self {
+		self.open_params.push((videoio::VIDEOWRITER_PROP_HW_DEVICE, device));
+		self
+	}
+
+	/// Encoding quality in the range `0..=100` (`VIDEOWRITER_PROP_QUALITY`), not supported by all codecs
+	pub fn quality(self, quality: f64) -> Result<Self> {
+		let quality = validate_quality(quality)?;
+		Ok(self.prop(videoio::VIDEOWRITER_PROP_QUALITY, quality))
+	}
+
+	/// Arbitrary `VIDEOWRITER_PROP_*` property to set after the writer is opened
+	pub fn prop(mut self, prop_id: i32, value: f64) -> Self {
+		self.props.push((prop_id, value));
+		self
+	}
+
+	/// Opens the output file or stream
+	pub fn open(&self, filename: &str) -> Result<(VideoWriter, PropertyReport)> {
+		let missing = |name: &str| Error::new(core::StsBadArg, format!("VideoWriterBuilder: {} is not specified", name));
+		let fourcc = self.fourcc.ok_or_else(|| missing("fourcc"))?;
+		let fps = self.fps.ok_or_else(|| missing("fps"))?;
+		let frame_size = self.frame_size.ok_or_else(|| missing("frame size"))?;
+		let params = self
+			.open_params
+			.iter()
+			.flat_map(|&(prop_id, value)| [prop_id, value])
+			.collect::<Vector<i32>>();
+		let mut writer = VideoWriter::new_2(filename, self.backend as i32, fourcc.code(), fps, frame_size, &params)?;
+		if !writer.is_opened()? {
+			return Err(Error::new(core::StsError, format!("Can't open video writer for {}", filename)));
+		}
+		let mut report = PropertyReport::default();
+		for &(prop_id, value) in &self.open_params {
+			let actual = writer.get(prop_id)?;
+			report.push_open_param(prop_id, f64::from(value), actual, videoio::VIDEOWRITER_PROP_HW_ACCELERATION);
+		}
+		for &(prop_id, value) in &self.props {
+			let set = writer.set(prop_id, value)?;
+			let actual = writer.get(prop_id)?;
+			report.push_set(prop_id, value, set, actual);
+		}
+		Ok((writer, report))
+	}
+}
+
+impl VideoCapture {
+	/// Current frame size (`CAP_PROP_FRAME_WIDTH` and `CAP_PROP_FRAME_HEIGHT`)
+	pub fn frame_size(&self) -> Result<Size> {
+		Ok(Size::new(
+			self.get(videoio::CAP_PROP_FRAME_WIDTH)? as i32,
+			self.get(videoio::CAP_PROP_FRAME_HEIGHT)? as i32,
+		))
+	}
+
+	/// Sets the frame size, returns `true` if the backend has accepted both dimensions
+	pub fn set_frame_size(&mut self, size: Size) -> Result<bool> {
+		let size = validate_frame_size(size)?;
+		let width = self.set(videoio::CAP_PROP_FRAME_WIDTH, f64::from(size.width))?;
+		let height = self.set(videoio::CAP_PROP_FRAME_HEIGHT, f64::from(size.height))?;
+		Ok(width && height)
+	}
+
+	/// Current frame rate (`CAP_PROP_FPS`), `0.` if unknown
+	pub fn fps(&self) -> Result<f64> {
+		self.get(videoio::CAP_PROP_FPS)
+	}
+
+	/// Sets the frame rate, returns `true` if the backend has accepted it
+	pub fn set_fps(&mut self, fps: f64) -> Result<bool> {
+		let fps = validate_fps(fps)?;
+		self.set(videoio::CAP_PROP_FPS, fps)
+	}
+
+	/// Pixel format code of the stream (`CAP_PROP_FOURCC`), `None` if the backend doesn't report it
+	pub fn fourcc(&self) -> Result<Option<FourCC>> {
+		let code = self.get(videoio::CAP_PROP_FOURCC)?;
+		if code == 0. {
+			Ok(None)
+		} else {
+			FourCC::try_from(code).map(Some)
+		}
+	}
+
+	/// Sets the pixel format code, returns `true` if the backend has accepted it
+	pub fn set_fourcc(&mut self, fourcc: FourCC) -> Result<bool> {
+		self.set(videoio::CAP_PROP_FOURCC, f64::from(fourcc.code()))
+	}
+
+	/// Hardware acceleration used for decoding (`CAP_PROP_HW_ACCELERATION`)
+	pub fn hw_acceleration(&self) -> Result<VideoAccelerationType> {
+		acceleration_from_f64(self.get(videoio::CAP_PROP_HW_ACCELERATION)?)
+	}
+}
+
+impl VideoWriter {
+	/// Encoding quality in the range `0..=100` (`VIDEOWRITER_PROP_QUALITY`)
+	pub fn quality(&self) -> Result<f64> {
+		self.get(videoio::VIDEOWRITER_PROP_QUALITY)
+	}
+
+	/// Sets the encoding quality, returns `true` if the codec supports it
+	pub fn set_quality(&mut self, quality: f64) -> Result<bool> {
+		let quality = validate_quality(quality)?;
+		self.set(videoio::VIDEOWRITER_PROP_QUALITY, quality)
+	}
+
+	/// Hardware acceleration used for encoding (`VIDEOWRITER_PROP_HW_ACCELERATION`)
+	pub fn hw_acceleration(&self) -> Result<VideoAccelerationType> {
+		acceleration_from_f64(self.get(videoio::VIDEOWRITER_PROP_HW_ACCELERATION)?)
+	}
+}
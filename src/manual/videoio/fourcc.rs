@@ -0,0 +1,176 @@
+use std::{
+	convert::TryFrom,
+	fmt,
+	str::{self, FromStr},
+};
+
+use crate::{
+	core::{self, Size},
+	prelude::*,
+	videoio::VideoWriter,
+	Error, Result,
+};
+
+/// Four character code that identifies a video codec or a pixel format
+///
+/// All four characters are guaranteed to be ASCII. Use `i32::from()` to get the code accepted by the OpenCV functions and
+/// [FourCC::try_from] to convert the `f64` value returned for `CAP_PROP_FOURCC` by `VideoCapture::get()`.
+///
+/// ## Example
+/// ```
+/// use opencv::videoio::FourCC;
+///
+/// let fourcc: FourCC = "MJPG".parse()?;
+/// assert_eq!(FourCC::MJPG, fourcc);
+/// assert_eq!("MJPG", fourcc.to_string());
+/// assert_eq!(0x47504a4d, i32::from(fourcc));
+/// # Ok::<_, opencv::Error>(())
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FourCC([u8; 4]);
+
+impl FourCC {
+	/// Motion JPEG
+	pub const MJPG: Self = Self(*b"MJPG");
+	/// Xvid MPEG-4 Part 2
+	pub const XVID: Self = Self(*b"XVID");
+	/// DivX MPEG-4 Part 2
+	pub const DIVX: Self = Self(*b"DIVX");
+	/// MPEG-4 Part 2
+	pub const MP4V: Self = Self(*b"mp4v");
+	/// H.264
+	pub const H264: Self = Self(*b"H264");
+	/// H.264 in the form usually expected by the MP4 container
+	pub const AVC1: Self = Self(*b"avc1");
+	/// H.264 encoded by x264
+	pub const X264: Self = Self(*b"X264");
+	/// H.265
+	pub const HEVC: Self = Self(*b"HEVC");
+	/// H.265 in the form usually expected by the MP4 container
+	pub const HVC1: Self = Self(*b"hvc1");
+	/// VP8
+	pub const VP80: Self = Self(*b"VP80");
+	/// VP9
+	pub const VP90: Self = Self(*b"VP90");
+	/// AV1
+	pub const AV01: Self = Self(*b"av01");
+	/// FFV1 lossless codec
+	pub const FFV1: Self = Self(*b"FFV1");
+	/// Packed YUV 4:2:2 pixel format
+	pub const YUYV: Self = Self(*b"YUYV");
+	/// Planar YUV 4:2:0 pixel format with interleaved UV plane
+	pub const NV12: Self = Self(*b"NV12");
+	/// 8-bit grayscale pixel format
+	pub const GREY: Self = Self(*b"GREY");
+
+	/// Creates the code from 4 characters, fails if any of them is not ASCII
+	pub fn new(c1: char, c2: char, c3: char, c4: char) -> Result<Self> {
+		let mut chars = [0; 4];
+		for (dst, c) in chars.iter_mut().zip([c1, c2, c3, c4]) {
+			if !c.is_ascii() {
+				return Err(Error::new(
+					core::StsBadArg,
+					format!("FourCC character must be ASCII, got: {:?}", c),
+				));
+			}
+			*dst = c as u8;
+		}
+		Ok(Self(chars))
+	}
+
+	/// Creates the code from 4 bytes, fails if any of them is not ASCII
+	pub fn from_bytes(bytes: [u8; 4]) -> Result<Self> {
+		if bytes.is_ascii() {
+			Ok(Self(bytes))
+		} else {
+			Err(Error::new(
+				core::StsBadArg,
+				format!("FourCC bytes must be ASCII, got: {:?}", bytes),
+			))
+		}
+	}
+
+	/// Characters of the code in the order they are written
+	pub fn as_bytes(&self) -> &[u8; 4] {
+		&self.0
+	}
+
+	/// Numeric code as used by OpenCV, the same as `VideoWriter::fourcc()` returns
+	pub fn code(self) -> i32 {
+		i32::from_le_bytes(self.0)
+	}
+}
+
+impl FromStr for FourCC {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self> {
+		let bytes = <[u8; 4]>::try_from(s.as_bytes()).map_err(|_| {
+			Error::new(
+				core::StsBadArg,
+				format!("FourCC must be exactly 4 characters long, got: {:?}", s),
+			)
+		})?;
+		Self::from_bytes(bytes)
+	}
+}
+
+impl fmt::Display for FourCC {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for &b in &self.0 {
+			fmt::Display::fmt(&(b as char).escape_default(), f)?;
+		}
+		Ok(())
+	}
+}
+
+impl fmt::Debug for FourCC {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "FourCC(\"{}\")", self)
+	}
+}
+
+impl From<FourCC> for i32 {
+	#[inline]
+	fn from(fourcc: FourCC) -> Self {
+		fourcc.code()
+	}
+}
+
+impl TryFrom<i32> for FourCC {
+	type Error = Error;
+
+	/// Fails for `0` which backends use to signal that the codec is unknown
+	fn try_from(code: i32) -> Result<Self> {
+		if code == 0 {
+			return Err(Error::new(core::StsBadArg, "FourCC value 0 doesn't identify a codec"));
+		}
+		Self::from_bytes(code.to_le_bytes())
+	}
+}
+
+impl TryFrom<f64> for FourCC {
+	type Error = Error;
+
+	/// Converts the value returned by `VideoCapture::get(CAP_PROP_FOURCC)`
+	fn try_from(code: f64) -> Result<Self> {
+		if code.fract() != 0. || code < f64::from(i32::MIN) || code > f64::from(u32::MAX) {
+			return Err(Error::new(core::StsBadArg, format!("Invalid FourCC value: {}", code)));
+		}
+		// backends return the code both as signed and unsigned 32-bit value
+		let code = if code < 0. { code as i32 } else { code as u32 as i32 };
+		Self::try_from(code)
+	}
+}
+
+impl VideoWriter {
+	/// Same as `VideoWriter::new()`, but takes the codec as [FourCC]
+	pub fn new_with_fourcc(filename: &str, fourcc: FourCC, fps: f64, frame_size: Size, is_color: bool) -> Result<Self> {
+		Self::new(filename, fourcc.code(), fps, frame_size, is_color)
+	}
+
+	/// Same as `VideoWriter::open()`, but takes the codec as [FourCC]
+	pub fn open_with_fourcc(&mut self, filename: &str, fourcc: FourCC, fps: f64, frame_size: Size, is_color: bool) -> Result<bool> {
+		self.open(filename, fourcc.code(), fps, frame_size, is_color)
+	}
+}
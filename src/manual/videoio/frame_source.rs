@@ -0,0 +1,103 @@
+use std::{ffi::c_void, mem::ManuallyDrop};
+
+use crate::{
+	core::{self, Mat},
+	prelude::*,
+	sys,
+	types::Unit,
+	videoio::VideoCapture,
+	Error, Result,
+};
+
+/// Source of video frames implemented in Rust
+///
+/// It can be wrapped into a [VideoCapture] with [VideoCapture::from_source] to be used in the code that expects a capture, or
+/// passed to the `videostab` and `superres` modules with the corresponding `Ptr::from_rust()` functions.
+///
+/// Property ids passed to `get()` and `set()` are the `CAP_PROP_*` constants.
+pub trait FrameSource: Send {
+	/// Reads the next frame into `frame`, returns `false` when there are no more frames
+	fn read(&mut self, frame: &mut Mat) -> Result<bool>;
+
+	/// Returns the value of the specified property, `0.` means that the property is not supported
+	fn get(&self, _prop_id: i32) -> f64 {
+		0.
+	}
+
+	/// Sets the value of the specified property, returns `false` if the property is not supported
+	fn set(&mut self, _prop_id: i32, _value: f64) -> Result<bool> {
+		Ok(false)
+	}
+
+	/// Rewinds the source to the first frame
+	fn reset(&mut self) -> Result<()> {
+		Err(Error::new(core::StsNotImplemented, "Frame source doesn't support reset"))
+	}
+}
+
+/// Function table used by the C++ classes that forward to [FrameSource], matches `OcvrsFrameSourceVtable` in `ocvrs_frame_source.hpp`
+#[repr(C)]
+pub(crate) struct FrameSourceVtable {
+	drop: unsafe extern "C" fn(*mut c_void),
+	read: unsafe extern "C" fn(*mut c_void, *mut c_void, *mut sys::Result<bool>),
+	get: unsafe extern "C" fn(*const c_void, i32, *mut sys::Result<f64>),
+	set: unsafe extern "C" fn(*mut c_void, i32, f64, *mut sys::Result<bool>),
+	reset: unsafe extern "C" fn(*mut c_void, *mut sys::Result_void),
+}
+
+static VTABLE: FrameSourceVtable = FrameSourceVtable {
+	drop: sys::drop_instance::<dyn FrameSource>,
+	read: vtable_read,
+	get: vtable_get,
+	set: vtable_set,
+	reset: vtable_reset,
+};
+
+unsafe extern "C" fn vtable_read(instance: *mut c_void, frame: *mut c_void, ocvrs_return: *mut sys::Result<bool>) {
+	let instance = sys::instance_mut::<dyn FrameSource>(instance);
+	let mut frame = ManuallyDrop::new(Mat::from_raw(frame));
+	ocvrs_return.write(sys::Result::from_callback(|| instance.read(&mut frame)));
+}
+
+unsafe extern "C" fn vtable_get(instance: *const c_void, prop_id: i32, ocvrs_return: *mut sys::Result<f64>) {
+	let instance = sys::instance_ref::<dyn FrameSource>(instance);
+	ocvrs_return.write(sys::Result::from_callback(|| Ok(instance.get(prop_id))));
+}
+
+unsafe extern "C" fn vtable_set(instance: *mut c_void, prop_id: i32, value: f64, ocvrs_return: *mut sys::Result<bool>) {
+	let instance = sys::instance_mut::<dyn FrameSource>(instance);
+	ocvrs_return.write(sys::Result::from_callback(|| instance.set(prop_id, value)));
+}
+
+unsafe extern "C" fn vtable_reset(instance: *mut c_void, ocvrs_return: *mut sys::Result_void) {
+	let instance = sys::instance_mut::<dyn FrameSource>(instance);
+	ocvrs_return.write(sys::Result::<Unit, ()>::from_callback(|| instance.reset()));
+}
+
+/// Creates the C++ object that wraps `source` using the `extern_new` function
+///
+/// `extern_new` takes the ownership of the instance only on success.
+pub(crate) fn frame_source_into_extern(
+	source: impl FrameSource + 'static,
+	extern_new: unsafe extern "C" fn(*mut c_void, *const FrameSourceVtable, *mut sys::Result<*mut c_void>),
+) -> Result<*mut c_void> {
+	let source: Box<dyn FrameSource> = Box::new(source);
+	unsafe { sys::into_extern(source, |instance, ocvrs_return| extern_new(instance, &VTABLE, ocvrs_return)) }
+}
+
+impl VideoCapture {
+	/// Creates a `VideoCapture` that reads the frames from the Rust [FrameSource]
+	///
+	/// `read()`, `grab()`, `retrieve()`, `get()`, `set()`, `is_opened()` and `release()` are forwarded to the `source`.
+	/// `VideoCapture::wait_any()` doesn't support such captures.
+	pub fn from_source(source: impl FrameSource + 'static) -> Result<Self> {
+		extern "C" {
+			fn cv_manual_VideoCapture_from_source(
+				instance: *mut c_void,
+				vtable: *const FrameSourceVtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		frame_source_into_extern(source, cv_manual_VideoCapture_from_source).map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
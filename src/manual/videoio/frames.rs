@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use crate::{core::Mat, prelude::*, videoio, videoio::VideoCapture, Result};
+
+/// Video frame together with its position in the stream, produced by [VideoCapture::frames] and [VideoCapture::frames_into]
+#[derive(Debug)]
+pub struct Frame<M = Mat> {
+	/// Frame image
+	pub mat: M,
+	/// Sequential index of the frame since the start of the iteration
+	pub index: u64,
+	/// Position of the frame in the stream as reported by `CAP_PROP_POS_MSEC`, `None` if the backend doesn't provide it
+	pub timestamp: Option<Duration>,
+	/// Raw value of the hardware timestamp property if it was requested with `with_hw_timestamp()` and the backend provided it
+	pub hw_timestamp: Option<f64>,
+}
+
+/// Reads the frame metadata from `cap` right after the frame was read
+struct FrameReader {
+	index: u64,
+	hw_timestamp_prop: Option<i32>,
+	done: bool,
+}
+
+impl FrameReader {
+	fn new() -> Self {
+		Self {
+			index: 0,
+			hw_timestamp_prop: None,
+			done: false,
+		}
+	}
+
+	/// Reads the next frame into `mat` and returns the metadata, `None` at the end of the stream or after the first error
+	fn read_into<'m, M>(
+		&mut self,
+		cap: &mut VideoCapture,
+		mat: &'m mut Mat,
+		wrap: impl FnOnce(&'m mut Mat) -> M,
+	) -> Option<Result<Frame<M>>> {
+		if self.done {
+			return None;
+		}
+		let res = cap.read(mat).and_then(|has_frame| {
+			if !has_frame || mat.empty() {
+				return Ok(None);
+			}
+			let timestamp = cap.get(videoio::CAP_PROP_POS_MSEC).map(msec_to_duration)?;
+			let hw_timestamp = self
+				.hw_timestamp_prop
+				.map(|prop_id| cap.get(prop_id))
+				.transpose()?
+				.filter(|&ts| ts != 0.);
+			Ok(Some((timestamp, hw_timestamp)))
+		});
+		match res {
+			Ok(Some((timestamp, hw_timestamp))) => {
+				let index = self.index;
+				self.index += 1;
+				Some(Ok(Frame {
+					mat: wrap(mat),
+					index,
+					timestamp,
+					hw_timestamp,
+				}))
+			}
+			Ok(None) => {
+				self.done = true;
+				None
+			}
+			Err(e) => {
+				self.done = true;
+				Some(Err(e))
+			}
+		}
+	}
+}
+
+/// Converts the `CAP_PROP_POS_MSEC` value, backends return negative values when the position is unknown
+pub(super) fn msec_to_duration(msec: f64) -> Option<Duration> {
+	if msec.is_finite() && msec >= 0. {
+		Some(Duration::from_secs_f64(msec / 1000.))
+	} else {
+		None
+	}
+}
+
+/// Iterator over the frames of a [VideoCapture], see [VideoCapture::frames]
+pub struct Frames<'c> {
+	cap: &'c mut VideoCapture,
+	reader: FrameReader,
+}
+
+impl Frames<'_> {
+	/// Additionally read the specified backend-specific property as the hardware timestamp of every frame
+	///
+	/// There is no backend independent hardware timestamp property, so it must be specified explicitly. The value is returned
+	/// as is in [Frame::hw_timestamp], a `0.` value is treated as not available.
+	pub fn with_hw_timestamp(mut self, prop_id: i32) -> Self {
+		self.reader.hw_timestamp_prop = Some(prop_id);
+		self
+	}
+}
+
+impl Iterator for Frames<'_> {
+	type Item = Result<Frame>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let mut mat = Mat::default();
+		self.reader.read_into(self.cap, &mut mat, std::mem::take)
+	}
+}
+
+/// Lending iterator over the frames of a [VideoCapture] that reuses a single buffer, see [VideoCapture::frames_into]
+pub struct FramesInto<'c, 'm> {
+	cap: &'c mut VideoCapture,
+	mat: &'m mut Mat,
+	reader: FrameReader,
+}
+
+impl FramesInto<'_, '_> {
+	/// Additionally read the specified backend-specific property as the hardware timestamp of every frame, see
+	/// [Frames::with_hw_timestamp]
+	pub fn with_hw_timestamp(mut self, prop_id: i32) -> Self {
+		self.reader.hw_timestamp_prop = Some(prop_id);
+		self
+	}
+
+	/// Reads the next frame into the buffer, returns `None` at the end of the stream or after the first error
+	///
+	/// The returned frame borrows the buffer so it must be dropped before the next call.
+	pub fn next_frame(&mut self) -> Option<Result<Frame<&Mat>>> {
+		self.reader.read_into(self.cap, self.mat, |mat| &*mat)
+	}
+}
+
+impl VideoCapture {
+	/// Returns an iterator over the remaining frames of the capture
+	///
+	/// Each item is either a new [Frame] or the error returned by the capture. The iteration ends when the capture reports that
+	/// there are no more frames (`read()` returns `false` or an empty frame) or after the first error. A new `Mat` is allocated
+	/// for every frame so it can be kept or sent to another thread, use [VideoCapture::frames_into] to avoid the allocations.
+	///
+	/// ## Example
+	/// ```no_run
+	/// use opencv::{prelude::*, videoio};
+	///
+	/// let mut cap = videoio::VideoCapture::from_file("video.mp4", videoio::CAP_ANY)?;
+	/// for frame in cap.frames() {
+	///     let frame = frame?;
+	///     println!("frame {} at {:?}: {:?}", frame.index, frame.timestamp, frame.mat.size()?);
+	/// }
+	/// # Ok::<_, opencv::Error>(())
+	/// ```
+	pub fn frames(&mut self) -> Frames<'_> {
+		Frames {
+			cap: self,
+			reader: FrameReader::new(),
+		}
+	}
+
+	/// Same as [VideoCapture::frames], but reads all the frames into the same `buf`
+	///
+	/// The result doesn't implement `Iterator` because each frame borrows the buffer, use `while let` with
+	/// [FramesInto::next_frame] instead.
+	///
+	/// ## Example
+	/// ```no_run
+	/// use opencv::{prelude::*, videoio};
+	///
+	/// let mut cap = videoio::VideoCapture::from_file("video.mp4", videoio::CAP_ANY)?;
+	/// let mut buf = Mat::default();
+	/// let mut frames = cap.frames_into(&mut buf);
+	/// while let Some(frame) = frames.next_frame() {
+	///     let frame = frame?;
+	///     println!("frame {}: {:?}", frame.index, frame.mat.size()?);
+	/// }
+	/// # Ok::<_, opencv::Error>(())
+	/// ```
+	pub fn frames_into<'m>(&mut self, buf: &'m mut Mat) -> FramesInto<'_, 'm> {
+		FramesInto {
+			cap: self,
+			mat: buf,
+			reader: FrameReader::new(),
+		}
+	}
+}
@@ -0,0 +1,158 @@
+use std::{
+	fmt,
+	pin::Pin,
+	sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+	task::{Context, Poll, Waker},
+	thread,
+};
+
+use futures_core::Stream;
+
+use crate::{
+	core::{self, Mat},
+	videoio::VideoCapture,
+	Error, Result,
+};
+
+struct State {
+	frame: Option<Result<Mat>>,
+	waker: Option<Waker>,
+	finished: bool,
+	cancelled: bool,
+}
+
+struct Shared {
+	state: Mutex<State>,
+	frame_taken: Condvar,
+}
+
+impl Shared {
+	fn lock(&self) -> MutexGuard<'_, State> {
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Hands the frame over to the stream, returns `false` if the stream was dropped
+	fn send(&self, frame: Result<Mat>) -> bool {
+		let mut state = self.lock();
+		while !state.cancelled && state.frame.is_some() {
+			state = self.frame_taken.wait(state).unwrap_or_else(PoisonError::into_inner);
+		}
+		if state.cancelled {
+			return false;
+		}
+		state.frame = Some(frame);
+		if let Some(waker) = state.waker.take() {
+			waker.wake();
+		}
+		true
+	}
+
+	fn finish(&self) {
+		let mut state = self.lock();
+		state.finished = true;
+		if let Some(waker) = state.waker.take() {
+			waker.wake();
+		}
+	}
+}
+
+/// Asynchronous stream of the frames of a [VideoCapture], see [VideoCapture::into_stream]
+#[must_use = "streams do nothing unless polled"]
+pub struct FrameStream {
+	cap: Option<VideoCapture>,
+	shared: Arc<Shared>,
+}
+
+impl FrameStream {
+	fn start(&mut self, mut cap: VideoCapture) -> Result<()> {
+		let shared = Arc::clone(&self.shared);
+		thread::Builder::new()
+			.name("opencv-frame-stream".to_string())
+			.spawn(move || {
+				for frame in cap.frames() {
+					let is_err = frame.is_err();
+					if !shared.send(frame.map(|frame| frame.mat)) || is_err {
+						break;
+					}
+				}
+				shared.finish();
+			})
+			.map(|_| ())
+			.map_err(|e| Error::new(core::StsError, format!("Can't spawn frame stream thread: {}", e)))
+	}
+}
+
+impl Stream for FrameStream {
+	type Item = Result<Mat>;
+
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		if let Some(cap) = self.cap.take() {
+			if let Err(e) = self.start(cap) {
+				self.shared.lock().finished = true;
+				return Poll::Ready(Some(Err(e)));
+			}
+		}
+		let mut state = self.shared.lock();
+		if let Some(frame) = state.frame.take() {
+			self.shared.frame_taken.notify_all();
+			Poll::Ready(Some(frame))
+		} else if state.finished {
+			Poll::Ready(None)
+		} else {
+			state.waker = Some(cx.waker().clone());
+			Poll::Pending
+		}
+	}
+}
+
+impl Drop for FrameStream {
+	fn drop(&mut self) {
+		let mut state = self.shared.lock();
+		state.cancelled = true;
+		state.frame = None;
+		state.waker = None;
+		self.shared.frame_taken.notify_all();
+	}
+}
+
+impl fmt::Debug for FrameStream {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("FrameStream").field("started", &self.cap.is_none()).finish()
+	}
+}
+
+impl VideoCapture {
+	/// Converts the capture into an asynchronous stream of frames
+	///
+	/// The frames are read on a dedicated thread that is started on the first poll, reading only proceeds when the previous frame
+	/// was taken from the stream. The stream ends when the capture has no more frames or after the first error. Dropping the
+	/// stream stops the reading thread after the current `read()` call returns.
+	///
+	/// ## Example
+	/// ```no_run
+	/// use futures_util::StreamExt;
+	/// use opencv::{prelude::*, videoio};
+	///
+	/// # async fn f() -> opencv::Result<()> {
+	/// let mut frames = videoio::VideoCapture::new(0, videoio::CAP_ANY)?.into_stream();
+	/// while let Some(frame) = frames.next().await {
+	///     println!("{:?}", frame?.size()?);
+	/// }
+	/// # Ok(())
+	/// # }
+	/// ```
+	pub fn into_stream(self) -> FrameStream {
+		FrameStream {
+			cap: Some(self),
+			shared: Arc::new(Shared {
+				state: Mutex::new(State {
+					frame: None,
+					waker: None,
+					finished: false,
+					cancelled: false,
+				}),
+				frame_taken: Condvar::new(),
+			}),
+		}
+	}
+}
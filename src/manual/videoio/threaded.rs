@@ -0,0 +1,348 @@
+use std::{
+	collections::VecDeque,
+	fmt,
+	sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+	thread::{self, JoinHandle},
+	time::{Duration, Instant},
+};
+
+#[cfg(ocvrs_opencv_4_5_2)]
+use super::frames::msec_to_duration;
+use crate::{
+	core,
+	videoio::{Frame, VideoCapture},
+	Error, Result,
+};
+#[cfg(ocvrs_opencv_4_5_2)]
+use crate::{
+	core::{Mat, Vector},
+	prelude::*,
+	videoio,
+};
+
+/// What [ThreadedCapture] does with a new frame when the buffer of its stream is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DropPolicy {
+	/// Discard the oldest buffered frame to make space for the new one, keeps the latency bounded
+	DropOldest,
+	/// Discard the new frame, keeps the buffered frames intact
+	DropNewest,
+	/// Stop capturing until there is space in the buffer, no frames are lost, but the capture may fall behind the camera
+	Block,
+}
+
+/// Frame counters of a single stream of the [ThreadedCapture]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CaptureStats {
+	/// Number of frames read from the capture
+	pub captured: u64,
+	/// Number of frames that were discarded without being received, either because of the [DropPolicy] or by
+	/// [ThreadedCapture::latest]
+	pub dropped: u64,
+	/// Number of frames currently in the buffer
+	pub buffered: usize,
+}
+
+#[derive(Default)]
+struct StreamBuffer {
+	frames: VecDeque<Frame>,
+	captured: u64,
+	dropped: u64,
+}
+
+struct State {
+	streams: Vec<StreamBuffer>,
+	error: Option<Error>,
+	finished: bool,
+	stop: bool,
+}
+
+struct Shared {
+	state: Mutex<State>,
+	capacity: usize,
+	policy: DropPolicy,
+	frame_ready: Condvar,
+	space_ready: Condvar,
+}
+
+impl Shared {
+	fn lock(&self) -> MutexGuard<'_, State> {
+		// the state is kept consistent even if a thread panics while holding the lock
+		self.state.lock().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Puts the frame into the buffer of the `stream`, returns `false` if the capture must stop
+	fn push(&self, stream: usize, frame: Frame) -> bool {
+		let mut state = self.lock();
+		if self.policy == DropPolicy::Block {
+			while !state.stop && state.streams[stream].frames.len() >= self.capacity {
+				state = self.space_ready.wait(state).unwrap_or_else(PoisonError::into_inner);
+			}
+		}
+		if state.stop {
+			return false;
+		}
+		let buf = &mut state.streams[stream];
+		buf.captured += 1;
+		if buf.frames.len() >= self.capacity {
+			buf.dropped += 1;
+			match self.policy {
+				DropPolicy::DropOldest => {
+					buf.frames.pop_front();
+				}
+				DropPolicy::DropNewest => return true,
+				DropPolicy::Block => unreachable!("Buffer must have space after waiting"),
+			}
+		}
+		buf.frames.push_back(frame);
+		self.frame_ready.notify_all();
+		true
+	}
+
+	#[cfg(ocvrs_opencv_4_5_2)]
+	fn is_stopped(&self) -> bool {
+		self.lock().stop
+	}
+
+	fn finish(&self, res: Result<()>) {
+		let mut state = self.lock();
+		state.error = res.err();
+		state.finished = true;
+		self.frame_ready.notify_all();
+	}
+}
+
+/// Captures the frames from one or more [VideoCapture]s on a background thread
+///
+/// Every stream has a buffer of at most `capacity` frames, when the consumer is slower than the camera the [DropPolicy] decides
+/// which frames are lost. Use [ThreadedCapture::latest] to always process the most recent frame with the minimal latency or
+/// [ThreadedCapture::recv_timeout] to receive the buffered frames in order.
+///
+/// The capture thread is stopped when the `ThreadedCapture` is dropped.
+///
+/// ## Example
+/// ```no_run
+/// use std::time::Duration;
+/// use opencv::videoio::{self, DropPolicy, ThreadedCapture, VideoCapture};
+///
+/// let cap = VideoCapture::new(0, videoio::CAP_ANY)?;
+/// let threaded = ThreadedCapture::new(cap, 1, DropPolicy::DropOldest)?;
+/// while let Some(frame) = threaded.recv_timeout(Duration::from_secs(1))? {
+///     // slow processing doesn't make the next frame stale
+///     println!("frame {}, dropped so far: {}", frame.index, threaded.stats(0).dropped);
+/// }
+/// # Ok::<_, opencv::Error>(())
+/// ```
+pub struct ThreadedCapture {
+	shared: Arc<Shared>,
+	thread: Option<JoinHandle<()>>,
+}
+
+impl ThreadedCapture {
+	/// Polling interval for `VideoCapture::wait_any()` that defines how fast the capture thread reacts to the stop request
+	#[cfg(ocvrs_opencv_4_5_2)]
+	const WAIT_ANY_TIMEOUT: Duration = Duration::from_millis(100);
+
+	/// Starts capturing from `cap` on a background thread keeping at most `capacity` frames in the buffer
+	pub fn new(cap: VideoCapture, capacity: usize, policy: DropPolicy) -> Result<Self> {
+		let mut cap = cap;
+		Self::spawn(1, capacity, policy, move |shared| {
+			for frame in cap.frames() {
+				if !shared.push(0, frame?) {
+					break;
+				}
+			}
+			Ok(())
+		})
+	}
+
+	/// Starts capturing from multiple captures on a single background thread using `VideoCapture::wait_any()`
+	///
+	/// Stream indices used in the other methods correspond to the order of `caps`. The captures must be supported by
+	/// `wait_any()`, currently only V4L cameras are. With [DropPolicy::Block] a full buffer of one stream blocks all of them.
+	#[cfg(ocvrs_opencv_4_5_2)]
+	pub fn new_multi(caps: Vec<VideoCapture>, capacity: usize, policy: DropPolicy) -> Result<Self> {
+		let stream_count = caps.len();
+		if stream_count == 0 {
+			return Err(Error::new(core::StsBadArg, "At least one capture is required"));
+		}
+		Self::spawn(stream_count, capacity, policy, move |shared| {
+			let streams = caps.into_iter().collect::<Vector<VideoCapture>>();
+			let mut ready = Vector::<i32>::new();
+			let mut indices = vec![0; stream_count];
+			let timeout_ns = Self::WAIT_ANY_TIMEOUT.as_nanos() as i64;
+			loop {
+				if shared.is_stopped() {
+					return Ok(());
+				}
+				if streams.iter().all(|cap| !cap.is_opened().unwrap_or(false)) {
+					return Ok(());
+				}
+				if !VideoCapture::wait_any(&streams, &mut ready, timeout_ns)? {
+					continue;
+				}
+				for stream in ready.iter() {
+					let stream = stream as usize;
+					let mut cap = streams.get(stream)?;
+					let mut mat = Mat::default();
+					if !cap.retrieve(&mut mat, 0)? || mat.empty() {
+						continue;
+					}
+					let frame = Frame {
+						mat,
+						index: indices[stream],
+						timestamp: msec_to_duration(cap.get(videoio::CAP_PROP_POS_MSEC)?),
+						hw_timestamp: None,
+					};
+					indices[stream] += 1;
+					if !shared.push(stream, frame) {
+						return Ok(());
+					}
+				}
+			}
+		})
+	}
+
+	fn spawn(
+		stream_count: usize,
+		capacity: usize,
+		policy: DropPolicy,
+		run: impl FnOnce(&Shared) -> Result<()> + Send + 'static,
+	) -> Result<Self> {
+		if capacity == 0 {
+			return Err(Error::new(core::StsBadArg, "Buffer capacity must be at least 1"));
+		}
+		let shared = Arc::new(Shared {
+			state: Mutex::new(State {
+				streams: (0..stream_count).map(|_| StreamBuffer::default()).collect(),
+				error: None,
+				finished: false,
+				stop: false,
+			}),
+			capacity,
+			policy,
+			frame_ready: Condvar::new(),
+			space_ready: Condvar::new(),
+		});
+		let thread = thread::Builder::new()
+			.name("opencv-threaded-capture".to_string())
+			.spawn({
+				let shared = Arc::clone(&shared);
+				move || shared.finish(run(&shared))
+			})
+			.map_err(|e| Error::new(core::StsError, format!("Can't spawn capture thread: {}", e)))?;
+		Ok(Self {
+			shared,
+			thread: Some(thread),
+		})
+	}
+
+	/// Number of streams being captured
+	pub fn streams(&self) -> usize {
+		self.shared.lock().streams.len()
+	}
+
+	/// Returns `true` when the capture thread has stopped, either at the end of all streams or because of an error
+	///
+	/// There still may be buffered frames to receive.
+	pub fn is_finished(&self) -> bool {
+		self.shared.lock().finished
+	}
+
+	/// Frame counters of the specified stream
+	pub fn stats(&self, stream: usize) -> CaptureStats {
+		let state = self.shared.lock();
+		state
+			.streams
+			.get(stream)
+			.map_or_else(CaptureStats::default, |buf| CaptureStats {
+				captured: buf.captured,
+				dropped: buf.dropped,
+				buffered: buf.frames.len(),
+			})
+	}
+
+	/// Same as [ThreadedCapture::latest_from] for the first stream
+	pub fn latest(&self) -> Option<Frame> {
+		self.latest_from(0)
+	}
+
+	/// Takes the most recent frame of the stream without waiting, the older buffered frames are dropped
+	pub fn latest_from(&self, stream: usize) -> Option<Frame> {
+		let mut state = self.shared.lock();
+		let buf = state.streams.get_mut(stream)?;
+		let latest = buf.frames.pop_back();
+		buf.dropped += buf.frames.len() as u64;
+		buf.frames.clear();
+		self.shared.space_ready.notify_all();
+		latest
+	}
+
+	/// Same as [ThreadedCapture::recv_timeout_from] for the first stream
+	pub fn recv_timeout(&self, timeout: Duration) -> Result<Option<Frame>> {
+		self.recv_timeout_from(0, timeout)
+	}
+
+	/// Receives the oldest buffered frame of the stream waiting for at most `timeout` for it to arrive
+	///
+	/// Returns `Ok(None)` on timeout or when the capture has finished and there are no more buffered frames, use
+	/// [ThreadedCapture::is_finished] to distinguish between the two. The error that stopped the capture thread is returned once
+	/// after all the frames captured before it have been received.
+	pub fn recv_timeout_from(&self, stream: usize, timeout: Duration) -> Result<Option<Frame>> {
+		// a timeout too large to be represented as an Instant means waiting indefinitely
+		let deadline = Instant::now().checked_add(timeout);
+		let mut state = self.shared.lock();
+		if stream >= state.streams.len() {
+			return Err(Error::new(
+				core::StsOutOfRange,
+				format!("Stream index: {} is out of bounds: {}", stream, state.streams.len()),
+			));
+		}
+		loop {
+			if let Some(frame) = state.streams[stream].frames.pop_front() {
+				self.shared.space_ready.notify_all();
+				return Ok(Some(frame));
+			}
+			if state.finished {
+				return state.error.take().map_or(Ok(None), Err);
+			}
+			state = if let Some(deadline) = deadline {
+				let now = Instant::now();
+				if now >= deadline {
+					return Ok(None);
+				}
+				self
+					.shared
+					.frame_ready
+					.wait_timeout(state, deadline - now)
+					.unwrap_or_else(PoisonError::into_inner)
+					.0
+			} else {
+				self.shared.frame_ready.wait(state).unwrap_or_else(PoisonError::into_inner)
+			};
+		}
+	}
+}
+
+impl Drop for ThreadedCapture {
+	fn drop(&mut self) {
+		self.shared.lock().stop = true;
+		self.shared.space_ready.notify_all();
+		if let Some(thread) = self.thread.take() {
+			// capture thread doesn't panic unless OpenCV does, nothing to do about it here
+			let _ = thread.join();
+		}
+	}
+}
+
+impl fmt::Debug for ThreadedCapture {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let state = self.shared.lock();
+		f.debug_struct("ThreadedCapture")
+			.field("streams", &state.streams.len())
+			.field("capacity", &self.shared.capacity)
+			.field("policy", &self.shared.policy)
+			.field("finished", &state.finished)
+			.finish()
+	}
+}
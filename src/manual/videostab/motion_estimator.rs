@@ -0,0 +1,132 @@
+use std::{ffi::c_void, mem::ManuallyDrop, ptr};
+
+use crate::{
+	core::{Mat, Ptr, _InputArray},
+	sys,
+	traits::Boxed,
+	videostab::{ImageMotionEstimatorBase, MotionEstimatorBase, MotionModel},
+	Result,
+};
+
+/// Rust implementation of the `videostab::MotionEstimatorBase` that estimates the global motion between two point clouds
+///
+/// Use [Ptr::from_rust](crate::core::Ptr::<dyn MotionEstimatorBase>::from_rust) to pass it to `KeypointBasedMotionEstimator`.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/d5/d7a/classcv_1_1videostab_1_1MotionEstimatorBase.html)
+pub trait MotionEstimatorBaseImpl: Send + Sync {
+	/// Estimates the global motion between the matched `points0` and `points1` according to the current motion `model`
+	///
+	/// Returns the 3x3 motion matrix or `None` if the estimation failed.
+	fn estimate(&mut self, points0: &_InputArray, points1: &_InputArray, model: MotionModel) -> Result<Option<Mat>>;
+}
+
+/// Rust implementation of the `videostab::ImageMotionEstimatorBase` that estimates the global motion between two frames
+///
+/// Use [Ptr::from_rust](crate::core::Ptr::<dyn ImageMotionEstimatorBase>::from_rust) to pass it to a stabilizer.
+///
+/// [docs.opencv.org](https://docs.opencv.org/4.x/d6/d6f/classcv_1_1videostab_1_1ImageMotionEstimatorBase.html)
+pub trait ImageMotionEstimatorBaseImpl: Send + Sync {
+	/// Estimates the global motion between `frame0` and `frame1` according to the current motion `model`
+	///
+	/// Returns the 3x3 motion matrix or `None` if the estimation failed.
+	fn estimate(&mut self, frame0: &Mat, frame1: &Mat, model: MotionModel) -> Result<Option<Mat>>;
+}
+
+#[repr(C)]
+struct Vtable {
+	drop: unsafe extern "C" fn(*mut c_void),
+	estimate: unsafe extern "C" fn(*mut c_void, *const c_void, *const c_void, MotionModel, *mut sys::Result<*mut c_void>),
+}
+
+static MOTION_VTABLE: Vtable = Vtable {
+	drop: sys::drop_instance::<dyn MotionEstimatorBaseImpl>,
+	estimate: motion_vtable_estimate,
+};
+
+static IMAGE_MOTION_VTABLE: Vtable = Vtable {
+	drop: sys::drop_instance::<dyn ImageMotionEstimatorBaseImpl>,
+	estimate: image_motion_vtable_estimate,
+};
+
+/// The ownership of the resulting `Mat` is passed to the C++ side, null means that the estimation failed
+fn motion_into_raw(motion: Result<Option<Mat>>) -> Result<*mut c_void> {
+	motion.map(|motion| motion.map_or(ptr::null_mut(), Mat::into_raw))
+}
+
+unsafe extern "C" fn motion_vtable_estimate(
+	instance: *mut c_void,
+	points0: *const c_void,
+	points1: *const c_void,
+	model: MotionModel,
+	ocvrs_return: *mut sys::Result<*mut c_void>,
+) {
+	let instance = sys::instance_mut::<dyn MotionEstimatorBaseImpl>(instance);
+	// arrays are owned by the C++ caller
+	let points0 = ManuallyDrop::new(_InputArray::from_raw(points0 as *mut c_void));
+	let points1 = ManuallyDrop::new(_InputArray::from_raw(points1 as *mut c_void));
+	ocvrs_return.write(sys::Result::from_callback(|| {
+		motion_into_raw(instance.estimate(&points0, &points1, model))
+	}));
+}
+
+unsafe extern "C" fn image_motion_vtable_estimate(
+	instance: *mut c_void,
+	frame0: *const c_void,
+	frame1: *const c_void,
+	model: MotionModel,
+	ocvrs_return: *mut sys::Result<*mut c_void>,
+) {
+	let instance = sys::instance_mut::<dyn ImageMotionEstimatorBaseImpl>(instance);
+	// frames are owned by the C++ caller
+	let frame0 = ManuallyDrop::new(Mat::from_raw(frame0 as *mut c_void));
+	let frame1 = ManuallyDrop::new(Mat::from_raw(frame1 as *mut c_void));
+	ocvrs_return.write(sys::Result::from_callback(|| {
+		motion_into_raw(instance.estimate(&frame0, &frame1, model))
+	}));
+}
+
+impl Ptr<dyn MotionEstimatorBase> {
+	/// Wraps the Rust implementation of the estimator into the C++ `videostab::MotionEstimatorBase` object
+	///
+	/// `model` is the initial motion model, it can be changed later with `set_motion_model()`.
+	pub fn from_rust(estimator: impl MotionEstimatorBaseImpl + 'static, model: MotionModel) -> Result<Self> {
+		extern "C" {
+			fn cv_manual_PtrOfMotionEstimatorBase_from_rust(
+				instance: *mut c_void,
+				model: MotionModel,
+				vtable: *const Vtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		let estimator: Box<dyn MotionEstimatorBaseImpl> = Box::new(estimator);
+		unsafe {
+			sys::into_extern(estimator, |instance, ocvrs_return| {
+				cv_manual_PtrOfMotionEstimatorBase_from_rust(instance, model, &MOTION_VTABLE, ocvrs_return)
+			})
+		}
+		.map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
+
+impl Ptr<dyn ImageMotionEstimatorBase> {
+	/// Wraps the Rust implementation of the estimator into the C++ `videostab::ImageMotionEstimatorBase` object
+	///
+	/// `model` is the initial motion model, it can be changed later with `set_motion_model()`.
+	pub fn from_rust(estimator: impl ImageMotionEstimatorBaseImpl + 'static, model: MotionModel) -> Result<Self> {
+		extern "C" {
+			fn cv_manual_PtrOfImageMotionEstimatorBase_from_rust(
+				instance: *mut c_void,
+				model: MotionModel,
+				vtable: *const Vtable,
+				ocvrs_return: *mut sys::Result<*mut c_void>,
+			);
+		}
+		let estimator: Box<dyn ImageMotionEstimatorBaseImpl> = Box::new(estimator);
+		unsafe {
+			sys::into_extern(estimator, |instance, ocvrs_return| {
+				cv_manual_PtrOfImageMotionEstimatorBase_from_rust(instance, model, &IMAGE_MOTION_VTABLE, ocvrs_return)
+			})
+		}
+		.map(|ptr| unsafe { Self::from_raw(ptr) })
+	}
+}
@@ -5940,3 +5940,4 @@ impl UsacParams {
 	}
 	
 }
+pub use crate::manual::calib3d::*;
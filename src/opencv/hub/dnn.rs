@@ -439,11 +439,9 @@ pub fn read_net_from_caffe(prototxt: &str, caffe_model: &str) -> Result<crate::d
 /// * buffer_model: NULL
 /// * len_model: 0
 #[inline]
-pub fn read_net_from_caffe_str(buffer_proto: &str, len_proto: size_t, buffer_model: &str, len_model: size_t) -> Result<crate::dnn::Net> {
-	extern_container_arg!(buffer_proto);
-	extern_container_arg!(buffer_model);
+pub fn read_net_from_caffe_slice(buffer_proto: &[u8], buffer_model: &[u8]) -> Result<crate::dnn::Net> {
 	return_send!(via ocvrs_return);
-	unsafe { sys::cv_dnn_readNetFromCaffe_const_charX_size_t_const_charX_size_t(buffer_proto.opencv_as_extern(), len_proto, buffer_model.opencv_as_extern(), len_model, ocvrs_return.as_mut_ptr()) };
+	unsafe { sys::cv_dnn_readNetFromCaffe_const_charX_size_t_const_charX_size_t(buffer_proto.as_ptr(), buffer_proto.len() as _, buffer_model.as_ptr(), buffer_model.len() as _, ocvrs_return.as_mut_ptr()) };
 	return_receive!(unsafe ocvrs_return => ret);
 	let ret = ret.into_result()?;
 	let ret = unsafe { crate::dnn::Net::opencv_from_extern(ret) };
@@ -505,11 +503,9 @@ pub fn read_net_from_darknet(cfg_file: &str, darknet_model: &str) -> Result<crat
 /// * buffer_model: NULL
 /// * len_model: 0
 #[inline]
-pub fn read_net_from_darknet_str(buffer_cfg: &str, len_cfg: size_t, buffer_model: &str, len_model: size_t) -> Result<crate::dnn::Net> {
-	extern_container_arg!(buffer_cfg);
-	extern_container_arg!(buffer_model);
+pub fn read_net_from_darknet_slice(buffer_cfg: &[u8], buffer_model: &[u8]) -> Result<crate::dnn::Net> {
 	return_send!(via ocvrs_return);
-	unsafe { sys::cv_dnn_readNetFromDarknet_const_charX_size_t_const_charX_size_t(buffer_cfg.opencv_as_extern(), len_cfg, buffer_model.opencv_as_extern(), len_model, ocvrs_return.as_mut_ptr()) };
+	unsafe { sys::cv_dnn_readNetFromDarknet_const_charX_size_t_const_charX_size_t(buffer_cfg.as_ptr(), buffer_cfg.len() as _, buffer_model.as_ptr(), buffer_model.len() as _, ocvrs_return.as_mut_ptr()) };
 	return_receive!(unsafe ocvrs_return => ret);
 	let ret = ret.into_result()?;
 	let ret = unsafe { crate::dnn::Net::opencv_from_extern(ret) };
@@ -566,9 +562,9 @@ pub fn read_net_from_model_optimizer(xml: &str, bin: &str) -> Result<crate::dnn:
 /// Networks imported from Intel's Model Optimizer are launched in Intel's Inference Engine
 /// backend.
 #[inline]
-pub fn read_net_from_model_optimizer_2(buffer_model_config_ptr: &u8, buffer_model_config_size: size_t, buffer_weights_ptr: &u8, buffer_weights_size: size_t) -> Result<crate::dnn::Net> {
+pub fn read_net_from_model_optimizer_2(buffer_model_config_ptr: &[u8], buffer_weights_ptr: &[u8]) -> Result<crate::dnn::Net> {
 	return_send!(via ocvrs_return);
-	unsafe { sys::cv_dnn_readNetFromModelOptimizer_const_unsigned_charX_size_t_const_unsigned_charX_size_t(buffer_model_config_ptr, buffer_model_config_size, buffer_weights_ptr, buffer_weights_size, ocvrs_return.as_mut_ptr()) };
+	unsafe { sys::cv_dnn_readNetFromModelOptimizer_const_unsigned_charX_size_t_const_unsigned_charX_size_t(buffer_model_config_ptr.as_ptr(), buffer_model_config_ptr.len() as _, buffer_weights_ptr.as_ptr(), buffer_weights_ptr.len() as _, ocvrs_return.as_mut_ptr()) };
 	return_receive!(unsafe ocvrs_return => ret);
 	let ret = ret.into_result()?;
 	let ret = unsafe { crate::dnn::Net::opencv_from_extern(ret) };
@@ -618,10 +614,9 @@ pub fn read_net_from_onnx(onnx_file: &str) -> Result<crate::dnn::Net> {
 /// Network object that ready to do forward, throw an exception
 ///       in failure cases.
 #[inline]
-pub fn read_net_from_onnx_str(buffer: &str, size_buffer: size_t) -> Result<crate::dnn::Net> {
-	extern_container_arg!(buffer);
+pub fn read_net_from_onnx_slice(buffer: &[u8]) -> Result<crate::dnn::Net> {
 	return_send!(via ocvrs_return);
-	unsafe { sys::cv_dnn_readNetFromONNX_const_charX_size_t(buffer.opencv_as_extern(), size_buffer, ocvrs_return.as_mut_ptr()) };
+	unsafe { sys::cv_dnn_readNetFromONNX_const_charX_size_t(buffer.as_ptr(), buffer.len() as _, ocvrs_return.as_mut_ptr()) };
 	return_receive!(unsafe ocvrs_return => ret);
 	let ret = ret.into_result()?;
 	let ret = unsafe { crate::dnn::Net::opencv_from_extern(ret) };
@@ -681,11 +676,9 @@ pub fn read_net_from_tensorflow(model: &str, config: &str) -> Result<crate::dnn:
 /// * buffer_config: NULL
 /// * len_config: 0
 #[inline]
-pub fn read_net_from_tensorflow_str(buffer_model: &str, len_model: size_t, buffer_config: &str, len_config: size_t) -> Result<crate::dnn::Net> {
-	extern_container_arg!(buffer_model);
-	extern_container_arg!(buffer_config);
+pub fn read_net_from_tensorflow_slice(buffer_model: &[u8], buffer_config: &[u8]) -> Result<crate::dnn::Net> {
 	return_send!(via ocvrs_return);
-	unsafe { sys::cv_dnn_readNetFromTensorflow_const_charX_size_t_const_charX_size_t(buffer_model.opencv_as_extern(), len_model, buffer_config.opencv_as_extern(), len_config, ocvrs_return.as_mut_ptr()) };
+	unsafe { sys::cv_dnn_readNetFromTensorflow_const_charX_size_t_const_charX_size_t(buffer_model.as_ptr(), buffer_model.len() as _, buffer_config.as_ptr(), buffer_config.len() as _, ocvrs_return.as_mut_ptr()) };
 	return_receive!(unsafe ocvrs_return => ret);
 	let ret = ret.into_result()?;
 	let ret = unsafe { crate::dnn::Net::opencv_from_extern(ret) };
@@ -8878,9 +8871,9 @@ impl Net {
 	/// ## Returns
 	/// Net object.
 	#[inline]
-	pub fn read_from_model_optimizer_2(buffer_model_config_ptr: &u8, buffer_model_config_size: size_t, buffer_weights_ptr: &u8, buffer_weights_size: size_t) -> Result<crate::dnn::Net> {
+	pub fn read_from_model_optimizer_2(buffer_model_config_ptr: &[u8], buffer_weights_ptr: &[u8]) -> Result<crate::dnn::Net> {
 		return_send!(via ocvrs_return);
-		unsafe { sys::cv_dnn_Net_readFromModelOptimizer_const_unsigned_charX_size_t_const_unsigned_charX_size_t(buffer_model_config_ptr, buffer_model_config_size, buffer_weights_ptr, buffer_weights_size, ocvrs_return.as_mut_ptr()) };
+		unsafe { sys::cv_dnn_Net_readFromModelOptimizer_const_unsigned_charX_size_t_const_unsigned_charX_size_t(buffer_model_config_ptr.as_ptr(), buffer_model_config_ptr.len() as _, buffer_weights_ptr.as_ptr(), buffer_weights_ptr.len() as _, ocvrs_return.as_mut_ptr()) };
 		return_receive!(unsafe ocvrs_return => ret);
 		let ret = ret.into_result()?;
 		let ret = unsafe { crate::dnn::Net::opencv_from_extern(ret) };
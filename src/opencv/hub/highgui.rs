@@ -1512,3 +1512,4 @@ impl crate::highgui::QtFontTrait for QtFont {
 
 impl QtFont {
 }
+pub use crate::manual::highgui::*;
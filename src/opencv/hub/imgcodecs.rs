@@ -571,3 +571,4 @@ pub fn imwritemulti(filename: &str, img: &dyn core::ToInputArray, params: &core:
 	let ret = ret.into_result()?;
 	Ok(ret)
 }
+pub use crate::manual::imgcodecs::*;
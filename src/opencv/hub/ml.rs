@@ -4415,4 +4415,5 @@ impl dyn TrainData + '_ {
 		Ok(ret)
 	}
 	
-}
\ No newline at end of file
+}
+pub use crate::manual::ml::*;
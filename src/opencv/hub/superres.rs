@@ -1103,3 +1103,4 @@ pub trait Superres_SuperResolution: core::AlgorithmTrait + crate::superres::Supe
 	}
 	
 }
+pub use crate::manual::superres::*;
@@ -2082,4 +2082,5 @@ impl dyn TextDetectorCNN + '_ {
 		Ok(ret)
 	}
 	
-}
\ No newline at end of file
+}
+pub use crate::manual::text::*;
@@ -1960,3 +1960,4 @@ impl VideoWriter {
 	}
 	
 }
+pub use crate::manual::videoio::*;
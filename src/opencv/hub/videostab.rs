@@ -4143,3 +4143,4 @@ pub trait WobbleSuppressorBase: crate::videostab::WobbleSuppressorBaseConst {
 	}
 	
 }
+pub use crate::manual::videostab::*;
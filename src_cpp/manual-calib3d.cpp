@@ -0,0 +1,27 @@
+#include "ocvrs_rust_impl.hpp"
+#include <opencv2/calib3d.hpp>
+
+template struct Result<void*>;
+template struct Result<bool>;
+
+struct OcvrsLMSolverCallbackVtable {
+	void (*drop)(void* instance);
+	void (*compute)(const void* instance, const cv::_InputArray* param, const cv::_OutputArray* err, const cv::_OutputArray* J, Result<bool>* ocvrs_return);
+};
+
+class OcvrsLMSolverCallback : public cv::LMSolver::Callback, OcvrsRustImpl<OcvrsLMSolverCallbackVtable> {
+public:
+	OcvrsLMSolverCallback(void* instance, const OcvrsLMSolverCallbackVtable* vtable) : OcvrsRustImpl(instance, vtable) {}
+
+	bool compute(cv::InputArray param, cv::OutputArray err, cv::OutputArray J) const override {
+		Result<bool> ret;
+		vtable.compute(instance, &param, &err, &J, &ret);
+		return ocvrs_callback_result(ret);
+	}
+};
+
+extern "C" {
+	void cv_manual_PtrOfLMSolver_Callback_from_rust(void* instance, const OcvrsLMSolverCallbackVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::LMSolver::Callback, OcvrsLMSolverCallback>("LMSolver::Callback", ocvrs_return, instance, vtable);
+	}
+}
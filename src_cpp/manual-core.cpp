@@ -1,8 +1,11 @@
 #include "core.hpp"
+#include "ocvrs_rust_impl.hpp"
 
 template struct Result<void*>;
 template struct Result<cv::Size>;
 template struct Result<const unsigned char*>;
+template struct Result<int>;
+template struct Result<double>;
 
 template<typename T> inline void ocvrs_input_array(const T* instance, Result<void*>* ocvrs_return) {
 	try {
@@ -39,6 +42,57 @@ template<typename T> inline void ocvrs_input_output_array(T* instance, Result<vo
 	ocvrs_ioa(base##s) \
 	ocvrs_ioa(base##w)
 
+typedef void (*ocvrs_parallel_loop_body)(const void* userdata, int start, int end);
+
+class OcvrsParallelLoopBody : public cv::ParallelLoopBody {
+	ocvrs_parallel_loop_body body;
+	const void* userdata;
+
+public:
+	OcvrsParallelLoopBody(ocvrs_parallel_loop_body body, const void* userdata) : body(body), userdata(userdata) {}
+
+	void operator()(const cv::Range& range) const override {
+		body(userdata, range.start, range.end);
+	}
+};
+
+struct OcvrsMinProblemSolverFunctionVtable {
+	void (*drop)(void* instance);
+	void (*get_dims)(const void* instance, Result<int>* ocvrs_return);
+	void (*get_gradient_eps)(const void* instance, Result<double>* ocvrs_return);
+	void (*calc)(const void* instance, const double* x, int dims, Result<double>* ocvrs_return);
+	void (*get_gradient)(void* instance, const double* x, double* grad, int dims, Result_void* ocvrs_return);
+};
+
+class OcvrsMinProblemSolverFunction : public cv::MinProblemSolver::Function, OcvrsRustImpl<OcvrsMinProblemSolverFunctionVtable> {
+public:
+	OcvrsMinProblemSolverFunction(void* instance, const OcvrsMinProblemSolverFunctionVtable* vtable) : OcvrsRustImpl(instance, vtable) {}
+
+	int getDims() const override {
+		Result<int> ret;
+		vtable.get_dims(instance, &ret);
+		return ocvrs_callback_result(ret);
+	}
+
+	double getGradientEps() const override {
+		Result<double> ret;
+		vtable.get_gradient_eps(instance, &ret);
+		return ocvrs_callback_result(ret);
+	}
+
+	double calc(const double* x) const override {
+		Result<double> ret;
+		vtable.calc(instance, x, getDims(), &ret);
+		return ocvrs_callback_result(ret);
+	}
+
+	void getGradient(const double* x, double* grad) override {
+		Result_void ret;
+		vtable.get_gradient(instance, x, grad, getDims(), &ret);
+		ocvrs_callback_result(ret);
+	}
+};
+
 extern "C" {
 	void cv_manual_Mat_size(const cv::Mat* instance, Result<cv::Size>* ocvrs_return) {
 		try {
@@ -63,6 +117,17 @@ extern "C" {
 		} OCVRS_CATCH(Result<cv::Size>)
 	}
 
+	void cv_manual_parallel_for(int start, int end, ocvrs_parallel_loop_body body, const void* userdata, double nstripes, Result_void* ocvrs_return) {
+		try {
+			cv::parallel_for_(cv::Range(start, end), OcvrsParallelLoopBody(body, userdata), nstripes);
+			Ok(ocvrs_return);
+		} OCVRS_CATCH(Result_void)
+	}
+
+	void cv_manual_PtrOfMinProblemSolver_Function_from_rust(void* instance, const OcvrsMinProblemSolverFunctionVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::MinProblemSolver::Function, OcvrsMinProblemSolverFunction>("MinProblemSolver::Function", ocvrs_return, instance, vtable);
+	}
+
 	int cv_manual_MatSize_dims(const cv::MatSize* instance) {
 		return *(instance->p - 1);
 	}
@@ -1,6 +1,9 @@
 #include "dnn.hpp"
+#include <map>
+#include <mutex>
 
 template struct Result<void*>;
+template struct Result<bool>;
 
 extern "C" {
 	void cv_dnn_LayerParams_LayerParams(Result<void*>* ocvrs_return) {
@@ -9,3 +12,126 @@ extern "C" {
 		} OCVRS_CATCH(Result<void*>)
 	}
 }
+
+struct OcvrsLayerVtable {
+	void (*create)(cv::dnn::LayerParams* params, Result<void*>* ocvrs_return);
+	void (*drop)(void* instance);
+	void (*get_memory_shapes)(const void* instance, const std::vector<cv::dnn::MatShape>* inputs, int required_outputs, std::vector<cv::dnn::MatShape>* outputs, std::vector<cv::dnn::MatShape>* internals, Result<bool>* ocvrs_return);
+	void (*finalize)(void* instance, cv::Mat* const* inputs, size_t inputs_len, cv::Mat** outputs, size_t outputs_len, Result_void* ocvrs_return);
+	void (*forward)(void* instance, cv::Mat* const* inputs, size_t inputs_len, cv::Mat** outputs, size_t outputs_len, Result_void* ocvrs_return);
+};
+
+typedef void (*OcvrsLayerMatsFn)(void* instance, cv::Mat* const* inputs, size_t inputs_len, cv::Mat** outputs, size_t outputs_len, Result_void* ocvrs_return);
+
+class OcvrsLayer : public cv::dnn::Layer {
+	void* instance;
+	OcvrsLayerVtable vtable;
+
+	// outputs are passed to Rust as separate Mat headers that it owns during the call, if Rust replaced any of them
+	// the data is copied back into the blobs allocated by the Net
+	void callWithMats(OcvrsLayerMatsFn fn, cv::InputArrayOfArrays inputs_arr, cv::OutputArrayOfArrays outputs_arr) {
+		std::vector<cv::Mat> inputs, outputs;
+		inputs_arr.getMatVector(inputs);
+		outputs_arr.getMatVector(outputs);
+		std::vector<cv::Mat*> inputs_ptrs(inputs.size());
+		for (size_t i = 0; i < inputs.size(); ++i) {
+			inputs_ptrs[i] = &inputs[i];
+		}
+		std::vector<cv::Mat*> outputs_ptrs(outputs.size());
+		for (size_t i = 0; i < outputs.size(); ++i) {
+			outputs_ptrs[i] = new cv::Mat(outputs[i]);
+		}
+		Result_void ret;
+		fn(instance, inputs_ptrs.data(), inputs_ptrs.size(), outputs_ptrs.data(), outputs_ptrs.size(), &ret);
+		bool shape_mismatch = false;
+		for (size_t i = 0; i < outputs.size(); ++i) {
+			cv::Mat* out = outputs_ptrs[i];
+			if (out->data != outputs[i].data) {
+				if (out->size == outputs[i].size && out->type() == outputs[i].type()) {
+					out->copyTo(outputs[i]);
+				} else {
+					shape_mismatch = true;
+				}
+			}
+			delete out;
+		}
+		ocvrs_callback_result(ret);
+		if (shape_mismatch) {
+			CV_Error(cv::Error::StsUnmatchedSizes, "Rust layer replaced the output Mat with the one of different shape or type");
+		}
+	}
+
+public:
+	OcvrsLayer(const cv::dnn::LayerParams& params, void* instance, const OcvrsLayerVtable& vtable) : cv::dnn::Layer(params), instance(instance), vtable(vtable) {}
+
+	~OcvrsLayer() {
+		vtable.drop(instance);
+	}
+
+	bool getMemoryShapes(const std::vector<cv::dnn::MatShape>& inputs, const int requiredOutputs, std::vector<cv::dnn::MatShape>& outputs, std::vector<cv::dnn::MatShape>& internals) const override {
+		Result<bool> ret;
+		vtable.get_memory_shapes(instance, &inputs, requiredOutputs, &outputs, &internals, &ret);
+		return ocvrs_callback_result(ret);
+	}
+
+	void finalize(cv::InputArrayOfArrays inputs_arr, cv::OutputArrayOfArrays outputs_arr) override {
+		callWithMats(vtable.finalize, inputs_arr, outputs_arr);
+	}
+
+	void forward(cv::InputArrayOfArrays inputs_arr, cv::OutputArrayOfArrays outputs_arr, cv::OutputArrayOfArrays internals_arr) override {
+		if (inputs_arr.depth() == CV_16S) {
+			forward_fallback(inputs_arr, outputs_arr, internals_arr);
+			return;
+		}
+		callWithMats(vtable.forward, inputs_arr, outputs_arr);
+	}
+};
+
+// LayerFactory constructors don't receive any user data, so the Rust implementation is looked up by the layer type
+static std::mutex ocvrs_layers_mutex;
+static std::map<std::string, OcvrsLayerVtable> ocvrs_layers;
+
+static cv::Ptr<cv::dnn::Layer> ocvrs_layer_create(cv::dnn::LayerParams& params) {
+	OcvrsLayerVtable vtable;
+	{
+		std::lock_guard<std::mutex> lock(ocvrs_layers_mutex);
+		std::map<std::string, OcvrsLayerVtable>::const_iterator it = ocvrs_layers.find(params.type);
+		if (it == ocvrs_layers.end()) {
+			CV_Error(cv::Error::StsObjectNotFound, "Rust layer implementation for type \"" + params.type + "\" is not registered");
+		}
+		vtable = it->second;
+	}
+	Result<void*> ret;
+	vtable.create(&params, &ret);
+	void* instance = ocvrs_callback_result(ret);
+	try {
+		return cv::makePtr<OcvrsLayer>(params, instance, vtable);
+	} catch (...) {
+		vtable.drop(instance);
+		throw;
+	}
+}
+
+extern "C" {
+	void cv_manual_dnn_registerRustLayer(const char* type, const OcvrsLayerVtable* vtable, Result_void* ocvrs_return) {
+		try {
+			std::lock_guard<std::mutex> lock(ocvrs_layers_mutex);
+			if (ocvrs_layers.count(type)) {
+				CV_Error(cv::Error::StsBadArg, cv::String("Rust layer \"") + type + "\" is already registered");
+			}
+			cv::dnn::LayerFactory::registerLayer(type, ocvrs_layer_create);
+			ocvrs_layers[type] = *vtable;
+			Ok(ocvrs_return);
+		} OCVRS_CATCH(Result_void)
+	}
+
+	void cv_manual_dnn_unregisterRustLayer(const char* type, Result_void* ocvrs_return) {
+		try {
+			std::lock_guard<std::mutex> lock(ocvrs_layers_mutex);
+			if (ocvrs_layers.erase(type)) {
+				cv::dnn::LayerFactory::unregisterLayer(type);
+			}
+			Ok(ocvrs_return);
+		} OCVRS_CATCH(Result_void)
+	}
+}
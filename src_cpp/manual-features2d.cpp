@@ -1,7 +1,45 @@
-#include "ocvrs_common.hpp"
+#include "ocvrs_rust_impl.hpp"
 #include <opencv2/features2d.hpp>
 
 template struct Result<void*>;
+template struct Result<int>;
+
+struct OcvrsFeature2DVtable {
+	void (*drop)(void* instance);
+	void (*detectAndCompute)(void* instance, const cv::_InputArray* image, const cv::_InputArray* mask, std::vector<cv::KeyPoint>* keypoints, const cv::_OutputArray* descriptors, bool useProvidedKeypoints, Result_void* ocvrs_return);
+	void (*descriptorSize)(const void* instance, Result<int>* ocvrs_return);
+	void (*descriptorType)(const void* instance, Result<int>* ocvrs_return);
+	void (*defaultNorm)(const void* instance, Result<int>* ocvrs_return);
+};
+
+class OcvrsFeature2D : public cv::Feature2D, OcvrsRustImpl<OcvrsFeature2DVtable> {
+public:
+	OcvrsFeature2D(void* instance, const OcvrsFeature2DVtable* vtable) : OcvrsRustImpl(instance, vtable) {}
+
+	void detectAndCompute(cv::InputArray image, cv::InputArray mask, std::vector<cv::KeyPoint>& keypoints, cv::OutputArray descriptors, bool useProvidedKeypoints) override {
+		Result_void ret;
+		vtable.detectAndCompute(instance, &image, &mask, &keypoints, &descriptors, useProvidedKeypoints, &ret);
+		ocvrs_callback_result(ret);
+	}
+
+	int descriptorSize() const override {
+		Result<int> ret;
+		vtable.descriptorSize(instance, &ret);
+		return ocvrs_callback_result(ret);
+	}
+
+	int descriptorType() const override {
+		Result<int> ret;
+		vtable.descriptorType(instance, &ret);
+		return ocvrs_callback_result(ret);
+	}
+
+	int defaultNorm() const override {
+		Result<int> ret;
+		vtable.defaultNorm(instance, &ret);
+		return ocvrs_callback_result(ret);
+	}
+};
 
 extern "C" {
 	void cv_ORB_create(Result<void*>* ocvrs_return) {
@@ -9,4 +47,8 @@ extern "C" {
 			return Ok<void*>(new cv::Ptr<cv::ORB>(cv::ORB::create()), ocvrs_return);
 		} OCVRS_CATCH(Result<void*>)
 	}
+
+	void cv_manual_PtrOfFeature2D_from_rust(void* instance, const OcvrsFeature2DVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::Feature2D, OcvrsFeature2D>("Feature2D", ocvrs_return, instance, vtable);
+	}
 }
@@ -0,0 +1,35 @@
+#include "ocvrs_rust_impl.hpp"
+#include <opencv2/superres.hpp>
+#include "ocvrs_frame_source.hpp"
+
+template struct Result<void*>;
+template struct Result<bool>;
+template struct Result<double>;
+
+class OcvrsSuperresFrameSource : public cv::superres::FrameSource, OcvrsRustImpl<OcvrsFrameSourceVtable> {
+public:
+	OcvrsSuperresFrameSource(void* instance, const OcvrsFrameSourceVtable* vtable) : OcvrsRustImpl(instance, vtable) {}
+
+	void reset() override {
+		Result_void ret;
+		vtable.reset(instance, &ret);
+		ocvrs_callback_result(ret);
+	}
+
+	void nextFrame(cv::OutputArray frame) override {
+		cv::Mat buf;
+		Result<bool> ret;
+		vtable.read(instance, &buf, &ret);
+		if (ocvrs_callback_result(ret)) {
+			buf.copyTo(frame);
+		} else {
+			frame.release();
+		}
+	}
+};
+
+extern "C" {
+	void cv_manual_PtrOfSuperres_FrameSource_from_rust(void* instance, const OcvrsFrameSourceVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::superres::FrameSource, OcvrsSuperresFrameSource>("superres::FrameSource", ocvrs_return, instance, vtable);
+	}
+}
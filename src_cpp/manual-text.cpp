@@ -0,0 +1,27 @@
+#include "ocvrs_rust_impl.hpp"
+#include <opencv2/text.hpp>
+
+template struct Result<void*>;
+template struct Result<double>;
+
+struct OcvrsERFilterCallbackVtable {
+	void (*drop)(void* instance);
+	void (*eval)(void* instance, const cv::text::ERStat* stat, Result<double>* ocvrs_return);
+};
+
+class OcvrsERFilterCallback : public cv::text::ERFilter::Callback, OcvrsRustImpl<OcvrsERFilterCallbackVtable> {
+public:
+	OcvrsERFilterCallback(void* instance, const OcvrsERFilterCallbackVtable* vtable) : OcvrsRustImpl(instance, vtable) {}
+
+	double eval(const cv::text::ERStat& stat) override {
+		Result<double> ret;
+		vtable.eval(instance, &stat, &ret);
+		return ocvrs_callback_result(ret);
+	}
+};
+
+extern "C" {
+	void cv_manual_PtrOfERFilter_Callback_from_rust(void* instance, const OcvrsERFilterCallbackVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::text::ERFilter::Callback, OcvrsERFilterCallback>("text::ERFilter::Callback", ocvrs_return, instance, vtable);
+	}
+}
@@ -0,0 +1,91 @@
+#include "videoio.hpp"
+#include "ocvrs_frame_source.hpp"
+
+template struct Result<void*>;
+template struct Result<bool>;
+template struct Result<double>;
+
+class OcvrsVideoCapture : public cv::VideoCapture {
+	void* instance;
+	OcvrsFrameSourceVtable vtable;
+	cv::Mat frame;
+	bool grabbed;
+
+public:
+	OcvrsVideoCapture(void* instance, const OcvrsFrameSourceVtable* vtable) : instance(instance), vtable(*vtable), grabbed(false) {}
+
+	~OcvrsVideoCapture() {
+		release();
+	}
+
+	bool isOpened() const override {
+		return instance != NULL;
+	}
+
+	void release() override {
+		if (instance) {
+			vtable.drop(instance);
+			instance = NULL;
+		}
+		frame.release();
+		grabbed = false;
+	}
+
+	bool grab() override {
+		grabbed = false;
+		if (!instance) {
+			return false;
+		}
+		Result<bool> ret;
+		vtable.read(instance, &frame, &ret);
+		grabbed = ocvrs_callback_result(ret) && !frame.empty();
+		return grabbed;
+	}
+
+	bool retrieve(cv::OutputArray image, int) override {
+		if (!grabbed) {
+			image.release();
+			return false;
+		}
+		frame.copyTo(image);
+		return true;
+	}
+
+	bool read(cv::OutputArray image) override {
+		if (grab()) {
+			return retrieve(image, 0);
+		}
+		image.release();
+		return false;
+	}
+
+	bool set(int propId, double value) override {
+		if (!instance) {
+			return false;
+		}
+		Result<bool> ret;
+		vtable.set(instance, propId, value, &ret);
+		return ocvrs_callback_result(ret);
+	}
+
+	double get(int propId) const override {
+		if (!instance) {
+			return 0.;
+		}
+		Result<double> ret;
+		vtable.get(instance, propId, &ret);
+		return ocvrs_callback_result(ret);
+	}
+};
+
+extern "C" {
+	void cv_manual_VideoCapture_from_source(void* instance, const OcvrsFrameSourceVtable* vtable, Result<void*>* ocvrs_return) {
+		try {
+			// the ownership of instance is only taken when nothing can throw anymore, otherwise it stays with Rust
+			cv::VideoCapture* ret = new OcvrsVideoCapture(instance, vtable);
+			Ok<void*>(ret, ocvrs_return);
+		} catch (...) {
+			Err<Result<void*>>(cv::Error::StsNoMem, "can't allocate VideoCapture", ocvrs_return);
+		}
+	}
+}
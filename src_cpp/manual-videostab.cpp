@@ -0,0 +1,84 @@
+#include "ocvrs_rust_impl.hpp"
+#include <opencv2/videostab.hpp>
+#include "ocvrs_frame_source.hpp"
+
+template struct Result<void*>;
+template struct Result<bool>;
+template struct Result<double>;
+
+class OcvrsVideostabFrameSource : public cv::videostab::IFrameSource, OcvrsRustImpl<OcvrsFrameSourceVtable> {
+public:
+	OcvrsVideostabFrameSource(void* instance, const OcvrsFrameSourceVtable* vtable) : OcvrsRustImpl(instance, vtable) {}
+
+	void reset() override {
+		Result_void ret;
+		vtable.reset(instance, &ret);
+		ocvrs_callback_result(ret);
+	}
+
+	cv::Mat nextFrame() override {
+		cv::Mat frame;
+		Result<bool> ret;
+		vtable.read(instance, &frame, &ret);
+		if (!ocvrs_callback_result(ret)) {
+			frame.release();
+		}
+		return frame;
+	}
+};
+
+struct OcvrsMotionEstimatorVtable {
+	void (*drop)(void* instance);
+	void (*estimate)(void* instance, const void* arg0, const void* arg1, cv::videostab::MotionModel model, Result<void*>* ocvrs_return);
+};
+
+// the Rust side passes the ownership of the estimated cv::Mat or NULL if the estimation failed, in that case the identity is
+// returned just like the OpenCV estimators do
+static cv::Mat ocvrs_estimated_motion(const Result<void*>& ret, bool* ok) {
+	cv::Mat* motion = static_cast<cv::Mat*>(ocvrs_callback_result(ret));
+	if (ok) {
+		*ok = motion != NULL;
+	}
+	if (!motion) {
+		return cv::Mat::eye(3, 3, CV_32F);
+	}
+	cv::Mat out = *motion;
+	delete motion;
+	return out;
+}
+
+class OcvrsMotionEstimator : public cv::videostab::MotionEstimatorBase, OcvrsRustImpl<OcvrsMotionEstimatorVtable> {
+public:
+	OcvrsMotionEstimator(void* instance, cv::videostab::MotionModel model, const OcvrsMotionEstimatorVtable* vtable) : cv::videostab::MotionEstimatorBase(model), OcvrsRustImpl(instance, vtable) {}
+
+	cv::Mat estimate(cv::InputArray points0, cv::InputArray points1, bool* ok) override {
+		Result<void*> ret;
+		vtable.estimate(instance, &points0, &points1, motionModel(), &ret);
+		return ocvrs_estimated_motion(ret, ok);
+	}
+};
+
+class OcvrsImageMotionEstimator : public cv::videostab::ImageMotionEstimatorBase, OcvrsRustImpl<OcvrsMotionEstimatorVtable> {
+public:
+	OcvrsImageMotionEstimator(void* instance, cv::videostab::MotionModel model, const OcvrsMotionEstimatorVtable* vtable) : cv::videostab::ImageMotionEstimatorBase(model), OcvrsRustImpl(instance, vtable) {}
+
+	cv::Mat estimate(const cv::Mat& frame0, const cv::Mat& frame1, bool* ok) override {
+		Result<void*> ret;
+		vtable.estimate(instance, &frame0, &frame1, motionModel(), &ret);
+		return ocvrs_estimated_motion(ret, ok);
+	}
+};
+
+extern "C" {
+	void cv_manual_PtrOfIFrameSource_from_rust(void* instance, const OcvrsFrameSourceVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::videostab::IFrameSource, OcvrsVideostabFrameSource>("videostab::IFrameSource", ocvrs_return, instance, vtable);
+	}
+
+	void cv_manual_PtrOfMotionEstimatorBase_from_rust(void* instance, cv::videostab::MotionModel model, const OcvrsMotionEstimatorVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::videostab::MotionEstimatorBase, OcvrsMotionEstimator>("videostab::MotionEstimatorBase", ocvrs_return, instance, model, vtable);
+	}
+
+	void cv_manual_PtrOfImageMotionEstimatorBase_from_rust(void* instance, cv::videostab::MotionModel model, const OcvrsMotionEstimatorVtable* vtable, Result<void*>* ocvrs_return) {
+		ocvrs_ptr_from_rust<cv::videostab::ImageMotionEstimatorBase, OcvrsImageMotionEstimator>("videostab::ImageMotionEstimatorBase", ocvrs_return, instance, model, vtable);
+	}
+}
@@ -62,4 +62,26 @@ template<typename T> inline void Err(int code, const char* msg, T* ocvrs_return)
 	// it's ok to leave result uninitialized because the Rust implementation only assumes it as init if error_msg is NULL
 }
 
+// defined in src/manual/sys.rs
+extern "C" void ocvrs_drop_c_string(void*);
+
+// Results coming from the Rust implementations of C++ virtual methods carry a C string in error_msg (see Result::from_callback()),
+// it's rethrown as cv::Exception so that the error surfaces back in the Rust code that called into OpenCV
+inline void ocvrs_callback_check(int error_code, void* error_msg) {
+	if (error_msg) {
+		std::string msg(static_cast<const char*>(error_msg));
+		ocvrs_drop_c_string(error_msg);
+		cv::error(error_code, msg, CV_Func, __FILE__, __LINE__);
+	}
+}
+
+template<typename T> inline T ocvrs_callback_result(const Result<T>& res) {
+	ocvrs_callback_check(res.error_code, res.error_msg);
+	return res.result;
+}
+
+inline void ocvrs_callback_result(const Result_void& res) {
+	ocvrs_callback_check(res.error_code, res.error_msg);
+}
+
 #endif
@@ -0,0 +1,15 @@
+#ifndef __OCVRS_FRAME_SOURCE_HPP__
+#define __OCVRS_FRAME_SOURCE_HPP__
+
+#include "ocvrs_common.hpp"
+
+// Function table of the Rust FrameSource, matches videoio::FrameSourceVtable in Rust
+struct OcvrsFrameSourceVtable {
+	void (*drop)(void* instance);
+	void (*read)(void* instance, cv::Mat* frame, Result<bool>* ocvrs_return);
+	void (*get)(const void* instance, int propId, Result<double>* ocvrs_return);
+	void (*set)(void* instance, int propId, double value, Result<bool>* ocvrs_return);
+	void (*reset)(void* instance, Result_void* ocvrs_return);
+};
+
+#endif
@@ -0,0 +1,38 @@
+#ifndef __OCVRS_RUST_IMPL_HPP__
+#define __OCVRS_RUST_IMPL_HPP__
+
+#include "ocvrs_common.hpp"
+
+// Base of the C++ classes that forward the virtual methods to the Rust implementation, owns the Rust instance created by
+// sys::into_extern() and frees it through the drop entry of the vtable when destroyed
+template<typename Vtable> class OcvrsRustImpl {
+protected:
+	void* instance;
+	Vtable vtable;
+
+	OcvrsRustImpl(void* instance, const Vtable* vtable) : instance(instance), vtable(*vtable) {}
+
+	~OcvrsRustImpl() {
+		vtable.drop(instance);
+	}
+
+private:
+	OcvrsRustImpl(const OcvrsRustImpl&);
+	OcvrsRustImpl& operator=(const OcvrsRustImpl&);
+};
+
+// Body of the from_rust() functions: creates cv::Ptr<Base> holding Impl constructed from args, the ownership of the Rust instance
+// passed in args is only taken when nothing can throw anymore, otherwise it stays with Rust
+template<typename Base, typename Impl, typename... Args> inline void ocvrs_ptr_from_rust(const char* name, Result<void*>* ocvrs_return, Args... args) {
+	cv::Ptr<Base>* ret = NULL;
+	try {
+		ret = new cv::Ptr<Base>();
+		*ret = cv::makePtr<Impl>(args...);
+		Ok<void*>(ret, ocvrs_return);
+	} catch (...) {
+		delete ret;
+		Err<Result<void*>>(cv::Error::StsNoMem, (std::string("can't allocate ") + name).c_str(), ocvrs_return);
+	}
+}
+
+#endif
@@ -0,0 +1,47 @@
+#![cfg(ocvrs_has_module_calib3d)]
+
+use opencv::{
+	calib3d::{LMSolver, LMSolver_Callback, LMSolver_CallbackImpl},
+	core::{self, Ptr, _InputArray, _OutputArray},
+	prelude::*,
+	Result,
+};
+
+/// Fits `y = a * x + b` to the points lying on the `y = 2 * x - 3` line
+struct LineFit {
+	points: Vec<(f64, f64)>,
+}
+
+impl LMSolver_CallbackImpl for LineFit {
+	fn compute(&self, param: &_InputArray, err: &mut _OutputArray, j: &mut _OutputArray) -> Result<bool> {
+		let param = param.get_mat(-1)?;
+		let param = param.data_typed::<f64>()?;
+		let (a, b) = (param[0], param[1]);
+		let errors = self.points.iter().map(|&(x, y)| a * x + b - y).collect::<Vec<_>>();
+		Mat::from_slice(&errors)?.t()?.to_mat()?.copy_to(err)?;
+		if j.needed()? {
+			let mut jac = Mat::new_rows_cols_with_default(self.points.len() as i32, 2, f64::typ(), core::Scalar::all(1.))?;
+			for (i, &(x, _)) in self.points.iter().enumerate() {
+				*jac.at_2d_mut::<f64>(i as i32, 0)? = x;
+			}
+			jac.copy_to(j)?;
+		}
+		Ok(true)
+	}
+}
+
+#[test]
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn lm_solver_rust_callback() -> Result<()> {
+	let cb = Ptr::<dyn LMSolver_Callback>::from_rust(LineFit {
+		points: (0..10).map(|x| (f64::from(x), 2. * f64::from(x) - 3.)).collect(),
+	})?;
+	let solver = <dyn LMSolver>::create(&cb, 100)?;
+	let mut param = Mat::from_slice(&[0., 0.])?.t()?.to_mat()?;
+	let iters = solver.run(&mut param)?;
+	assert!(iters > 0);
+	let param = param.data_typed::<f64>()?;
+	assert!((param[0] - 2.).abs() < 1e-6);
+	assert!((param[1] + 3.).abs() < 1e-6);
+	Ok(())
+}
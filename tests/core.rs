@@ -1,5 +1,18 @@
+use std::{
+	env,
+	sync::{
+		atomic::{AtomicI64, AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
 use opencv::{
-	core::{self, Moments, Point2f, RotatedRect, Scalar, Size2f, Vec3b, CV_32S, CV_64F, CV_8U, CV_MAKETYPE},
+	core::{
+		self,
+		npy::{self, NpzCompression, Order},
+		MinProblemSolver_Function, MinProblemSolver_FunctionImpl, Moments, Point2f, Ptr, RotatedRect, Scalar, Size2f, TermCriteria,
+		Vec3b, CV_16S, CV_16U, CV_32F, CV_32S, CV_64F, CV_8S, CV_8U, CV_MAKETYPE,
+	},
 	prelude::*,
 	types::VectorOfMat,
 	Result,
@@ -98,3 +111,278 @@ fn min_max_loc() -> Result<()> {
 	assert_eq!(30., max_val);
 	Ok(())
 }
+
+#[test]
+fn parallel_for() -> Result<()> {
+	let sum = AtomicI64::new(0);
+	let calls = AtomicUsize::new(0);
+	core::parallel_for(0..1000, 4., |r| {
+		calls.fetch_add(1, Ordering::Relaxed);
+		sum.fetch_add(r.map(i64::from).sum(), Ordering::Relaxed);
+	})?;
+	assert_eq!(499500, sum.into_inner());
+	assert!(calls.into_inner() >= 1);
+
+	core::parallel_for(0..0, -1., |_| panic!("body must not be called for empty range"))?;
+	Ok(())
+}
+
+#[test]
+#[should_panic(expected = "stripe panic")]
+fn parallel_for_panic() {
+	core::parallel_for(0..100, -1., |r| {
+		if r.contains(&50) {
+			panic!("stripe panic");
+		}
+	})
+	.unwrap();
+}
+
+struct Paraboloid {
+	calls: Arc<AtomicUsize>,
+}
+
+impl MinProblemSolver_FunctionImpl for Paraboloid {
+	fn get_dims(&self) -> i32 {
+		2
+	}
+
+	fn calc(&self, x: &[f64]) -> Result<f64> {
+		self.calls.fetch_add(1, Ordering::Relaxed);
+		Ok((x[0] - 1.).powi(2) + (x[1] + 2.).powi(2))
+	}
+}
+
+#[test]
+fn min_problem_solver_rust_function() -> Result<()> {
+	let calls = Arc::new(AtomicUsize::new(0));
+	let func = Ptr::<dyn MinProblemSolver_Function>::from_rust(Paraboloid {
+		calls: Arc::clone(&calls),
+	})?;
+	let termcrit = TermCriteria::new(
+		core::TermCriteria_Type::COUNT as i32 + core::TermCriteria_Type::EPS as i32,
+		5000,
+		1e-10,
+	)?;
+	let mut x = Mat::from_slice(&[0., 0.])?;
+	{
+		let mut solver = <dyn core::DownhillSolver>::create(&func, &Mat::from_slice(&[1., 1.])?, termcrit)?;
+		let res = solver.minimize(&mut x)?;
+		assert!(res < 1e-6);
+	}
+	let x = x.data_typed::<f64>()?;
+	assert!((x[0] - 1.).abs() < 1e-3);
+	assert!((x[1] + 2.).abs() < 1e-3);
+	assert!(calls.load(Ordering::Relaxed) > 0);
+
+	let mut x = Mat::from_slice(&[0., 0.])?;
+	{
+		let mut solver = <dyn core::ConjGradSolver>::create(&func, termcrit)?;
+		solver.minimize(&mut x)?;
+	}
+	let x = x.data_typed::<f64>()?;
+	assert!((x[0] - 1.).abs() < 1e-3);
+	assert!((x[1] + 2.).abs() < 1e-3);
+	Ok(())
+}
+
+struct FailingFunction;
+
+impl MinProblemSolver_FunctionImpl for FailingFunction {
+	fn get_dims(&self) -> i32 {
+		1
+	}
+
+	fn calc(&self, _x: &[f64]) -> Result<f64> {
+		Err(opencv::Error::new(core::StsBadArg, "calc failed"))
+	}
+}
+
+#[test]
+fn min_problem_solver_rust_function_error() -> Result<()> {
+	let func = Ptr::<dyn MinProblemSolver_Function>::from_rust(FailingFunction)?;
+	let mut solver = <dyn core::DownhillSolver>::create(&func, &Mat::from_slice(&[1.])?, TermCriteria::default()?)?;
+	let mut x = Mat::from_slice(&[0.])?;
+	let err = solver.minimize(&mut x).unwrap_err();
+	assert_eq!(core::StsBadArg, err.code);
+	assert!(err.message.contains("calc failed"));
+	Ok(())
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn async_futures() -> Result<()> {
+	use std::{thread, time::Duration};
+
+	let res = pollster::block_on(core::spawn_blocking(|| Ok(40 + 2)))?;
+	assert_eq!(42, res);
+	let err = pollster::block_on(core::spawn_blocking(|| -> Result<()> {
+		Err(opencv::Error::new(core::StsBadArg, "fail"))
+	}));
+	assert_eq!(core::StsBadArg, err.unwrap_err().code);
+
+	// dropping the future before it was polled means it never runs
+	let ran = Arc::new(AtomicUsize::new(0));
+	drop(core::spawn_blocking({
+		let ran = Arc::clone(&ran);
+		move || {
+			ran.fetch_add(1, Ordering::SeqCst);
+			Ok(())
+		}
+	}));
+	thread::sleep(Duration::from_millis(50));
+	assert_eq!(0, ran.load(Ordering::SeqCst));
+
+	let mut promise = core::AsyncPromise::default();
+	let array = promise.get_array_result()?;
+	let setter = thread::spawn(move || -> Result<()> {
+		thread::sleep(Duration::from_millis(100));
+		promise.set_value(&Mat::from_slice(&[1, 2, 3])?)
+	});
+	let res = pollster::block_on(array.wait_async())?;
+	setter.join().expect("Setter thread panicked")?;
+	assert_eq!(&[1, 2, 3], res.data_typed::<i32>()?);
+	Ok(())
+}
+
+/// Creates the Mat with every byte of the data set to its index
+fn npy_test_mat(sizes: &[i32], typ: i32) -> Result<Mat> {
+	let mut out = Mat::new_nd_with_default(sizes, typ, Scalar::all(0.))?;
+	out.data_bytes_mut()?.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+	Ok(out)
+}
+
+#[test]
+fn npy_round_trip() -> Result<()> {
+	#[allow(unused_mut)]
+	let mut depths = vec![CV_8U, CV_8S, CV_16U, CV_16S, CV_32S, CV_32F, CV_64F];
+	#[cfg(not(ocvrs_opencv_branch_32))]
+	depths.push(core::CV_16F);
+	for depth in depths {
+		for sizes in [&[5, 7][..], &[2, 3, 4], &[2, 1, 3, 2, 2]] {
+			let mat = npy_test_mat(sizes, CV_MAKETYPE(depth, 1))?;
+			for order in [Order::C, Order::Fortran] {
+				let read = npy::read_bytes(&npy::to_bytes(&mat, order)?)?;
+				assert_eq!(mat.typ(), read.typ());
+				assert_eq!(sizes, &*read.mat_size());
+				assert_eq!(mat.data_bytes()?, read.data_bytes()?);
+			}
+		}
+	}
+
+	// channels become the last axis
+	let mat = npy_test_mat(&[2, 3], CV_MAKETYPE(CV_16U, 3))?;
+	let read = npy::read_bytes(&npy::to_bytes(&mat, Order::Fortran)?)?;
+	assert_eq!(CV_16U, read.typ());
+	assert_eq!(&[2, 3, 3], &*read.mat_size());
+	assert_eq!(mat.data_bytes()?, read.data_bytes()?);
+
+	// non-continuous Mat
+	let mat = npy_test_mat(&[4, 6], CV_32F)?;
+	let col = mat.col(2)?;
+	let read = npy::read_bytes(&npy::to_bytes(&col, Order::C)?)?;
+	assert_eq!(&[4, 1], &*read.mat_size());
+	assert_eq!(col.try_clone()?.data_bytes()?, read.data_bytes()?);
+
+	let path = env::temp_dir().join("opencv-rust-round-trip.npy");
+	let mat = npy_test_mat(&[3, 4, 5], CV_64F)?;
+	npy::write(&path, &mat)?;
+	let read = npy::read(&path)?;
+	assert_eq!(&[3, 4, 5], &*read.mat_size());
+	assert_eq!(mat.data_bytes()?, read.data_bytes()?);
+	npy::write_with_order(&path, &mat, Order::Fortran)?;
+	assert_eq!(mat.data_bytes()?, npy::read(&path)?.data_bytes()?);
+	Ok(())
+}
+
+fn npy_bytes(header: &str, data: &[u8]) -> Vec<u8> {
+	let mut out = b"\x93NUMPY\x01\x00".to_vec();
+	out.extend_from_slice(&(header.len() as u16).to_le_bytes());
+	out.extend_from_slice(header.as_bytes());
+	out.extend_from_slice(data);
+	out
+}
+
+#[test]
+fn npy_format() -> Result<()> {
+	// big-endian int16 in Fortran order
+	let data = [1i16, 4, 2, 5, 3, 6].iter().flat_map(|v| v.to_be_bytes()).collect::<Vec<_>>();
+	let mat = npy::read_bytes(&npy_bytes(
+		"{'descr': '>i2', 'fortran_order': True, 'shape': (2, 3), }\n",
+		&data,
+	))?;
+	assert_eq!(CV_16S, mat.typ());
+	assert_eq!(&[2, 3], &*mat.mat_size());
+	assert_eq!(&[1, 2, 3, 4, 5, 6], mat.data_typed::<i16>()?);
+
+	// 1-d and 0-d arrays, Python 2 style header
+	let data = [1.5f32, 2.5, 3.5].iter().flat_map(|v| v.to_le_bytes()).collect::<Vec<_>>();
+	let mat = npy::read_bytes(&npy_bytes("{'descr':'<f4','fortran_order':False,'shape':(3L,)}", &data))?;
+	assert_eq!(&[1, 3], &*mat.mat_size());
+	assert_eq!(&[1.5, 2.5, 3.5], mat.data_typed::<f32>()?);
+	let mat = npy::read_bytes(&npy_bytes("{'shape': (), 'fortran_order': False, 'descr': '|b1'}", &[1]))?;
+	assert_eq!(&[1, 1], &*mat.mat_size());
+	assert_eq!(&[1], mat.data_typed::<u8>()?);
+
+	// header is aligned and the Fortran data is transposed
+	let mat = Mat::from_slice_2d(&[[1i32, 2, 3], [4, 5, 6]])?;
+	let bytes = npy::to_bytes(&mat, Order::Fortran)?;
+	let header_len = usize::from(u16::from_le_bytes([bytes[8], bytes[9]]));
+	assert_eq!(0, (10 + header_len) % 64);
+	assert!(std::str::from_utf8(&bytes[10..10 + header_len])
+		.unwrap()
+		.contains("'fortran_order': True, 'shape': (2, 3)"));
+	let data = [1i32, 4, 2, 5, 3, 6].iter().flat_map(|v| v.to_ne_bytes()).collect::<Vec<_>>();
+	assert_eq!(&data, &bytes[10 + header_len..]);
+
+	let err = npy::read_bytes(&npy_bytes(
+		"{'descr': '<i8', 'fortran_order': False, 'shape': (1,), }",
+		&[0; 8],
+	))
+	.unwrap_err();
+	assert_eq!(core::StsUnsupportedFormat, err.code);
+	let err = npy::read_bytes(&npy_bytes(
+		"{'descr': '<i4', 'fortran_order': False, 'shape': (2,), }",
+		&[0; 4],
+	))
+	.unwrap_err();
+	assert_eq!(core::StsParseError, err.code);
+	Ok(())
+}
+
+#[test]
+fn npz_round_trip() -> Result<()> {
+	let image = npy_test_mat(&[16, 16], CV_MAKETYPE(CV_8U, 3))?;
+	let blob = npy_test_mat(&[1, 3, 4, 4], CV_32F)?;
+	for compression in [NpzCompression::Stored, NpzCompression::Deflate] {
+		let bytes = npy::npz_to_bytes(&[("image", &image), ("blob", &blob)], compression)?;
+		let arrays = npy::read_npz_bytes(&bytes)?;
+		assert_eq!(2, arrays.len());
+		assert_eq!("image", arrays[0].0);
+		assert_eq!(&[16, 16, 3], &*arrays[0].1.mat_size());
+		assert_eq!(image.data_bytes()?, arrays[0].1.data_bytes()?);
+		assert_eq!("blob", arrays[1].0);
+		assert_eq!(&[1, 3, 4, 4], &*arrays[1].1.mat_size());
+		assert_eq!(blob.data_bytes()?, arrays[1].1.data_bytes()?);
+	}
+
+	let path = env::temp_dir().join("opencv-rust-round-trip.npz");
+	npy::write_npz(&path, &[("blob", &blob)], NpzCompression::Deflate)?;
+	let arrays = npy::read_npz(&path)?;
+	assert_eq!("blob", arrays[0].0);
+	assert_eq!(blob.data_bytes()?, arrays[0].1.data_bytes()?);
+
+	// corrupted data is detected by the checksum
+	let mut bytes = npy::npz_to_bytes(&[("blob", &blob)], NpzCompression::Stored)?;
+	bytes[200] ^= 0xFF;
+	assert_eq!(core::StsParseError, npy::read_npz_bytes(&bytes).unwrap_err().code);
+
+	// zip64 locator pointing to the offset that overflows when the record fields are read
+	let mut bytes = vec![0x50, 0x4b, 0x06, 0x07, 0, 0, 0, 0];
+	bytes.extend_from_slice(&(u64::MAX - 2).to_le_bytes());
+	bytes.extend_from_slice(&[1, 0, 0, 0]);
+	bytes.extend_from_slice(&[0x50, 0x4b, 0x05, 0x06, 0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff]);
+	bytes.extend_from_slice(&[0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0, 0]);
+	assert!(npy::read_npz_bytes(&bytes).is_err());
+	Ok(())
+}
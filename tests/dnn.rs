@@ -1,10 +1,16 @@
 #![cfg(ocvrs_has_module_dnn)]
 
+use std::{sync::Arc, thread, time::Duration};
+
 use matches::assert_matches;
 
 use opencv::{
-	core,
-	dnn::{DictValue, LayerParams, Net},
+	core::{self, Point2f, Rect2f, RotatedRect, Size, Size2f, Vector},
+	dnn::{
+		self,
+		postprocess::{self, BlobMapping, Detection, ResizeMode, RetinaFaceConfig, ScrfdLevel, YoloLayout},
+		BatchConfig, DictValue, Framework, LayerParams, Net, NetEdge, NetPool, Preprocessor, RustLayer, Tensor,
+	},
 	prelude::*,
 	types::VectorOfMat,
 	Error, Result,
@@ -123,3 +129,642 @@ fn dict_value() -> Result<()> {
 	}
 	Ok(())
 }
+
+/// Multiplies the input by the "scale" layer parameter
+struct ScaleLayer {
+	scale: f64,
+}
+
+impl RustLayer for ScaleLayer {
+	fn new(params: &LayerParams) -> Result<Self> {
+		Ok(Self {
+			scale: params.get("scale")?.get_f64(-1)?,
+		})
+	}
+
+	fn forward(&mut self, inputs: &[Mat], outputs: &mut [Mat]) -> Result<()> {
+		inputs[0].convert_to(&mut outputs[0], -1, self.scale, 0.)
+	}
+}
+
+#[test]
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn rust_layer() -> Result<()> {
+	let registration = dnn::register_layer::<ScaleLayer>("RustScale")?;
+	assert_eq!("RustScale", registration.type_name());
+	assert!(dnn::register_layer::<ScaleLayer>("RustScale").is_err());
+
+	let mut net = Net::default()?;
+	let mut params = LayerParams::default()?;
+	params.set_f64("scale", &3.)?;
+	net.add_layer_to_prev("scale", "RustScale", &mut params)?;
+	let input = Mat::from_slice_2d(&[&[1f32, 2.], &[3., 4.]])?;
+	let input = input.reshape_nd(1, &[1, 1, 2, 2])?;
+	net.set_input(&input, "", 1., core::Scalar::default())?;
+	let out = net.forward_single("scale")?;
+	assert_eq!(&[3., 6., 9., 12.], out.data_typed::<f32>()?);
+
+	drop(registration);
+	let mut net = Net::default()?;
+	let mut params = LayerParams::default()?;
+	net.add_layer_to_prev("scale", "RustScale", &mut params)?;
+	net.set_input(&input, "", 1., core::Scalar::default())?;
+	assert!(net.forward_single("scale").is_err());
+	Ok(())
+}
+
+#[test]
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn net_graph() -> Result<()> {
+	let mut net = Net::default()?;
+	net.add_layer_to_prev("relu \"1\"", "ReLU", &mut LayerParams::default()?)?;
+	net.add_layer_to_prev("sigmoid", "Sigmoid", &mut LayerParams::default()?)?;
+
+	let graph = net.graph(&[])?;
+	assert_eq!(vec![0, 1, 2], graph.nodes.iter().map(|n| n.id).collect::<Vec<_>>());
+	assert_eq!(vec![NetEdge { from: 0, to: 1 }, NetEdge { from: 1, to: 2 }], graph.edges);
+	assert_eq!("ReLU", graph.node_by_name("relu \"1\"").unwrap().typ);
+	assert_eq!("relu \"1\"", graph.node(1).unwrap().params.name());
+	assert!(graph.node(2).unwrap().output_shapes.is_empty());
+
+	let graph = net.graph(&[&[1, 1, 2, 2]])?;
+	let sigmoid = graph.node_by_name("sigmoid").unwrap();
+	assert_eq!(vec![vec![1, 1, 2, 2]], sigmoid.input_shapes);
+	assert_eq!(vec![vec![1, 1, 2, 2]], sigmoid.output_shapes);
+	assert_eq!(vec![1], graph.inputs_of(2).collect::<Vec<_>>());
+	assert_eq!(vec![2], graph.outputs_of(1).collect::<Vec<_>>());
+	assert!(graph.flops > 0);
+	assert!(graph.blobs_memory > 0);
+
+	let dot = graph.to_dot();
+	assert!(dot.starts_with("digraph net {"));
+	assert!(dot.contains("l1 [label=\"{relu \\\"1\\\"|ReLU|1x1x2x2}\"];"));
+	assert!(dot.contains("l1 -> l2;"));
+	let json = graph.to_json();
+	assert!(json.starts_with(r#"{"nodes":[{"id":0,"#));
+	assert!(json.contains(r#""name":"relu \"1\"","type":"ReLU","blobs":[],"inputs":[[1,1,2,2]],"outputs":[[1,1,2,2]]"#));
+	assert!(json.contains(r#""edges":[[0,1],[1,2]]"#));
+
+	let input = Mat::new_nd_with_default(&[1, 1, 2, 2], core::CV_32F, core::Scalar::all(1.))?;
+	net.set_input(&input, "", 1., core::Scalar::default())?;
+	net.forward_single("sigmoid")?;
+	let profile = net.perf_profile()?;
+	assert_eq!(
+		vec!["relu \"1\"", "sigmoid"],
+		profile.layers.iter().map(|l| l.name.as_str()).collect::<Vec<_>>()
+	);
+	assert!(profile.to_string().ends_with(" ms"));
+	Ok(())
+}
+
+#[test]
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn net_pool() -> Result<()> {
+	let pool = NetPool::new(2, || {
+		let mut net = Net::default()?;
+		net.add_layer_to_prev("relu", "ReLU", &mut LayerParams::default()?)?;
+		Ok(net)
+	})?;
+	assert!(NetPool::new(0, Net::default).is_err());
+	assert_eq!(2, pool.size());
+	{
+		let first = pool.get();
+		let _second = pool.try_get().unwrap();
+		assert_eq!(0, pool.available());
+		assert!(pool.try_get().is_none());
+		assert!(pool.get_timeout(Duration::from_millis(10)).is_none());
+		assert_eq!(vec!["relu".to_string()], first.get_layer_names()?.to_vec());
+	}
+	assert_eq!(2, pool.available());
+
+	let batched = Arc::new(
+		pool.batched(
+			BatchConfig::new(Preprocessor::new(Size::new(2, 2)).resize_mode(ResizeMode::Letterbox))
+				.max_batch(3)
+				.max_latency(Duration::from_millis(50)),
+		)?,
+	);
+	let threads = (0..5u8)
+		.map(|i| {
+			let batched = Arc::clone(&batched);
+			thread::spawn(move || {
+				let image = Mat::new_rows_cols_with_default(2, 2, core::CV_8UC3, core::Scalar::all(f64::from(i)))?;
+				batched.infer(&image)
+			})
+		})
+		.collect::<Vec<_>>();
+	for (i, thread) in threads.into_iter().enumerate() {
+		let (outputs, transform) = thread.join().unwrap()?;
+		assert_eq!(1, outputs.len());
+		assert_eq!(&[1, 3, 2, 2], &*outputs[0].mat_size());
+		assert_eq!(&[i as f32; 12], outputs[0].data_typed::<f32>()?);
+		assert_eq!(Size::new(2, 2), transform.source_size());
+	}
+	// the wider image is letterboxed keeping its aspect ratio
+	let image = Mat::new_rows_cols_with_default(1, 4, core::CV_8UC3, core::Scalar::all(4.))?;
+	let (outputs, transform) = batched.infer(&image)?;
+	assert_eq!(&[1, 3, 2, 2], &*outputs[0].mat_size());
+	assert_eq!(Point2f::new(2., 0.5), transform.to_source(Point2f::new(1., 0.5)));
+	assert_eq!(0, batched.pending());
+	assert_eq!(2, pool.available());
+	Ok(())
+}
+
+#[test]
+fn framework_detect() -> Result<()> {
+	assert_eq!(Some(Framework::Onnx), Framework::detect(b"\x08\x07\x12\x07pytorch"));
+	assert_eq!(
+		Some(Framework::TensorFlow),
+		Framework::detect(b"\x0a\x2a\x0a\x05input\x12\x0bPlaceholder")
+	);
+	assert_eq!(Some(Framework::Caffe), Framework::detect(b"\x0a\x07AlexNet\xa2\x06"));
+	assert_eq!(Some(Framework::Caffe), Framework::detect(b"\xa2\x06\x1a\x0a\x04data"));
+	assert_eq!(
+		Some(Framework::Darknet),
+		Framework::detect(&[0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0])
+	);
+	assert_eq!(None, Framework::detect(b"[net]\nbatch=1"));
+	assert_eq!(None, Framework::detect(b""));
+	assert_eq!(None, Framework::detect(b"\x0a"));
+
+	assert_matches!(
+		Net::from_reader(&b"[net]"[..], Framework::Auto).map(|_| ()),
+		Err(Error {
+			code: core::StsUnsupportedFormat,
+			..
+		})
+	);
+	assert_matches!(
+		Net::from_reader(&b"\x0a\x07AlexNet"[..], Framework::Auto).map(|_| ()),
+		Err(Error {
+			code: core::StsBadArg,
+			..
+		})
+	);
+	#[cfg(not(ocvrs_opencv_branch_32))]
+	assert!(Net::from_onnx_bytes(b"\xff\x00 not a model").is_err());
+	Ok(())
+}
+
+#[test]
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn model_labels() {
+	let labels = dnn::Labels::parse("person\nbicycle \r\n\ntraffic light\n\n\n");
+	assert_eq!(4, labels.len());
+	assert_eq!(Some("bicycle"), labels.name(1));
+	assert_eq!(Some(""), labels.name(2));
+	assert_eq!(Some("traffic light"), labels.name(3));
+	assert_eq!(None, labels.name(4));
+	assert_eq!(None, labels.name(-1));
+	assert_eq!(Some(3), labels.id("traffic light"));
+	assert_eq!(None, labels.id("car"));
+	assert!(dnn::Labels::parse("").is_empty());
+
+	let detection = Detection {
+		class_id: 1,
+		confidence: 0.9,
+		rect: Rect2f::new(0., 0., 1., 1.),
+		landmarks: vec![],
+	};
+	assert_eq!(Some("bicycle"), detection.label(&labels));
+	let classification = dnn::Classification {
+		class_id: 7,
+		confidence: 0.5,
+	};
+	assert_eq!(None, classification.label(&labels));
+
+	#[cfg(ocvrs_opencv_branch_4)]
+	{
+		use opencv::core::{Point, Rect};
+		let region = dnn::TextRegion {
+			quad: [Point::new(10, 40), Point::new(12, 8), Point::new(90, 10), Point::new(88, 42)],
+			confidence: 1.,
+		};
+		assert_eq!(Rect::new(10, 8, 80, 34), region.bounding_rect());
+	}
+}
+
+#[test]
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn keypoints_model() -> Result<()> {
+	use opencv::core::Vec3b;
+	// the identity network returns the frame channels as the keypoint heatmaps, the last one is the background
+	let mut net = Net::default()?;
+	net.add_layer_to_prev("relu", "ReLU", &mut LayerParams::default()?)?;
+	let mut model = dnn::KeypointsModel::new_1(&net)?;
+	model.set_input_size(Size::new(4, 4))?;
+	let mut frame = Mat::new_rows_cols_with_default(4, 4, Vec3b::typ(), core::Scalar::all(0.))?;
+	*frame.at_2d_mut::<Vec3b>(1, 2)? = Vec3b::from([200, 0, 0]);
+	let keypoints = model.keypoints(&frame, 0.5)?;
+	assert_eq!(
+		vec![dnn::Keypoint {
+			id: 0,
+			point: Point2f::new(2., 1.)
+		}],
+		keypoints
+	);
+	let labels = dnn::Labels::parse("nose\nneck");
+	assert_eq!(Some("nose"), keypoints[0].label(&labels));
+	Ok(())
+}
+
+#[test]
+#[cfg(feature = "dnn-testing")]
+fn golden_testing() -> Result<()> {
+	use opencv::dnn::testing::{self, GoldenCase, Tolerance};
+
+	let next_after_2 = f32::from_bits(2f32.to_bits() + 1);
+	let actual = Mat::from_slice(&[1f32, 2., 3.])?;
+	let expected = Mat::from_slice(&[1f32, next_after_2, 3.5])?;
+	let report = testing::compare("out", &actual, &expected, Tolerance::exact().ulp(1))?;
+	assert!(!report.passed());
+	assert_eq!(vec![1, 3], report.shape);
+	assert_eq!(3, report.elements);
+	assert_eq!(1, report.mismatches);
+	assert_eq!(0.5, report.max_abs_error);
+	assert_eq!(vec![0, 2], report.max_abs_location);
+	assert_eq!((3., 3.5), report.max_abs_values);
+	assert_eq!(
+		vec![1, 0, 0, 1, 0, 0, 0, 0, 0, 1, 0],
+		report.histogram.iter().map(|bin| bin.count).collect::<Vec<_>>()
+	);
+	assert!(report
+		.to_string()
+		.starts_with("out: FAIL, shape [1, 3], 1 of 3 elements mismatched"));
+	let report = testing::compare("out", &actual, &expected, Tolerance::exact().rel(0.2))?;
+	assert!(report.passed());
+	let report = testing::compare("out", &actual, &Mat::from_slice(&[1f32, 2.])?, Tolerance::default())?;
+	assert!(!report.passed());
+	assert_eq!(0, report.elements);
+
+	let mut net = Net::default()?;
+	net.add_layer_to_prev("relu", "ReLU", &mut LayerParams::default()?)?;
+	let input = Mat::new_nd_with_default(&[1, 1, 1, 2], core::CV_32F, core::Scalar::all(-1.))?;
+	let expected = Mat::new_nd_with_default(&[1, 1, 1, 2], core::CV_32F, core::Scalar::all(0.))?;
+	let case = GoldenCase::new().input("", input.clone()).expected("relu", expected.clone());
+	let report = testing::run(&mut net, &case, &Tolerance::default())?;
+	assert!(report.passed(), "{}", report);
+	assert_eq!(1, report.outputs.len());
+	assert_eq!(0, report.failures().count());
+
+	// same case loaded from the .npy files
+	let dir = std::env::temp_dir();
+	let (input_path, expected_path) = (
+		dir.join("opencv-rust-golden-input.npy"),
+		dir.join("opencv-rust-golden-relu.npy"),
+	);
+	core::npy::write(&input_path, &input)?;
+	core::npy::write(&expected_path, &expected)?;
+	let case = GoldenCase::new()
+		.input_from_npy("", &input_path)?
+		.expected_from_npy("relu", &expected_path)?;
+	assert!(testing::run(&mut net, &case, &Tolerance::default())?.passed());
+	Ok(())
+}
+
+#[test]
+fn tensor() -> Result<()> {
+	let mat = Mat::from_slice(&[1f32, 2., 3., 4., 5., 6.])?.reshape_nd(1, &[1, 2, 1, 3])?;
+	let mut tensor = Tensor::<f32>::from_mat(mat)?;
+	assert_eq!(&[1, 2, 1, 3], tensor.shape());
+	assert_eq!([1, 2, 1, 3], tensor.nchw()?);
+	assert_eq!(6, tensor.len());
+	assert_eq!(6., tensor[[0, 1, 0, 2]]);
+	assert_eq!(None, tensor.get(&[0, 2, 0, 0]));
+	assert_eq!(None, tensor.get(&[0, 0]));
+	tensor[[0, 0, 0, 0]] = 10.;
+	assert_eq!(10., *tensor.as_mat().at_nd::<f32>(&[0, 0, 0, 0])?);
+	assert_matches!(
+		Tensor::<u8>::from_mat(tensor.as_mat().clone()),
+		Err(Error {
+			code: core::StsUnmatchedFormats,
+			..
+		})
+	);
+
+	// reshaping keeps the data
+	assert!(tensor.nchw().is_ok());
+	let copy = Tensor::<f32>::from_mat(tensor.as_mat().clone())?;
+	let rows = tensor.reshape(&[-1, 3])?;
+	assert_eq!(&[2, 3], rows.shape());
+	assert_eq!(Some(&[4., 5., 6.][..]), rows.outer(1));
+	assert_eq!(copy.as_slice(), rows.as_slice());
+	assert!(rows.nchw().is_err());
+	assert_matches!(
+		copy.reshape(&[4, -1]),
+		Err(Error {
+			code: core::StsUnmatchedSizes,
+			..
+		})
+	);
+	assert_eq!(&[6], rows.reshape(&[6])?.shape());
+
+	let scores = Tensor::from_slice(&[2, 4], &[0.1f32, 0.7, 0.2, 0.7, 3., 1., 2., 0.])?;
+	let argmax = scores.argmax(1)?;
+	assert_eq!(&[2], argmax.shape());
+	assert_eq!(&[1, 0], argmax.as_slice());
+	assert_eq!(&[1, 1, 1, 0], scores.argmax(0)?.as_slice());
+
+	let (values, indices) = scores.topk(1, 3)?;
+	assert_eq!(&[2, 3], values.shape());
+	assert_eq!(&[0.7, 0.7, 0.2, 3., 2., 1.], values.as_slice());
+	assert_eq!(&[1, 3, 2, 0, 2, 1], indices.as_slice());
+	let (values, indices) = scores.topk(0, 1)?;
+	assert_eq!(&[1, 4], values.shape());
+	assert_eq!(&[3., 1., 2., 0.7], values.as_slice());
+	assert_eq!(&[1, 1, 1, 0], indices.as_slice());
+	let with_nan = Tensor::from_slice(&[5], &[0.5f32, f32::NAN, 2., f32::NAN, 1.])?;
+	let (values, indices) = with_nan.topk(0, 4)?;
+	assert_eq!(&[2., 1., 0.5], &values.as_slice()[..3]);
+	assert!(values.as_slice()[3].is_nan());
+	assert_eq!(&[2, 4, 0, 1], indices.as_slice());
+	assert_matches!(
+		scores.topk(1, 5),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+	assert_matches!(
+		scores.argmax(2),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+
+	let probs = Tensor::from_slice(&[2, 3], &[1f64, 2., 3., 1000., 1000., 1000.])?.softmax(1)?;
+	let expected = [0.09003057, 0.24472847, 0.66524096, 1. / 3., 1. / 3., 1. / 3.];
+	for (&prob, &expected) in probs.as_slice().iter().zip(expected.iter()) {
+		assert!((prob - expected).abs() < 1e-7, "{} != {}", prob, expected);
+	}
+
+	let mat: Mat = probs.into();
+	assert_eq!(core::CV_64F, mat.typ());
+	assert_eq!(6, mat.total());
+	Ok(())
+}
+
+#[test]
+fn postprocess_blob_mapping() {
+	let source = Size::new(200, 100);
+	let blob = Size::new(100, 100);
+	let stretch = BlobMapping::new(source, blob, ResizeMode::Stretch);
+	assert_eq!(Point2f::new(100., 50.), stretch.to_source_point(Point2f::new(50., 50.)));
+	let crop = BlobMapping::new(source, blob, ResizeMode::Crop);
+	assert_eq!(Point2f::new(50., 0.), crop.to_source_point(Point2f::new(0., 0.)));
+	let letterbox = BlobMapping::new(source, blob, ResizeMode::Letterbox);
+	assert_eq!(Point2f::new(100., 50.), letterbox.to_source_point(Point2f::new(50., 50.)));
+	assert_eq!(Point2f::new(50., 50.), letterbox.to_blob_point(Point2f::new(100., 50.)));
+	assert_eq!(Point2f::new(0., 0.), letterbox.to_source_point(Point2f::new(0., 25.)));
+	let top_left = BlobMapping::new(source, blob, ResizeMode::LetterboxTopLeft);
+	assert_eq!(Point2f::new(100., 50.), top_left.to_source_point(Point2f::new(50., 25.)));
+	// clipped to the source image
+	assert_eq!(
+		Rect2f::new(0., 0., 20., 10.),
+		letterbox.to_source_rect(Rect2f::new(-10., 20., 20., 10.))
+	);
+	let detection = Detection {
+		class_id: 1,
+		confidence: 0.5,
+		rect: Rect2f::new(10., 30., 20., 10.),
+		landmarks: vec![Point2f::new(20., 35.)],
+	};
+	let mapped = detection.to_source(&letterbox);
+	assert_eq!(Rect2f::new(20., 10., 40., 20.), mapped.rect);
+	assert_eq!(vec![Point2f::new(40., 20.)], mapped.landmarks);
+	assert_eq!(1, mapped.class_id);
+}
+
+#[test]
+fn postprocess_decode() -> Result<()> {
+	let input_size = Size::new(64, 32);
+
+	let ssd = Tensor::from_slice(
+		&[1, 1, 2, 7],
+		&[0., 3., 0.75, 0.25, 0.5, 0.5, 1., 0., 1., 0.25, 0., 0., 1., 1.],
+	)?;
+	let detections = postprocess::decode_ssd(&ssd, input_size, 0.5)?;
+	assert_eq!(1, detections.len());
+	assert_eq!(3, detections[0].class_id);
+	assert_eq!(0.75, detections[0].confidence);
+	assert_eq!(Rect2f::new(16., 16., 16., 16.), detections[0].rect);
+	assert_matches!(
+		postprocess::decode_ssd(&Tensor::from_slice(&[1, 6], &[0.; 6])?, input_size, 0.5),
+		Err(Error {
+			code: core::StsUnmatchedSizes,
+			..
+		})
+	);
+
+	// [cx, cy, w, h, objectness, class 0, class 1]
+	let rows = Tensor::from_slice(&[1, 2, 7], &[32., 16., 8., 4., 1., 0.25, 0.5, 10., 10., 2., 2., 0.25, 1., 1.])?;
+	let detections = postprocess::decode_yolo(&rows, &YoloLayout::Rows, input_size, 0.4)?;
+	assert_eq!(1, detections.len());
+	assert_eq!(1, detections[0].class_id);
+	assert_eq!(0.5, detections[0].confidence);
+	assert_eq!(Rect2f::new(28., 14., 8., 4.), detections[0].rect);
+
+	let region = Tensor::from_slice(&[1, 7], &[0.5, 0.5, 0.25, 0.5, 0.5, 0.5, 0.25])?;
+	let detections = postprocess::decode_yolo(&region, &YoloLayout::Region, input_size, 0.4)?;
+	assert_eq!(1, detections.len());
+	assert_eq!(0, detections[0].class_id);
+	assert_eq!(0.5, detections[0].confidence);
+	assert_eq!(Rect2f::new(24., 8., 16., 16.), detections[0].rect);
+
+	// [1, 4 + classes, N], two candidates as columns
+	let anchor_free = Tensor::from_slice(&[1, 6, 2], &[32., 8., 16., 8., 8., 4., 4., 4., 0.125, 0.5, 0.75, 0.25])?;
+	let detections = postprocess::decode_yolo(&anchor_free, &YoloLayout::AnchorFree, input_size, 0.4)?;
+	assert_eq!(2, detections.len());
+	assert_eq!((1, 0.75), (detections[0].class_id, detections[0].confidence));
+	assert_eq!(Rect2f::new(28., 14., 8., 4.), detections[0].rect);
+	assert_eq!((0, 0.5), (detections[1].class_id, detections[1].confidence));
+	assert_eq!(Rect2f::new(6., 6., 4., 4.), detections[1].rect);
+
+	// single anchor on a 2x1 grid, zero logits give 0.5 after sigmoid
+	let mut grid = Tensor::<f32>::zeros(&[1, 1, 1, 2, 7])?;
+	grid[[0, 0, 0, 1, 4]] = 10.;
+	grid[[0, 0, 0, 1, 6]] = 10.;
+	let layout = YoloLayout::GridV5 {
+		stride: 16.,
+		anchors: vec![(10., 20.)],
+	};
+	let detections = postprocess::decode_yolo(&grid, &layout, input_size, 0.9)?;
+	assert_eq!(1, detections.len());
+	assert_eq!(1, detections[0].class_id);
+	assert!(detections[0].confidence > 0.99);
+	assert_eq!(Rect2f::new(19., -2., 10., 20.), detections[0].rect);
+	// same data in the channels-first layout
+	let layout = YoloLayout::GridV3 {
+		stride: 16.,
+		anchors: vec![(10., 20.)],
+	};
+	let mut grid = Tensor::<f32>::zeros(&[1, 7, 1, 2])?;
+	grid[[0, 4, 0, 1]] = 10.;
+	grid[[0, 6, 0, 1]] = 10.;
+	let detections = postprocess::decode_yolo(&grid, &layout, input_size, 0.9)?;
+	assert_eq!(1, detections.len());
+	assert_eq!(Rect2f::new(19., -2., 10., 20.), detections[0].rect);
+	assert_matches!(
+		postprocess::decode_yolo(
+			&grid,
+			&YoloLayout::GridV3 {
+				stride: 16.,
+				anchors: vec![(1., 1.), (2., 2.)]
+			},
+			input_size,
+			0.9
+		),
+		Err(Error {
+			code: core::StsUnmatchedSizes,
+			..
+		})
+	);
+
+	// single prior box at the center of 16x16 input
+	let config = RetinaFaceConfig {
+		min_sizes: vec![vec![8.]],
+		steps: vec![16.],
+		variance: [0.1, 0.2],
+	};
+	let input_size = Size::new(16, 16);
+	let loc = Tensor::from_slice(&[1, 1, 4], &[10., 0., 0., 0.])?;
+	let conf = Tensor::from_slice(&[1, 1, 2], &[0.25, 0.75])?;
+	let landmarks = Tensor::from_slice(&[1, 1, 10], &[0., 0., 10., 10., 0., 0., 0., 0., 0., 0.])?;
+	let detections = postprocess::decode_retinaface(&loc, &conf, Some(&landmarks), input_size, &config, 0.5)?;
+	assert_eq!(1, detections.len());
+	assert_eq!(0.75, detections[0].confidence);
+	assert_eq!(Rect2f::new(12., 4., 8., 8.), detections[0].rect);
+	assert_eq!(5, detections[0].landmarks.len());
+	assert_eq!(Point2f::new(8., 8.), detections[0].landmarks[0]);
+	assert_eq!(Point2f::new(16., 16.), detections[0].landmarks[1]);
+	assert!(postprocess::decode_retinaface(&loc, &conf, None, input_size, &config, 0.9)?.is_empty());
+	assert!(postprocess::decode_retinaface(&loc, &conf, None, input_size, &RetinaFaceConfig::default(), 0.5).is_err());
+
+	// 2x1 cells with 2 anchors each for stride 8
+	let scores = Tensor::from_slice(&[4, 1], &[0.25, 0.5, 0.75, 0.])?;
+	let boxes = Tensor::from_slice(&[4, 4], &[1.; 16])?;
+	let keypoints = Tensor::from_slice(&[4, 10], &[0.5; 40])?;
+	let level = ScrfdLevel {
+		stride: 8,
+		scores: &scores,
+		boxes: &boxes,
+		landmarks: Some(&keypoints),
+	};
+	let detections = postprocess::decode_scrfd(&[level], Size::new(16, 8), 2, 0.5)?;
+	assert_eq!(2, detections.len());
+	assert_eq!(Rect2f::new(-8., -8., 16., 16.), detections[0].rect);
+	assert_eq!(Rect2f::new(0., -8., 16., 16.), detections[1].rect);
+	assert_eq!(0.75, detections[1].confidence);
+	assert_eq!(Point2f::new(12., 4.), detections[1].landmarks[4]);
+	Ok(())
+}
+
+#[test]
+#[cfg(not(ocvrs_opencv_branch_32))]
+fn postprocess_nms() -> Result<()> {
+	let detection = |class_id, confidence, x| Detection {
+		class_id,
+		confidence,
+		rect: Rect2f::new(x, 0., 10., 10.),
+		landmarks: vec![],
+	};
+	let detections = [
+		detection(0, 0.5, 0.),
+		detection(0, 0.75, 1.),
+		detection(1, 0.25, 0.),
+		detection(1, 0.9, 50.),
+		detection(2, 0.1, 0.),
+	];
+	let kept = postprocess::nms_by_class(&detections, 0.2, 0.5, 0)?;
+	assert_eq!(
+		vec![detections[3].clone(), detections[1].clone(), detections[2].clone()],
+		kept
+	);
+	assert_eq!(2, postprocess::nms_by_class(&detections, 0.2, 0.5, 2)?.len());
+
+	let boxes = Vector::<RotatedRect>::from_iter([
+		RotatedRect::new(Point2f::new(5., 5.), Size2f::new(10., 10.), 0.)?,
+		RotatedRect::new(Point2f::new(5., 5.), Size2f::new(10., 10.), 5.)?,
+		RotatedRect::new(Point2f::new(5., 5.), Size2f::new(10., 10.), 0.)?,
+	]);
+	let kept = postprocess::nms_rotated_by_class(&boxes, &[0.5, 0.75, 0.25], &[0, 0, 1], 0.2, 0.5)?;
+	assert_eq!(vec![1, 2], kept);
+	assert!(postprocess::nms_rotated_by_class(&boxes, &[0.5], &[0, 0, 1], 0.2, 0.5).is_err());
+	Ok(())
+}
+
+#[test]
+#[cfg(ocvrs_has_module_imgproc)]
+fn preprocessor() -> Result<()> {
+	use opencv::{
+		core::{Point, Rect, Scalar, Vec3b},
+		dnn::{BlobDepth, BlobLayout, Preprocessor},
+		imgproc,
+	};
+
+	let image = Mat::new_rows_cols_with_default(2, 4, Vec3b::typ(), Scalar::new(10., 20., 30., 0.))?;
+	let letterbox = Preprocessor::new(Size::new(4, 4))
+		.resize_mode(ResizeMode::Letterbox)
+		.pad_color(Scalar::all(114.))
+		.interpolation(imgproc::INTER_NEAREST);
+	let (blob, transform) = letterbox.process(&image)?;
+	assert_eq!(&[1, 3, 4, 4], &*blob.mat_size());
+	assert_eq!(core::CV_32F, blob.typ());
+	let data = blob.data_typed::<f32>()?;
+	// padding rows at the top and the bottom of each channel
+	assert_eq!(&[114.; 4], &data[..4]);
+	assert_eq!(&[10.; 4], &data[4..8]);
+	assert_eq!(&[114.; 4], &data[12..16]);
+	assert_eq!(&[30.; 4], &data[36..40]);
+	assert_eq!(Size::new(4, 2), transform.source_size());
+	assert_eq!(Point2f::new(2., 0.), transform.to_source(Point2f::new(2., 1.)));
+	assert_eq!(Point::new(1, 2), transform.to_blob(Point::new(1, 1)));
+	assert_eq!(Rect::new(0, 0, 4, 2), transform.to_source(Rect::new(0, 0, 4, 4)));
+
+	let normalize = Preprocessor::new(Size::new(2, 2))
+		.swap_rb(true)
+		.scale(0.5)
+		.mean(Scalar::new(1., 2., 3., 0.))
+		.std(Scalar::all(2.));
+	let (blob, transform) = normalize.process(&image)?;
+	assert_eq!(&[1, 3, 2, 2], &*blob.mat_size());
+	assert_eq!(&[7., 7., 7., 7., 4., 4., 4., 4., 1., 1., 1., 1.], blob.data_typed::<f32>()?);
+	assert_eq!(Point2f::new(4., 1.), transform.to_source(Point2f::new(2., 1.)));
+
+	let (blob, _) = normalize.clone().layout(BlobLayout::Nhwc).process(&image)?;
+	assert_eq!(&[1, 2, 2, 3], &*blob.mat_size());
+	assert_eq!(&[7., 4., 1., 7., 4., 1., 7., 4., 1., 7., 4., 1.], blob.data_typed::<f32>()?);
+
+	let (blob, _) = normalize.depth(BlobDepth::U8).process(&image)?;
+	assert_eq!(core::CV_8U, blob.depth());
+	assert_eq!(&[7, 7, 7, 7, 4, 4, 4, 4, 1, 1, 1, 1], blob.data_bytes()?);
+
+	// batch of images with different sizes
+	let tall = Mat::new_rows_cols_with_default(8, 2, Vec3b::typ(), Scalar::all(0.))?;
+	let crop = Preprocessor::new(Size::new(2, 2)).resize_mode(ResizeMode::Crop);
+	let (blob, transforms) = crop.process_batch(&[&image, &tall])?;
+	assert_eq!(&[2, 3, 2, 2], &*blob.mat_size());
+	assert_eq!(2, transforms.len());
+	assert_eq!(Point2f::new(1., 0.), transforms[0].to_source(Point2f::new(0., 0.)));
+	assert_eq!(Point2f::new(0., 3.), transforms[1].to_source(Point2f::new(0., 0.)));
+	assert_matches!(
+		crop.process_batch(&[]),
+		Err(Error {
+			code: core::StsBadArg,
+			..
+		})
+	);
+	// batch mixing the number of channels
+	let gray = Mat::new_rows_cols_with_default(2, 2, u8::typ(), Scalar::all(0.))?;
+	for &layout in [BlobLayout::Nchw, BlobLayout::Nhwc].iter() {
+		assert_matches!(
+			crop.clone().layout(layout).process_batch(&[&image, &gray]),
+			Err(Error {
+				code: core::StsUnmatchedSizes,
+				..
+			})
+		);
+	}
+	Ok(())
+}
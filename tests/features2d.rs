@@ -2,7 +2,14 @@
 
 use std::path::PathBuf;
 
-use opencv::{core::Size, features2d::ORB, imgcodecs, prelude::*, types::VectorOfKeyPoint, Result};
+use opencv::{
+	core::{self, KeyPoint, Point2f, Ptr, Scalar, Size, Vector, _InputArray, _OutputArray},
+	features2d::{Feature2D, Feature2DImpl, ORB},
+	imgcodecs,
+	prelude::*,
+	types::VectorOfKeyPoint,
+	Result,
+};
 
 #[test]
 fn orb() -> Result<()> {
@@ -21,3 +28,69 @@ fn orb() -> Result<()> {
 	assert_eq!(Size::new(32, size as i32), des.size()?);
 	Ok(())
 }
+
+/// Detects the image corners as keypoints and describes them with their coordinates
+struct Corners;
+
+impl Feature2DImpl for Corners {
+	fn detect_and_compute(
+		&mut self,
+		image: &_InputArray,
+		_mask: &_InputArray,
+		keypoints: &mut Vector<KeyPoint>,
+		descriptors: &mut _OutputArray,
+		use_provided_keypoints: bool,
+	) -> Result<()> {
+		if !use_provided_keypoints {
+			let size = image.size(-1)?;
+			keypoints.clear();
+			for &(x, y) in &[
+				(0, 0),
+				(size.width - 1, 0),
+				(0, size.height - 1),
+				(size.width - 1, size.height - 1),
+			] {
+				keypoints.push(KeyPoint::new_point(Point2f::new(x as f32, y as f32), 1., -1., 0., 0, -1)?);
+			}
+		}
+		if descriptors.needed()? {
+			let mut desc = Mat::new_rows_cols_with_default(keypoints.len() as i32, 2, f32::typ(), Scalar::all(0.))?;
+			for (i, kp) in keypoints.iter().enumerate() {
+				*desc.at_2d_mut::<f32>(i as i32, 0)? = kp.pt.x;
+				*desc.at_2d_mut::<f32>(i as i32, 1)? = kp.pt.y;
+			}
+			desc.copy_to(descriptors)?;
+		}
+		Ok(())
+	}
+
+	fn descriptor_size(&self) -> i32 {
+		2
+	}
+
+	fn descriptor_type(&self) -> i32 {
+		f32::typ()
+	}
+}
+
+#[test]
+fn feature2d_rust_impl() -> Result<()> {
+	let mut corners = Ptr::<Feature2D>::from_rust(Corners)?;
+	assert_eq!(2, corners.descriptor_size()?);
+	assert_eq!(f32::typ(), corners.descriptor_type()?);
+	assert_eq!(core::NORM_L2, corners.default_norm()?);
+
+	let img = Mat::new_rows_cols_with_default(30, 40, u8::typ(), Scalar::all(0.))?;
+	let mut kp = VectorOfKeyPoint::new();
+	corners.detect(&img, &mut kp, &Mat::default())?;
+	assert_eq!(4, kp.len());
+	assert_eq!(Point2f::new(39., 29.), kp.get(3)?.pt);
+
+	let mut kp = kp.into_iter().skip(1).collect::<VectorOfKeyPoint>();
+	let mut des = Mat::default();
+	corners.compute(&img, &mut kp, &mut des)?;
+	assert_eq!(3, kp.len());
+	assert_eq!(Size::new(2, 3), des.size()?);
+	assert_eq!(29., *des.at_2d::<f32>(1, 1)?);
+	Ok(())
+}
@@ -0,0 +1,128 @@
+#![cfg(ocvrs_has_module_highgui)]
+
+use opencv::{
+	core::Point,
+	highgui::{KeyCode, KeyEvent, KeyModifiers, MouseEventFlags, MouseEventTypes, MouseFlags, WindowEvent},
+};
+
+#[test]
+fn mouse_flags() {
+	let flags = MouseFlags::from_bits(MouseEventFlags::EVENT_FLAG_LBUTTON as i32 | MouseEventFlags::EVENT_FLAG_CTRLKEY as i32);
+	assert!(flags.contains(MouseEventFlags::EVENT_FLAG_LBUTTON));
+	assert!(flags.contains(MouseEventFlags::EVENT_FLAG_CTRLKEY));
+	assert!(!flags.contains(MouseEventFlags::EVENT_FLAG_RBUTTON));
+	assert_eq!(0, flags.wheel_delta());
+	assert_eq!("{EVENT_FLAG_LBUTTON, EVENT_FLAG_CTRLKEY}", format!("{:?}", flags));
+
+	// wheel delta is stored in the upper 16 bits
+	let wheel = MouseFlags::from_bits((-120 << 16) | MouseEventFlags::EVENT_FLAG_SHIFTKEY as i32);
+	assert_eq!(-120, wheel.wheel_delta());
+	assert!(wheel.contains(MouseEventFlags::EVENT_FLAG_SHIFTKEY));
+	assert!(!wheel.contains(MouseEventFlags::EVENT_FLAG_ALTKEY));
+
+	let event = WindowEvent::Mouse {
+		kind: MouseEventTypes::EVENT_MOUSEWHEEL,
+		pos: Point::new(1, 2),
+		flags: wheel,
+	};
+	assert_ne!(WindowEvent::Closed, event);
+}
+
+#[test]
+fn key_event() {
+	const NONE: KeyModifiers = KeyModifiers {
+		shift: false,
+		ctrl: false,
+		alt: false,
+	};
+	const SHIFT: KeyModifiers = KeyModifiers {
+		shift: true,
+		ctrl: false,
+		alt: false,
+	};
+	const CTRL_ALT: KeyModifiers = KeyModifiers {
+		shift: false,
+		ctrl: true,
+		alt: true,
+	};
+	let cases = [
+		// common
+		(27, KeyCode::Escape, NONE),
+		(13, KeyCode::Enter, NONE),
+		(9, KeyCode::Tab, NONE),
+		(8, KeyCode::Backspace, NONE),
+		(0x61, KeyCode::Char('a'), NONE),
+		(0x20, KeyCode::Char(' '), NONE),
+		(0xE9, KeyCode::Char('é'), NONE),
+		// GTK, keysyms with the modifier state in the upper 16 bits, NumLock (Mod2) is ignored
+		(0xFF51, KeyCode::Left, NONE),
+		(0xFF52, KeyCode::Up, NONE),
+		(0xFF53, KeyCode::Right, NONE),
+		(0xFF54, KeyCode::Down, NONE),
+		(0xFF50, KeyCode::Home, NONE),
+		(0xFF57, KeyCode::End, NONE),
+		(0xFF55, KeyCode::PageUp, NONE),
+		(0xFF56, KeyCode::PageDown, NONE),
+		(0xFF63, KeyCode::Insert, NONE),
+		(0xFFFF, KeyCode::Delete, NONE),
+		(0xFF08, KeyCode::Backspace, NONE),
+		(0xFFBE, KeyCode::F(1), NONE),
+		(0xFFC9, KeyCode::F(12), NONE),
+		(0x0001_0041, KeyCode::Char('A'), SHIFT),
+		(0x0010_0061, KeyCode::Char('a'), NONE),
+		(0x000C_FF51, KeyCode::Left, CTRL_ALT),
+		(0x0001_001B, KeyCode::Escape, SHIFT),
+		(0xFFE1, KeyCode::Unknown(0xFFE1), NONE),
+		// Qt
+		(0x0100_0012, KeyCode::Left, NONE),
+		(0x0100_0013, KeyCode::Up, NONE),
+		(0x0100_0014, KeyCode::Right, NONE),
+		(0x0100_0015, KeyCode::Down, NONE),
+		(0x0100_0010, KeyCode::Home, NONE),
+		(0x0100_0011, KeyCode::End, NONE),
+		(0x0100_0016, KeyCode::PageUp, NONE),
+		(0x0100_0017, KeyCode::PageDown, NONE),
+		(0x0100_0006, KeyCode::Insert, NONE),
+		(0x0100_0007, KeyCode::Delete, NONE),
+		(0x0100_0000, KeyCode::Escape, NONE),
+		(0x0100_0004, KeyCode::Enter, NONE),
+		(0x0100_0030, KeyCode::F(1), NONE),
+		(0x0100_003B, KeyCode::F(12), NONE),
+		// Win32, virtual key codes shifted by 16
+		(0x0025_0000, KeyCode::Left, NONE),
+		(0x0026_0000, KeyCode::Up, NONE),
+		(0x0027_0000, KeyCode::Right, NONE),
+		(0x0028_0000, KeyCode::Down, NONE),
+		(0x0024_0000, KeyCode::Home, NONE),
+		(0x0023_0000, KeyCode::End, NONE),
+		(0x0021_0000, KeyCode::PageUp, NONE),
+		(0x0022_0000, KeyCode::PageDown, NONE),
+		(0x002D_0000, KeyCode::Insert, NONE),
+		(0x002E_0000, KeyCode::Delete, NONE),
+		(0x0070_0000, KeyCode::F(1), NONE),
+		(0x007B_0000, KeyCode::F(12), NONE),
+		// Cocoa
+		(0xF702, KeyCode::Left, NONE),
+		(0xF700, KeyCode::Up, NONE),
+		(0xF703, KeyCode::Right, NONE),
+		(0xF701, KeyCode::Down, NONE),
+		(0xF729, KeyCode::Home, NONE),
+		(0xF72B, KeyCode::End, NONE),
+		(0xF72C, KeyCode::PageUp, NONE),
+		(0xF72D, KeyCode::PageDown, NONE),
+		(0xF728, KeyCode::Delete, NONE),
+		(0xF704, KeyCode::F(1), NONE),
+		(0xF70F, KeyCode::F(12), NONE),
+		(127, KeyCode::Backspace, NONE),
+	];
+	for &(raw, code, modifiers) in cases.iter() {
+		assert_eq!(
+			Some(KeyEvent { code, modifiers, raw }),
+			KeyEvent::from_raw(raw),
+			"raw code: {:#x}",
+			raw
+		);
+	}
+	assert_eq!(None, KeyEvent::from_raw(-1));
+	assert_eq!(Some(false), KeyEvent::from_raw(0x0004_0061).map(|key| key.modifiers.is_empty()));
+}
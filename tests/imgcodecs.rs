@@ -1,12 +1,19 @@
 #![cfg(ocvrs_has_module_imgcodecs)]
 
-use std::ffi::c_void;
+use std::{env, ffi::c_void, fs};
 
+use matches::assert_matches;
+
+#[cfg(ocvrs_opencv_4_5_2)]
+use opencv::imgcodecs::MultiPageReader;
 use opencv::{
 	core::{self, Size, Vec3b},
-	imgcodecs,
+	imgcodecs::{
+		self, Dpi, EncodeParams, ExrCompression, ExrParams, GpsPosition, ImageFormat, ImageMetadata, ImreadModes, ImwritePNGFlags,
+		JpegChromaSubsampling, JpegParams, MultiPageWriter, Orientation, PngParams, TiffCompression, TiffParams, WebpParams,
+	},
 	prelude::*,
-	Result,
+	Error, Result,
 };
 
 const PIXEL: &[u8] = include_bytes!("pixel.png");
@@ -41,3 +48,380 @@ fn decode() -> Result<()> {
 
 	Ok(())
 }
+
+#[test]
+#[cfg(feature = "async")]
+fn encode_async() -> Result<()> {
+	let src = imgcodecs::imdecode(&Mat::from_slice::<u8>(PIXEL)?, imgcodecs::IMREAD_COLOR)?;
+	let encoded = pollster::block_on(imgcodecs::imencode_async(".png", src, core::Vector::new()))?;
+	let dest = imgcodecs::imdecode(&encoded, imgcodecs::IMREAD_COLOR)?;
+	assert_eq!(*dest.at_2d::<Vec3b>(0, 0)?, Vec3b::from([56u8, 56, 191]));
+	let err = pollster::block_on(imgcodecs::imencode_async(".png", Mat::default(), core::Vector::new()));
+	assert!(err.is_err());
+	Ok(())
+}
+
+#[test]
+fn decode_slice() -> Result<()> {
+	let img = imgcodecs::decode(PIXEL, ImreadModes::IMREAD_COLOR)?;
+	assert_eq!(Size::new(1, 1), img.size()?);
+	assert_eq!(*img.at_2d::<Vec3b>(0, 0)?, Vec3b::from([56u8, 56, 191]));
+	let gray = imgcodecs::decode(PIXEL, ImreadModes::IMREAD_GRAYSCALE)?;
+	assert_eq!(1, gray.channels());
+
+	assert_matches!(imgcodecs::decode(&[], ImreadModes::IMREAD_COLOR), Err(Error { code: core::StsBadArg, .. }));
+	assert_matches!(
+		imgcodecs::decode(b"not an image", ImreadModes::IMREAD_COLOR),
+		Err(Error { code: core::StsError, .. })
+	);
+	Ok(())
+}
+
+#[test]
+fn encode_to_writer() -> Result<()> {
+	let img = imgcodecs::decode(PIXEL, ImreadModes::IMREAD_COLOR)?;
+	let mut png = vec![];
+	imgcodecs::encode_to(
+		&mut png,
+		".png",
+		&img,
+		&EncodeParams::Png(PngParams {
+			compression: Some(9),
+			strategy: Some(ImwritePNGFlags::IMWRITE_PNG_STRATEGY_RLE),
+			..PngParams::default()
+		}),
+	)?;
+	assert!(png.starts_with(b"\x89PNG"));
+	let decoded = imgcodecs::decode(&png, ImreadModes::IMREAD_COLOR)?;
+	assert_eq!(*decoded.at_2d::<Vec3b>(0, 0)?, Vec3b::from([56u8, 56, 191]));
+
+	let mut jpeg = vec![];
+	imgcodecs::encode_to(&mut jpeg, ".JPG", &img, &EncodeParams::default())?;
+	assert!(jpeg.starts_with(b"\xff\xd8"));
+
+	assert_matches!(
+		imgcodecs::encode_to(vec![], ".jpg", &img, &EncodeParams::Png(PngParams::default())),
+		Err(Error { code: core::StsBadArg, .. })
+	);
+	assert_matches!(
+		imgcodecs::encode_to(vec![], ".png", &Mat::default(), &EncodeParams::Default),
+		Err(Error { .. })
+	);
+	Ok(())
+}
+
+#[test]
+fn encode_params() -> Result<()> {
+	assert!(EncodeParams::Default.to_vector()?.is_empty());
+	let jpeg = JpegParams {
+		quality: Some(80),
+		progressive: true,
+		optimize: false,
+		..JpegParams::default()
+	};
+	assert_eq!(
+		vec![
+			imgcodecs::IMWRITE_JPEG_QUALITY,
+			80,
+			imgcodecs::IMWRITE_JPEG_PROGRESSIVE,
+			1,
+			imgcodecs::IMWRITE_JPEG_OPTIMIZE,
+			0,
+		],
+		EncodeParams::Jpeg(jpeg).to_vector()?.to_vec()
+	);
+	let jpeg = EncodeParams::Jpeg(JpegParams {
+		chroma_subsampling: Some(JpegChromaSubsampling::S444),
+		..jpeg
+	});
+	#[cfg(ocvrs_opencv_4_7_0)]
+	assert_eq!(
+		&[imgcodecs::IMWRITE_JPEG_SAMPLING_FACTOR, 0x111111],
+		&jpeg.to_vector()?.to_vec()[6..]
+	);
+	#[cfg(not(ocvrs_opencv_4_7_0))]
+	assert_matches!(
+		jpeg.to_vector(),
+		Err(Error {
+			code: core::StsNotImplemented,
+			..
+		})
+	);
+	let exr = EncodeParams::Exr(ExrParams {
+		compression: Some(ExrCompression::Piz),
+		..ExrParams::default()
+	});
+	#[cfg(ocvrs_opencv_4_5_5)]
+	assert_eq!(vec![imgcodecs::IMWRITE_EXR_COMPRESSION, 4], exr.to_vector()?.to_vec());
+	#[cfg(not(ocvrs_opencv_4_5_5))]
+	assert_matches!(
+		exr.to_vector(),
+		Err(Error {
+			code: core::StsNotImplemented,
+			..
+		})
+	);
+	assert_eq!(
+		vec![imgcodecs::IMWRITE_WEBP_QUALITY, 101],
+		EncodeParams::Webp(WebpParams::default()).to_vector()?.to_vec()
+	);
+	assert_eq!(
+		vec![imgcodecs::IMWRITE_TIFF_COMPRESSION, 8],
+		EncodeParams::Tiff(TiffParams {
+			compression: Some(TiffCompression::Deflate)
+		})
+		.to_vector()?
+		.to_vec()
+	);
+	assert_matches!(
+		EncodeParams::Png(PngParams {
+			compression: Some(10),
+			..PngParams::default()
+		})
+		.to_vector(),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+	assert_matches!(
+		EncodeParams::Webp(WebpParams { quality: Some(0) }).to_vector(),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+	Ok(())
+}
+
+#[test]
+fn multi_page() -> Result<()> {
+	let path = env::temp_dir().join("opencv-rust-multi-page.tiff");
+	let path = path.to_str().expect("Temp dir path must be valid UTF-8");
+	let mut writer = MultiPageWriter::new(
+		path,
+		EncodeParams::Tiff(TiffParams {
+			compression: Some(TiffCompression::Lzw),
+		}),
+	)?;
+	for i in 0..3 {
+		writer.push(Mat::new_rows_cols_with_default(4 + i, 5, core::CV_8UC1, core::Scalar::all(f64::from(i * 10)))?)?;
+	}
+	assert_matches!(writer.push(Mat::default()), Err(Error { code: core::StsBadArg, .. }));
+	assert_eq!(3, writer.len());
+	writer.finish()?;
+
+	#[cfg(ocvrs_opencv_4_5_2)]
+	{
+		let reader = MultiPageReader::open_with_flags(path, ImreadModes::IMREAD_UNCHANGED)?;
+		assert_eq!(3, reader.len());
+		let page = reader.get(2)?;
+		assert_eq!(Size::new(5, 6), page.size()?);
+		assert_eq!(20, *page.at_2d::<u8>(0, 0)?);
+		assert_matches!(
+			reader.get(3),
+			Err(Error {
+				code: core::StsOutOfRange,
+				..
+			})
+		);
+		let heights = reader.iter().map(|page| page.and_then(|p| p.size()).map(|s| s.height)).collect::<Result<Vec<_>>>()?;
+		assert_eq!(vec![4, 5, 6], heights);
+		assert_eq!(6, reader.iter().next_back().expect("Last page must exist")?.rows());
+		assert_eq!(1, reader.iter().skip(2).len());
+		let mut batched = reader.iter_batched(2);
+		assert_eq!(4, batched.next().expect("First page must exist")?.rows());
+		assert_eq!(6, batched.next_back().expect("Last page must exist")?.rows());
+		assert_eq!(5, batched.next().expect("Second page must exist")?.rows());
+		assert!(batched.next().is_none());
+		assert_eq!(6, reader.iter_batched(0).nth(2).expect("Last page must exist")?.rows());
+		assert!(MultiPageReader::open("non-existent.tiff").is_err());
+	}
+	fs::remove_file(path).ok();
+
+	assert_matches!(
+		MultiPageWriter::new("out.png", EncodeParams::Tiff(TiffParams::default())),
+		Err(Error { code: core::StsBadArg, .. })
+	);
+	assert_matches!(
+		MultiPageWriter::new("out.tiff", EncodeParams::Default)?.finish(),
+		Err(Error { code: core::StsBadArg, .. })
+	);
+	// the dot in the directory name is not an extension
+	assert!(MultiPageWriter::new("out.d/pages", EncodeParams::Tiff(TiffParams::default())).is_ok());
+	Ok(())
+}
+
+#[test]
+fn metadata() -> Result<()> {
+	let pixel = ImageMetadata::parse(PIXEL)?;
+	assert!((pixel.dpi.expect("pHYs must be parsed").x - 72.).abs() < 0.01);
+	assert_eq!(None, pixel.orientation);
+	assert!(ImageMetadata::parse(b"not an image")?.is_empty());
+	assert_matches!(
+		ImageMetadata::parse(&[0xFF, 0xD8, 0xFF, 0xE1, 0x00]),
+		Err(Error {
+			code: core::StsParseError,
+			..
+		})
+	);
+
+	let img = Mat::from_slice_2d(&[[1u8, 2, 3], [4, 5, 6]])?;
+	let metadata = ImageMetadata {
+		orientation: Some(Orientation::Rotate90),
+		datetime: Some("2022:10:01 12:34:56".to_string()),
+		camera_make: Some("Maker".to_string()),
+		camera_model: Some("Model 1".to_string()),
+		gps: Some(GpsPosition {
+			latitude: -33.8568,
+			longitude: 151.2153,
+			altitude: Some(-4.5),
+		}),
+		dpi: Some(Dpi { x: 300., y: 150. }),
+		// spans multiple JPEG segments
+		icc_profile: Some((0..=255).cycle().take(70_000).collect()),
+		text: vec![
+			("Comment".to_string(), "Zoë".to_string()),
+			("Title".to_string(), "Привет".to_string()),
+		],
+	};
+	for ext in [".png", ".jpg"] {
+		let buf = imgcodecs::encode_with_metadata(ext, &img, &EncodeParams::Default, &metadata)?;
+		let (decoded, parsed) = imgcodecs::decode_with_metadata(&buf, ImreadModes::IMREAD_GRAYSCALE)?;
+		assert_eq!(Size::new(3, 2), decoded.size()?, "{}", ext);
+		assert_eq!(metadata.orientation, parsed.orientation);
+		assert_eq!(metadata.datetime, parsed.datetime);
+		assert_eq!(metadata.camera_make, parsed.camera_make);
+		assert_eq!(metadata.camera_model, parsed.camera_model);
+		assert_eq!(metadata.dpi, parsed.dpi);
+		assert_eq!(metadata.icc_profile, parsed.icc_profile);
+		let gps = parsed.gps.expect("GPS must be parsed");
+		assert!((gps.latitude + 33.8568).abs() < 1e-6);
+		assert!((gps.longitude - 151.2153).abs() < 1e-6);
+		assert_eq!(Some(-4.5), gps.altitude);
+		if ext == ".png" {
+			assert_eq!(metadata.text, parsed.text);
+		} else {
+			let comments = parsed
+				.text
+				.iter()
+				.map(|(key, value)| (key.as_str(), value.as_str()))
+				.collect::<Vec<_>>();
+			assert_eq!(vec![("Comment", "Zoë"), ("Comment", "Привет")], comments);
+		}
+	}
+	assert_matches!(
+		imgcodecs::encode_with_metadata(".bmp", &img, &EncodeParams::Default, &metadata),
+		Err(Error {
+			code: core::StsNotImplemented,
+			..
+		})
+	);
+	let bad_keyword = ImageMetadata {
+		text: vec![("".to_string(), "value".to_string())],
+		..ImageMetadata::default()
+	};
+	assert_matches!(
+		imgcodecs::encode_with_metadata(".png", &img, &EncodeParams::Default, &bad_keyword),
+		Err(Error {
+			code: core::StsBadArg,
+			..
+		})
+	);
+
+	let path = env::temp_dir().join("opencv-rust-metadata.jpg");
+	let path = path.to_str().expect("Temp dir path must be valid UTF-8");
+	imgcodecs::write_with_metadata(path, &img, &EncodeParams::Default, &metadata)?;
+	let (mut read, parsed) = imgcodecs::read_with_metadata(path)?;
+	assert_eq!(3, read.channels());
+	imgcodecs::apply_orientation(&mut read, parsed.orientation.unwrap_or_default())?;
+	assert_eq!(Size::new(2, 3), read.size()?);
+	fs::remove_file(path).ok();
+	Ok(())
+}
+
+#[test]
+fn apply_orientation() -> Result<()> {
+	let src = Mat::from_slice_2d(&[[1u8, 2, 3], [4, 5, 6]])?;
+	let cases: [(Orientation, &[&[u8]]); 8] = [
+		(Orientation::Normal, &[&[1, 2, 3], &[4, 5, 6]]),
+		(Orientation::FlipHorizontal, &[&[3, 2, 1], &[6, 5, 4]]),
+		(Orientation::Rotate180, &[&[6, 5, 4], &[3, 2, 1]]),
+		(Orientation::FlipVertical, &[&[4, 5, 6], &[1, 2, 3]]),
+		(Orientation::Transpose, &[&[1, 4], &[2, 5], &[3, 6]]),
+		(Orientation::Rotate90, &[&[4, 1], &[5, 2], &[6, 3]]),
+		(Orientation::Transverse, &[&[6, 3], &[5, 2], &[4, 1]]),
+		(Orientation::Rotate270, &[&[3, 6], &[2, 5], &[1, 4]]),
+	];
+	for (orientation, expected) in cases.iter() {
+		let mut img = src.clone();
+		imgcodecs::apply_orientation(&mut img, *orientation)?;
+		assert_eq!(*expected, img.to_vec_2d::<u8>()?, "{:?}", orientation);
+		assert_eq!(orientation.swaps_dimensions(), img.rows() == 3);
+		assert_eq!(Some(*orientation), Orientation::from_exif(orientation.to_exif()));
+	}
+	assert_eq!(None, Orientation::from_exif(0));
+	Ok(())
+}
+
+#[test]
+fn probe() -> Result<()> {
+	let pixel = imgcodecs::probe(PIXEL)?;
+	assert_eq!(ImageFormat::Png, pixel.format);
+	assert_eq!((1, 1, 1), (pixel.width, pixel.height, pixel.page_count));
+	assert_eq!(core::CV_8UC3, pixel.typ());
+
+	let cases = [
+		(".jpg", ImageFormat::Jpeg, core::CV_8UC3),
+		(".png", ImageFormat::Png, core::CV_16UC4),
+		(".tiff", ImageFormat::Tiff, core::CV_16UC1),
+		(".bmp", ImageFormat::Bmp, core::CV_8UC3),
+		(".pgm", ImageFormat::Pnm, core::CV_16UC1),
+		(".ppm", ImageFormat::Pnm, core::CV_8UC3),
+		(".hdr", ImageFormat::Hdr, core::CV_32FC3),
+	];
+	for &(ext, format, typ) in cases.iter() {
+		let img = Mat::new_rows_cols_with_default(7, 5, typ, core::Scalar::all(1.))?;
+		let mut buf = vec![];
+		imgcodecs::encode_to(&mut buf, ext, &img, &EncodeParams::Default)?;
+		let info = imgcodecs::probe(&buf)?;
+		let decoded = imgcodecs::decode(&buf, ImreadModes::IMREAD_UNCHANGED)?;
+		assert_eq!(format, info.format, "{}", ext);
+		assert_eq!(decoded.size()?, info.size()?, "{}", ext);
+		assert_eq!(decoded.typ(), info.typ(), "{}", ext);
+		assert_eq!(1, info.page_count);
+	}
+
+	assert_matches!(
+		imgcodecs::probe(b"not an image"),
+		Err(Error {
+			code: core::StsUnsupportedFormat,
+			..
+		})
+	);
+	assert_matches!(
+		imgcodecs::probe(&PIXEL[..20]),
+		Err(Error {
+			code: core::StsParseError,
+			..
+		})
+	);
+	// EXR headers with the data window that is inverted or too large for u32
+	for &(min, max) in [(10, 2), (i32::MIN, i32::MAX)].iter() {
+		let mut exr = vec![0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0];
+		exr.extend_from_slice(b"dataWindow\0box2i\0");
+		exr.extend_from_slice(&16u32.to_le_bytes());
+		for &coord in [min, 0, max, 0].iter() {
+			exr.extend_from_slice(&coord.to_le_bytes());
+		}
+		exr.push(0);
+		assert_matches!(
+			imgcodecs::probe(&exr),
+			Err(Error {
+				code: core::StsParseError,
+				..
+			})
+		);
+	}
+	Ok(())
+}
@@ -1,8 +1,8 @@
 #![cfg(ocvrs_has_module_ml)]
 
 use opencv::{
-	core::{Scalar, Size},
-	ml,
+	core::{self, Scalar, Size},
+	ml::{self, TrainDataBuilder},
 	prelude::*,
 	Result,
 };
@@ -31,3 +31,96 @@ fn knn() -> Result<()> {
 	assert_eq!(Size::new(width, 1), dist.size()?);
 	Ok(())
 }
+
+#[test]
+fn train_data_builder() -> Result<()> {
+	let mut samples = (0..20).map(|i| [i as f32, (i % 3) as f32]).collect::<Vec<_>>();
+	samples[3][0] = -1.;
+	let labels = (0..20)
+		.map(|i| {
+			if i < 10 {
+				"low"
+			} else {
+				"high"
+			}
+		})
+		.collect::<Vec<_>>();
+	let builder = TrainDataBuilder::from_rows(&samples)?
+		.categorical(&[1])
+		.missing_marker(-1.)
+		.labels(labels.iter().copied())?;
+	assert_eq!(20, builder.sample_count());
+	assert_eq!(2, builder.var_count());
+	assert_eq!(Some(0), builder.classes().id(&"low"));
+	assert_eq!(Some(&"high"), builder.classes().label(1));
+	assert_eq!(Some(&"high"), builder.classes().label_for_response(1.));
+	assert_eq!(None, builder.classes().label(2));
+
+	let data = builder.build()?;
+	assert_eq!(20, data.get_n_samples()?);
+	assert_eq!(2, data.get_n_vars()?);
+	let var_type = data.get_var_type()?;
+	assert_eq!(
+		&[ml::VAR_ORDERED as u8, ml::VAR_CATEGORICAL as u8],
+		&var_type.data_bytes()?[..2]
+	);
+	let train_samples = data.get_samples()?;
+	assert_eq!(<dyn ml::TrainData>::missing_value()?, *train_samples.at_2d::<f32>(3, 0)?);
+	assert_eq!(4., *train_samples.at_2d::<f32>(4, 0)?);
+
+	// stratified and reproducible split
+	let split = builder.split(0.2, 7)?;
+	assert_eq!(16, split.train_indices.len());
+	assert_eq!(4, split.test_indices.len());
+	assert_eq!(2, split.test_indices.iter().filter(|&&i| i < 10).count());
+	assert_eq!(4, split.test.get_n_samples()?);
+	assert_eq!(16, split.train.get_n_samples()?);
+	let same = builder.split(0.2, 7)?;
+	assert_eq!(split.test_indices, same.test_indices);
+	assert_eq!(split.train_indices, same.train_indices);
+
+	// regression
+	let data = TrainDataBuilder::from_rows(vec![vec![1., 2.], vec![3., 4.]])?
+		.responses(&[0.5, 1.5])?
+		.build()?;
+	assert_eq!(&[0.5, 1.5], data.get_responses()?.data_typed::<f32>()?);
+
+	#[cfg(feature = "ndarray")]
+	{
+		let array = ndarray::arr2(&[[1f32, 2., 3.], [4., 5., 6.]]);
+		let data = TrainDataBuilder::from_ndarray(&array.t())?
+			.responses(&[1., 2., 3.])?
+			.build()?;
+		assert_eq!(&[1., 4., 2., 5., 3., 6.], data.get_samples()?.data_typed::<f32>()?);
+	}
+
+	let err = TrainDataBuilder::from_rows(vec![vec![1., 2.], vec![3.]])
+		.map(|_| ())
+		.unwrap_err();
+	assert_eq!(core::StsUnmatchedSizes, err.code);
+	let err = TrainDataBuilder::from_rows(&samples)?.labels([1, 2]).map(|_| ()).unwrap_err();
+	assert_eq!(core::StsUnmatchedSizes, err.code);
+	let err = builder.clone().categorical(&[2]).build().map(|_| ()).unwrap_err();
+	assert_eq!(core::StsOutOfRange, err.code);
+	// categorical features must be integers and can't be missing
+	let mut invalid = samples.clone();
+	invalid[5][1] = 1.5;
+	let err = TrainDataBuilder::from_rows(&invalid)?
+		.categorical(&[1])
+		.labels(labels.iter().copied())?
+		.build()
+		.map(|_| ())
+		.unwrap_err();
+	assert_eq!(core::StsBadArg, err.code);
+	invalid[5][1] = -1.;
+	let err = TrainDataBuilder::from_rows(&invalid)?
+		.categorical(&[1])
+		.missing_marker(-1.)
+		.labels(labels.iter().copied())?
+		.build()
+		.map(|_| ())
+		.unwrap_err();
+	assert_eq!(core::StsBadArg, err.code);
+	assert_eq!(core::StsOutOfRange, builder.split(1., 7).map(|_| ()).unwrap_err().code);
+	Ok(())
+}
@@ -0,0 +1,64 @@
+#![cfg(all(ocvrs_has_module_text, ocvrs_has_module_imgproc))]
+
+use std::sync::{
+	atomic::{AtomicUsize, Ordering},
+	Arc,
+};
+
+use opencv::{
+	core::{Mat, Ptr, Rect, Scalar, Vector},
+	imgproc,
+	prelude::*,
+	text::{self, ERFilter_Callback, ERFilter_CallbackImpl, ERStat},
+	Result,
+};
+
+/// Accepts only the regions that are at least `min_area` pixels large
+struct AreaFilter {
+	min_area: i32,
+	calls: Arc<AtomicUsize>,
+}
+
+impl ERFilter_CallbackImpl for AreaFilter {
+	fn eval(&mut self, stat: &ERStat) -> Result<f64> {
+		self.calls.fetch_add(1, Ordering::Relaxed);
+		Ok(if stat.area() >= self.min_area {
+			1.
+		} else {
+			0.
+		})
+	}
+}
+
+#[test]
+fn er_filter_rust_callback() -> Result<()> {
+	let calls = Arc::new(AtomicUsize::new(0));
+	let cb = Ptr::<dyn ERFilter_Callback>::from_rust(AreaFilter {
+		min_area: 100,
+		calls: Arc::clone(&calls),
+	})?;
+	let mut filter = text::create_er_filter_nm1(&cb, 16, 0., 1., 0.5, false, 0.1)?;
+	let mut img = Mat::new_rows_cols_with_default(60, 80, u8::typ(), Scalar::all(255.))?;
+	imgproc::rectangle(
+		&mut img,
+		Rect::new(10, 10, 20, 20),
+		Scalar::all(0.),
+		imgproc::FILLED,
+		imgproc::LINE_8,
+		0,
+	)?;
+	imgproc::rectangle(
+		&mut img,
+		Rect::new(50, 10, 3, 3),
+		Scalar::all(0.),
+		imgproc::FILLED,
+		imgproc::LINE_8,
+		0,
+	)?;
+	let mut regions = Vector::<ERStat>::new();
+	filter.run(&img, &mut regions)?;
+	assert!(calls.load(Ordering::Relaxed) > 0);
+	assert!(regions.iter().skip(1).all(|stat| stat.area() >= 100));
+	assert!(regions.iter().any(|stat| stat.rect() == Rect::new(10, 10, 20, 20)));
+	Ok(())
+}
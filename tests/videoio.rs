@@ -1,8 +1,17 @@
 #![cfg(ocvrs_has_module_imgproc)]
 
+use std::{convert::TryFrom, thread, time::Duration};
+
 use matches::assert_matches;
 
-use opencv::{core, videoio::VideoWriter, Error, Result};
+use opencv::{
+	core::{self, Scalar, Size},
+	prelude::*,
+	videoio::{self, CaptureStats, DropPolicy, FourCC, FrameSource, ThreadedCapture, VideoCapture, VideoWriter},
+	Error, Result,
+};
+#[cfg(ocvrs_opencv_4_5_2)]
+use opencv::videoio::{VideoCaptureBuilder, VideoWriterBuilder};
 
 #[test]
 fn fourcc() -> Result<()> {
@@ -18,3 +27,302 @@ fn fourcc() -> Result<()> {
 	);
 	Ok(())
 }
+
+struct Counter {
+	pos: i32,
+	count: i32,
+}
+
+impl FrameSource for Counter {
+	fn read(&mut self, frame: &mut Mat) -> Result<bool> {
+		if self.pos >= self.count {
+			return Ok(false);
+		}
+		*frame = Mat::new_rows_cols_with_default(2, 3, core::CV_8UC1, Scalar::all(f64::from(self.pos)))?;
+		self.pos += 1;
+		Ok(true)
+	}
+
+	fn get(&self, prop_id: i32) -> f64 {
+		match prop_id {
+			videoio::CAP_PROP_POS_FRAMES => f64::from(self.pos),
+			videoio::CAP_PROP_FRAME_COUNT => f64::from(self.count),
+			videoio::CAP_PROP_POS_MSEC => f64::from(self.pos - 1) * 40.,
+			_ => 0.,
+		}
+	}
+
+	fn set(&mut self, prop_id: i32, value: f64) -> Result<bool> {
+		if prop_id == videoio::CAP_PROP_POS_FRAMES {
+			self.pos = value as i32;
+			Ok(true)
+		} else {
+			Ok(false)
+		}
+	}
+}
+
+#[test]
+fn capture_from_source() -> Result<()> {
+	let mut cap = VideoCapture::from_source(Counter { pos: 0, count: 3 })?;
+	assert!(cap.is_opened()?);
+	assert_eq!(3., cap.get(videoio::CAP_PROP_FRAME_COUNT)?);
+	let mut frame = Mat::default();
+	for i in 0..3 {
+		assert!(cap.read(&mut frame)?);
+		assert_eq!(Size::new(3, 2), frame.size()?);
+		assert_eq!(i as u8, *frame.at_2d::<u8>(1, 2)?);
+	}
+	assert!(!cap.read(&mut frame)?);
+	assert!(frame.empty());
+
+	assert!(cap.set(videoio::CAP_PROP_POS_FRAMES, 1.)?);
+	assert!(!cap.set(videoio::CAP_PROP_FPS, 30.)?);
+	assert!(cap.grab()?);
+	assert!(cap.retrieve(&mut frame, 0)?);
+	assert_eq!(1, *frame.at_2d::<u8>(0, 0)?);
+
+	cap.release()?;
+	assert!(!cap.is_opened()?);
+	assert!(!cap.read(&mut frame)?);
+	Ok(())
+}
+
+struct Failing;
+
+impl FrameSource for Failing {
+	fn read(&mut self, _frame: &mut Mat) -> Result<bool> {
+		Err(Error::new(core::StsError, "no camera"))
+	}
+}
+
+#[test]
+fn capture_from_source_error() -> Result<()> {
+	let mut cap = VideoCapture::from_source(Failing)?;
+	let mut frame = Mat::default();
+	let res = cap.read(&mut frame);
+	assert_matches!(res, Err(Error { code: core::StsError, ref message }) if message.contains("no camera"));
+	Ok(())
+}
+
+#[test]
+fn capture_frames() -> Result<()> {
+	let mut cap = VideoCapture::from_source(Counter { pos: 0, count: 3 })?;
+	let frames = cap.frames().collect::<Result<Vec<_>>>()?;
+	assert_eq!(3, frames.len());
+	for (i, frame) in frames.iter().enumerate() {
+		assert_eq!(i as u64, frame.index);
+		assert_eq!(i as u8, *frame.mat.at_2d::<u8>(0, 0)?);
+		assert_eq!(None, frame.hw_timestamp);
+	}
+	assert_eq!(Some(Duration::ZERO), frames[0].timestamp);
+	assert_eq!(Some(Duration::from_millis(80)), frames[2].timestamp);
+	assert!(cap.frames().next().is_none());
+
+	let mut cap = VideoCapture::from_source(Counter { pos: 0, count: 2 })?;
+	let mut buf = Mat::default();
+	let mut frames = cap.frames_into(&mut buf).with_hw_timestamp(videoio::CAP_PROP_FRAME_COUNT);
+	let mut count = 0;
+	while let Some(frame) = frames.next_frame() {
+		let frame = frame?;
+		assert_eq!(count, frame.index);
+		assert_eq!(Some(2.), frame.hw_timestamp);
+		count += 1;
+	}
+	assert_eq!(2, count);
+	assert!(frames.next_frame().is_none());
+
+	let mut cap = VideoCapture::from_source(Failing)?;
+	let mut frames = cap.frames();
+	assert_matches!(frames.next(), Some(Err(Error { code: core::StsError, .. })));
+	assert!(frames.next().is_none());
+	Ok(())
+}
+
+#[test]
+#[cfg(ocvrs_opencv_4_5_2)]
+fn typed_properties() -> Result<()> {
+	let mut cap = VideoCapture::from_source(Counter { pos: 0, count: 3 })?;
+	assert_eq!(Size::new(0, 0), cap.frame_size()?);
+	assert_eq!(None, cap.fourcc()?);
+	assert!(!cap.set_fps(25.)?);
+	assert_matches!(cap.set_fps(-1.), Err(Error { code: core::StsOutOfRange, .. }));
+	assert_matches!(cap.set_fps(f64::NAN), Err(Error { code: core::StsOutOfRange, .. }));
+	assert_matches!(
+		cap.set_frame_size(Size::new(0, 480)),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+	assert_eq!(
+		videoio::VideoAccelerationType::VIDEO_ACCELERATION_NONE,
+		cap.hw_acceleration()?
+	);
+	Ok(())
+}
+
+#[test]
+#[cfg(ocvrs_opencv_4_5_2)]
+fn builder_validation() -> Result<()> {
+	assert_matches!(
+		VideoCaptureBuilder::new().fps(0.),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+	assert_matches!(
+		VideoCaptureBuilder::new().open_timeout(Duration::from_secs(u64::MAX)),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+	assert_matches!(
+		VideoWriterBuilder::new().quality(101.),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+	let missing_fourcc = VideoWriterBuilder::new()
+		.fps(30.)?
+		.frame_size(Size::new(640, 480))?
+		.open("missing_fourcc.avi")
+		.map(|_| ());
+	assert_matches!(missing_fourcc, Err(Error { code: core::StsBadArg, ref message }) if message.contains("fourcc"));
+	Ok(())
+}
+
+#[test]
+fn fourcc_type() -> Result<()> {
+	let mjpg = "MJPG".parse::<FourCC>()?;
+	assert_eq!(FourCC::MJPG, mjpg);
+	assert_eq!(VideoWriter::fourcc('M', 'J', 'P', 'G')?, i32::from(mjpg));
+	assert_eq!("MJPG", mjpg.to_string());
+	assert_eq!("FourCC(\"avc1\")", format!("{:?}", FourCC::AVC1));
+	assert_eq!(FourCC::AVC1, FourCC::new('a', 'v', 'c', '1')?);
+	assert_eq!(b"avc1", FourCC::AVC1.as_bytes());
+
+	assert_eq!(FourCC::H264, FourCC::try_from(f64::from(FourCC::H264.code()))?);
+	assert_eq!(FourCC::MJPG, FourCC::try_from(1196444237.)?);
+	assert_matches!(FourCC::try_from(1.5), Err(Error { code: core::StsBadArg, .. }));
+	assert_matches!(FourCC::try_from(-1), Err(Error { code: core::StsBadArg, .. }));
+	assert_matches!(FourCC::try_from(0), Err(Error { code: core::StsBadArg, .. }));
+	assert_matches!(FourCC::try_from(0.), Err(Error { code: core::StsBadArg, .. }));
+
+	assert_matches!("MJP".parse::<FourCC>(), Err(Error { code: core::StsBadArg, .. }));
+	assert_matches!("MJPGG".parse::<FourCC>(), Err(Error { code: core::StsBadArg, .. }));
+	assert_matches!("MJ😀".parse::<FourCC>(), Err(Error { code: core::StsBadArg, .. }));
+	assert_matches!(FourCC::new('M', 'J', 'P', 'é'), Err(Error { code: core::StsBadArg, .. }));
+	Ok(())
+}
+
+fn wait_finished(cap: &ThreadedCapture) {
+	while !cap.is_finished() {
+		thread::sleep(Duration::from_millis(1));
+	}
+}
+
+#[test]
+fn threaded_capture() -> Result<()> {
+	let timeout = Duration::from_secs(10);
+
+	let cap = ThreadedCapture::new(VideoCapture::from_source(Counter { pos: 0, count: 5 })?, 2, DropPolicy::Block)?;
+	for i in 0..5 {
+		let frame = cap.recv_timeout(timeout)?.expect("Frame must be received");
+		assert_eq!(i, frame.index);
+		assert_eq!(i as u8, *frame.mat.at_2d::<u8>(0, 0)?);
+	}
+	// doesn't overflow computing the deadline and doesn't block once the capture has finished
+	assert!(cap.recv_timeout(Duration::MAX)?.is_none());
+	assert!(cap.is_finished());
+	assert_eq!(
+		CaptureStats {
+			captured: 5,
+			dropped: 0,
+			buffered: 0
+		},
+		cap.stats(0)
+	);
+
+	let cap = ThreadedCapture::new(VideoCapture::from_source(Counter { pos: 0, count: 10 })?, 3, DropPolicy::DropOldest)?;
+	wait_finished(&cap);
+	assert_eq!(
+		CaptureStats {
+			captured: 10,
+			dropped: 7,
+			buffered: 3
+		},
+		cap.stats(0)
+	);
+	assert_eq!(7, cap.recv_timeout(timeout)?.expect("Frame must be received").index);
+	assert_eq!(9, cap.latest().expect("Frame must be received").index);
+	assert_eq!(8, cap.stats(0).dropped);
+	assert!(cap.latest().is_none());
+
+	let cap = ThreadedCapture::new(VideoCapture::from_source(Counter { pos: 0, count: 10 })?, 3, DropPolicy::DropNewest)?;
+	wait_finished(&cap);
+	assert_eq!(7, cap.stats(0).dropped);
+	assert_eq!(0, cap.recv_timeout(timeout)?.expect("Frame must be received").index);
+
+	let cap = ThreadedCapture::new(VideoCapture::from_source(Failing)?, 1, DropPolicy::DropOldest)?;
+	assert_matches!(cap.recv_timeout(timeout), Err(Error { code: core::StsError, .. }));
+	assert!(cap.recv_timeout(timeout)?.is_none());
+	assert_matches!(
+		cap.recv_timeout_from(1, timeout),
+		Err(Error {
+			code: core::StsOutOfRange,
+			..
+		})
+	);
+
+	assert_matches!(
+		ThreadedCapture::new(VideoCapture::from_source(Failing)?, 0, DropPolicy::Block),
+		Err(Error { code: core::StsBadArg, .. })
+	);
+	Ok(())
+}
+
+#[test]
+#[cfg(feature = "async")]
+fn capture_stream() -> Result<()> {
+	use std::{
+		future::Future,
+		pin::Pin,
+		task::{Context, Poll},
+	};
+
+	use opencv::{core::Mat, videoio::FrameStream};
+
+	struct Next<'s>(&'s mut FrameStream);
+
+	impl Future for Next<'_> {
+		type Output = Option<Result<Mat>>;
+
+		fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+			futures_core::Stream::poll_next(Pin::new(&mut *self.0), cx)
+		}
+	}
+
+	let mut stream = VideoCapture::from_source(Counter { pos: 0, count: 3 })?.into_stream();
+	for i in 0..3 {
+		let frame = pollster::block_on(Next(&mut stream)).expect("Frame must be received")?;
+		assert_eq!(i, *frame.at_2d::<u8>(0, 0)?);
+	}
+	assert!(pollster::block_on(Next(&mut stream)).is_none());
+
+	let mut stream = VideoCapture::from_source(Failing)?.into_stream();
+	assert_matches!(
+		pollster::block_on(Next(&mut stream)),
+		Some(Err(Error { code: core::StsError, .. }))
+	);
+	assert!(pollster::block_on(Next(&mut stream)).is_none());
+
+	// dropping a started stream stops the reading thread
+	let mut stream = VideoCapture::from_source(Counter { pos: 0, count: 100 })?.into_stream();
+	pollster::block_on(Next(&mut stream)).expect("Frame must be received")?;
+	drop(stream);
+	Ok(())
+}
@@ -0,0 +1,80 @@
+#![cfg(ocvrs_has_module_videostab)]
+
+use opencv::{
+	core::{Mat, Point2f, Ptr, Scalar, Vector, _InputArray},
+	prelude::*,
+	videostab::{
+		ImageMotionEstimatorBase, ImageMotionEstimatorBaseImpl, MotionEstimatorBase, MotionEstimatorBaseImpl, MotionModel,
+	},
+	Result,
+};
+
+/// Estimates the translation as the mean displacement of the points
+struct MeanShift;
+
+impl MotionEstimatorBaseImpl for MeanShift {
+	fn estimate(&mut self, points0: &_InputArray, points1: &_InputArray, model: MotionModel) -> Result<Option<Mat>> {
+		let points0 = points0.get_mat(-1)?;
+		let points1 = points1.get_mat(-1)?;
+		if model != MotionModel::MM_TRANSLATION || points0.empty() {
+			return Ok(None);
+		}
+		let (points0, points1) = (points0.data_typed::<Point2f>()?, points1.data_typed::<Point2f>()?);
+		let shift = points0
+			.iter()
+			.zip(points1)
+			.fold(Point2f::default(), |acc, (p0, p1)| acc + (*p1 - *p0));
+		let mut motion = Mat::eye(3, 3, f32::typ())?.to_mat()?;
+		*motion.at_2d_mut::<f32>(0, 2)? = shift.x / points0.len() as f32;
+		*motion.at_2d_mut::<f32>(1, 2)? = shift.y / points0.len() as f32;
+		Ok(Some(motion))
+	}
+}
+
+#[test]
+fn motion_estimator_rust_impl() -> Result<()> {
+	let mut estimator = Ptr::<dyn MotionEstimatorBase>::from_rust(MeanShift, MotionModel::MM_TRANSLATION)?;
+	assert_eq!(MotionModel::MM_TRANSLATION, estimator.motion_model()?);
+	let points0 = Vector::<Point2f>::from_slice(&[Point2f::new(0., 0.), Point2f::new(10., 5.)]);
+	let points1 = Vector::<Point2f>::from_slice(&[Point2f::new(2., 1.), Point2f::new(14., 7.)]);
+	let mut ok = false;
+	let motion = estimator.estimate(&points0, &points1, &mut ok)?;
+	assert!(ok);
+	assert_eq!(3., *motion.at_2d::<f32>(0, 2)?);
+	assert_eq!(1.5, *motion.at_2d::<f32>(1, 2)?);
+
+	estimator.set_motion_model(MotionModel::MM_AFFINE)?;
+	let motion = estimator.estimate(&points0, &points1, &mut ok)?;
+	assert!(!ok);
+	assert_eq!(0., *motion.at_2d::<f32>(0, 2)?);
+	Ok(())
+}
+
+/// Reports the identity motion for the frames of the same size and fails otherwise
+struct StillCamera;
+
+impl ImageMotionEstimatorBaseImpl for StillCamera {
+	fn estimate(&mut self, frame0: &Mat, frame1: &Mat, _model: MotionModel) -> Result<Option<Mat>> {
+		if frame0.size()? != frame1.size()? {
+			return Ok(None);
+		}
+		Mat::eye(3, 3, f32::typ())?.to_mat().map(Some)
+	}
+}
+
+#[test]
+fn image_motion_estimator_rust_impl() -> Result<()> {
+	let mut estimator = Ptr::<dyn ImageMotionEstimatorBase>::from_rust(StillCamera, MotionModel::MM_RIGID)?;
+	assert_eq!(MotionModel::MM_RIGID, estimator.motion_model()?);
+	let frame0 = Mat::new_rows_cols_with_default(20, 30, u8::typ(), Scalar::all(0.))?;
+	let frame1 = Mat::new_rows_cols_with_default(20, 30, u8::typ(), Scalar::all(255.))?;
+	let mut ok = false;
+	let motion = estimator.estimate(&frame0, &frame1, &mut ok)?;
+	assert!(ok);
+	assert_eq!(1., *motion.at_2d::<f32>(1, 1)?);
+
+	let frame1 = Mat::new_rows_cols_with_default(10, 30, u8::typ(), Scalar::all(255.))?;
+	estimator.estimate(&frame0, &frame1, &mut ok)?;
+	assert!(!ok);
+	Ok(())
+}